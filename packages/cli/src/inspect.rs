@@ -0,0 +1,180 @@
+use owasm_vm::{compile, read_version_metadata, Error, VersionMetadata};
+use std::fmt;
+use wasmparser::{BinaryReaderError, ExternalKind, ImportSectionEntryType, Parser, Payload};
+
+/// The size, exports, imports and memory limits of a Wasm module.
+#[derive(Debug, Default, PartialEq)]
+pub struct ModuleInfo {
+    pub size: usize,
+    /// The name and kind of each export, such as `prepare (function)`.
+    pub exports: Vec<String>,
+    /// The full name and kind of each import, such as `env.get_ask_count (function)`.
+    pub imports: Vec<String>,
+    /// The initial and maximum number of pages of the memory, if the module has one.
+    pub memory: Option<(u64, Option<u64>)>,
+}
+
+/// What `owasm-cli inspect` reports about a script.
+#[derive(Debug, PartialEq)]
+pub struct Inspection {
+    pub module: ModuleInfo,
+    pub version: Option<VersionMetadata>,
+    /// The module after `compile` instruments it, or the error the chain rejects it with.
+    pub instrumented: Result<ModuleInfo, Error>,
+}
+
+/// Reads the given Wasm module, failing only if it cannot be parsed at all.
+pub fn read_module(code: &[u8]) -> Result<ModuleInfo, BinaryReaderError> {
+    let mut info = ModuleInfo { size: code.len(), ..Default::default() };
+    for payload in Parser::new(0).parse_all(code) {
+        match payload? {
+            Payload::ExportSection(reader) => {
+                for export in reader {
+                    let export = export?;
+                    info.exports.push(format!("{} ({})", export.field, kind_name(export.kind)));
+                }
+            }
+            Payload::ImportSection(reader) => {
+                for import in reader {
+                    let import = import?;
+                    let name = format!("{}.{}", import.module, import.field.unwrap_or_default());
+                    let kind = match import.ty {
+                        ImportSectionEntryType::Function(_) => "function",
+                        ImportSectionEntryType::Table(_) => "table",
+                        ImportSectionEntryType::Memory(_) => "memory",
+                        ImportSectionEntryType::Global(_) => "global",
+                        _ => "other",
+                    };
+                    info.imports.push(format!("{} ({})", name, kind));
+                }
+            }
+            Payload::MemorySection(reader) => {
+                for memory in reader {
+                    let memory = memory?;
+                    info.memory = Some((memory.initial, memory.maximum));
+                }
+            }
+            _ => (),
+        }
+    }
+    Ok(info)
+}
+
+fn kind_name(kind: ExternalKind) -> &'static str {
+    match kind {
+        ExternalKind::Function => "function",
+        ExternalKind::Table => "table",
+        ExternalKind::Memory => "memory",
+        ExternalKind::Global => "global",
+        _ => "other",
+    }
+}
+
+/// Inspects the given script and runs it through `compile` as the chain does on upload.
+pub fn inspect(code: &[u8]) -> Result<Inspection, BinaryReaderError> {
+    let module = read_module(code)?;
+    let version = read_version_metadata(code).ok().flatten();
+    let instrumented = compile(code)
+        .map(|compiled| read_module(&compiled).expect("compile produced an unreadable module"));
+    Ok(Inspection { module, version, instrumented })
+}
+
+fn write_memory(f: &mut fmt::Formatter<'_>, memory: Option<(u64, Option<u64>)>) -> fmt::Result {
+    match memory {
+        Some((initial, Some(maximum))) => {
+            writeln!(f, "{} pages initial, {} pages maximum", initial, maximum)
+        }
+        Some((initial, None)) => writeln!(f, "{} pages initial, no maximum", initial),
+        None => writeln!(f, "none"),
+    }
+}
+
+impl fmt::Display for Inspection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "size: {} bytes", self.module.size)?;
+        writeln!(f, "exports: {}", self.module.exports.join(", "))?;
+        writeln!(f, "imports: {}", self.module.imports.join(", "))?;
+        write!(f, "memory: ")?;
+        write_memory(f, self.module.memory)?;
+        if let Some(version) = &self.version {
+            writeln!(f, "version: kit {}, script {}", version.kit, version.script)?;
+        }
+        match &self.instrumented {
+            Ok(instrumented) => {
+                writeln!(f, "instrumented size: {} bytes", instrumented.size)?;
+                write!(f, "instrumented memory: ")?;
+                write_memory(f, instrumented.memory)?;
+                writeln!(f, "accepted: yes")
+            }
+            Err(err) => writeln!(f, "accepted: no ({})", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::process::Command;
+    use tempfile::NamedTempFile;
+
+    fn wat2wasm(wat: impl AsRef<[u8]>) -> Vec<u8> {
+        let mut input_file = NamedTempFile::new().unwrap();
+        let mut output_file = NamedTempFile::new().unwrap();
+        input_file.write_all(wat.as_ref()).unwrap();
+        Command::new("wat2wasm")
+            .args([input_file.path().to_str().unwrap(), "-o", output_file.path().to_str().unwrap()])
+            .output()
+            .unwrap();
+        let mut wasm = Vec::new();
+        output_file.read_to_end(&mut wasm).unwrap();
+        wasm
+    }
+
+    #[test]
+    fn test_inspect() {
+        let wasm = wat2wasm(
+            r#"(module
+                (import "env" "get_ask_count" (func (result i64)))
+                (func)
+                (memory (export "memory") 17)
+                (export "prepare" (func 1))
+                (export "execute" (func 1)))
+            "#,
+        );
+        let inspection = inspect(&wasm).unwrap();
+        assert_eq!(
+            inspection.module.exports,
+            vec!["memory (memory)", "prepare (function)", "execute (function)"]
+        );
+        assert_eq!(inspection.module.imports, vec!["env.get_ask_count (function)"]);
+        assert_eq!(inspection.module.memory, Some((17, None)));
+        assert_eq!(inspection.version, None);
+        let instrumented = inspection.instrumented.as_ref().unwrap();
+        assert_eq!(instrumented.memory, Some((17, Some(512))));
+        assert!(instrumented.size > wasm.len());
+
+        let report = inspection.to_string();
+        assert!(report.contains("memory: 17 pages initial, no maximum\n"));
+        assert!(report.contains("instrumented memory: 17 pages initial, 512 pages maximum\n"));
+        assert!(report.ends_with("accepted: yes\n"));
+    }
+
+    #[test]
+    fn test_inspect_rejected() {
+        let wasm = wat2wasm(
+            r#"(module
+                (import "env" "beeb" (func))
+                (func)
+                (memory 1)
+                (export "prepare" (func 1))
+                (export "execute" (func 1)))
+            "#,
+        );
+        let inspection = inspect(&wasm).unwrap();
+        assert_eq!(inspection.module.imports, vec!["env.beeb (function)"]);
+        assert_eq!(inspection.instrumented, Err(Error::InvalidImportsError));
+        assert!(inspection.to_string().ends_with("accepted: no (InvalidImportsError)\n"));
+        assert!(inspect(b"beeb").is_err());
+    }
+}
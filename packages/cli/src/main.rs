@@ -0,0 +1,32 @@
+use std::process::exit;
+
+mod inspect;
+
+static USAGE: &str = "usage: owasm-cli inspect <script.wasm>";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let path = match args.as_slice() {
+        [command, path] if command == "inspect" => path,
+        _ => {
+            eprintln!("{}", USAGE);
+            exit(2);
+        }
+    };
+    let code = std::fs::read(path).unwrap_or_else(|err| {
+        eprintln!("failed to read {}: {}", path, err);
+        exit(1);
+    });
+    match inspect::inspect(&code) {
+        Ok(inspection) => {
+            print!("{}", inspection);
+            if inspection.instrumented.is_err() {
+                exit(1);
+            }
+        }
+        Err(err) => {
+            eprintln!("{} is not a Wasm module: {}", path, err);
+            exit(1);
+        }
+    }
+}
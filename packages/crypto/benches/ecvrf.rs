@@ -0,0 +1,26 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use owasm_crypto::ecvrf::{ecvrf_proof_to_hash, ecvrf_prove, ecvrf_verify, SecretKey};
+
+// The key of RFC 8032 test vector 1, proving a 32-byte alpha as used by the oracle.
+const SECRET_KEY: [u8; 32] = [
+    0x9d, 0x61, 0xb1, 0x9d, 0xef, 0xfd, 0x5a, 0x60, 0xba, 0x84, 0x4a, 0xf4, 0x92, 0xec, 0x2c, 0xc4,
+    0x44, 0x49, 0xc5, 0x69, 0x7b, 0x32, 0x69, 0x19, 0x70, 0x3b, 0xac, 0x03, 0x1c, 0xae, 0x7f, 0x60,
+];
+const ALPHA: [u8; 32] = [0xab; 32];
+
+fn bench_ecvrf(c: &mut Criterion) {
+    let secret_key = SecretKey::from(SECRET_KEY);
+    let pubkey = secret_key.public_key();
+    let pi = ecvrf_prove(&secret_key, &ALPHA).unwrap();
+
+    c.bench_function("ecvrf_verify", |b| {
+        b.iter(|| ecvrf_verify(black_box(pubkey.as_bytes()), black_box(&pi), black_box(&ALPHA)))
+    });
+    c.bench_function("ecvrf_prove", |b| {
+        b.iter(|| ecvrf_prove(black_box(&secret_key), black_box(&ALPHA)))
+    });
+    c.bench_function("ecvrf_proof_to_hash", |b| b.iter(|| ecvrf_proof_to_hash(black_box(&pi))));
+}
+
+criterion_group!(benches, bench_ecvrf);
+criterion_main!(benches);
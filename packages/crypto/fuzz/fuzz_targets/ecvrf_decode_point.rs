@@ -0,0 +1,10 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use owasm_crypto::ecvrf::fuzzing::decode_point;
+
+fuzz_target!(|data: &[u8]| {
+    // Only canonical encodings may decode, so a decoded point must encode back to the input.
+    if let Ok(point) = decode_point(data) {
+        assert_eq!(&point.compress().as_bytes()[..], data);
+    }
+});
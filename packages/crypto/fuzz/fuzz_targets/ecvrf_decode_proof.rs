@@ -0,0 +1,12 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use owasm_crypto::ecvrf::fuzzing::ecvrf_decode_proof;
+
+fuzz_target!(|data: &[u8]| {
+    // A decoded proof must encode back to the input, so no two proofs share a meaning.
+    if let Ok((gamma, c, s)) = ecvrf_decode_proof(data) {
+        assert_eq!(&gamma.compress().as_bytes()[..], &data[..32]);
+        assert_eq!(&c.as_bytes()[..16], &data[32..48]);
+        assert_eq!(&s.as_bytes()[..], &data[48..]);
+    }
+});
@@ -0,0 +1,10 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use owasm_crypto::ecvrf::{ecvrf_verify_with_suite, Suite};
+
+fuzz_target!(|input: (bool, &[u8], &[u8], &[u8])| {
+    let (tai, y, pi, alpha) = input;
+    let suite = if tai { Suite::Edwards25519Sha512Tai } else { Suite::Edwards25519Sha512Ell2 };
+    // Any input must either verify, fail to verify or be rejected with an error, never panic.
+    let _ = ecvrf_verify_with_suite(suite, y, pi, alpha);
+});
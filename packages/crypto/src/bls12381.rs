@@ -0,0 +1,309 @@
+//! BLS signature verification over BLS12-381 as specified by the IETF BLS signature draft.
+//!
+//! Both variants are supported. `MinPk` uses 48-byte G1 public keys and 96-byte G2
+//! signatures, as in eth2 and drand's default network. `MinSig` swaps the groups, so it
+//! uses 96-byte G2 public keys and 48-byte G1 signatures. The domain separation tag is
+//! supplied by the caller, because it differs between schemes (e.g. `_NUL_` vs `_POP_`).
+use crate::error::{CryptoError, CryptoResult};
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::{
+    multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt,
+};
+use core::convert::TryInto;
+use sha2::Sha256;
+
+pub const G1_POINT_LEN: usize = 48;
+pub const G2_POINT_LEN: usize = 96;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    /// Public keys in G1 and signatures in G2.
+    MinPk,
+    /// Public keys in G2 and signatures in G1.
+    MinSig,
+}
+
+fn read_g1(bytes: &[u8], format_err: fn() -> CryptoError) -> CryptoResult<G1Affine> {
+    let bytes: &[u8; G1_POINT_LEN] = bytes.try_into().map_err(|_| format_err())?;
+    Option::from(G1Affine::from_compressed(bytes)).ok_or_else(CryptoError::invalid_point_on_curve)
+}
+
+fn read_g2(bytes: &[u8], format_err: fn() -> CryptoError) -> CryptoResult<G2Affine> {
+    let bytes: &[u8; G2_POINT_LEN] = bytes.try_into().map_err(|_| format_err())?;
+    Option::from(G2Affine::from_compressed(bytes)).ok_or_else(CryptoError::invalid_point_on_curve)
+}
+
+fn hash_to_g1(message: &[u8], dst: &[u8]) -> G1Affine {
+    <G1Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(message, dst).into()
+}
+
+fn hash_to_g2(message: &[u8], dst: &[u8]) -> G2Affine {
+    <G2Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(message, dst).into()
+}
+
+// The identity is rejected as a public key since it verifies the identity signature for
+// every message.
+fn read_pubkeys_g1(pubkeys: &[&[u8]]) -> CryptoResult<Vec<G1Affine>> {
+    pubkeys
+        .iter()
+        .map(|pubkey| {
+            let point = read_g1(pubkey, CryptoError::invalid_pubkey_format)?;
+            if bool::from(point.is_identity()) {
+                return Err(CryptoError::invalid_pubkey_format());
+            }
+            Ok(point)
+        })
+        .collect()
+}
+
+fn read_pubkeys_g2(pubkeys: &[&[u8]]) -> CryptoResult<Vec<G2Affine>> {
+    pubkeys
+        .iter()
+        .map(|pubkey| {
+            let point = read_g2(pubkey, CryptoError::invalid_pubkey_format)?;
+            if bool::from(point.is_identity()) {
+                return Err(CryptoError::invalid_pubkey_format());
+            }
+            Ok(point)
+        })
+        .collect()
+}
+
+fn is_pairing_identity(terms: &[(G1Affine, G2Affine)]) -> bool {
+    let prepared: Vec<(G1Affine, G2Prepared)> =
+        terms.iter().map(|(p, q)| (*p, G2Prepared::from(*q))).collect();
+    let refs: Vec<(&G1Affine, &G2Prepared)> = prepared.iter().map(|(p, q)| (p, q)).collect();
+    multi_miller_loop(&refs).final_exponentiation() == Gt::identity()
+}
+
+pub fn verify(
+    variant: Variant,
+    dst: &[u8],
+    message: &[u8],
+    signature: &[u8],
+    pubkey: &[u8],
+) -> CryptoResult<bool> {
+    aggregate_verify(variant, dst, &[message], signature, &[pubkey])
+}
+
+/// Verifies an aggregate signature where `pubkeys[i]` signed `messages[i]`. The messages
+/// must be distinct, as the basic scheme requires to rule out rogue key attacks. To verify
+/// many signatures over the same message with proof-of-possession keys, aggregate the
+/// public keys with `aggregate_pubkeys` and call `verify` instead.
+pub fn aggregate_verify(
+    variant: Variant,
+    dst: &[u8],
+    messages: &[&[u8]],
+    signature: &[u8],
+    pubkeys: &[&[u8]],
+) -> CryptoResult<bool> {
+    if messages.is_empty() || messages.len() != pubkeys.len() {
+        return Err(CryptoError::generic_err("Mismatched number of messages and public keys"));
+    }
+    if messages.iter().collect::<BTreeSet<_>>().len() != messages.len() {
+        return Err(CryptoError::generic_err("Duplicate messages in aggregate signature"));
+    }
+
+    // Checks e(pk_1, H(m_1)) * ... * e(pk_n, H(m_n)) * e(-g, sig) == 1, with the
+    // pairing arguments swapped for MinSig.
+    let terms = match variant {
+        Variant::MinPk => {
+            let signature = read_g2(signature, CryptoError::invalid_signature_format)?;
+            let mut terms: Vec<(G1Affine, G2Affine)> = read_pubkeys_g1(pubkeys)?
+                .into_iter()
+                .zip(messages)
+                .map(|(pubkey, message)| (pubkey, hash_to_g2(message, dst)))
+                .collect();
+            terms.push((-G1Affine::generator(), signature));
+            terms
+        }
+        Variant::MinSig => {
+            let signature = read_g1(signature, CryptoError::invalid_signature_format)?;
+            let mut terms: Vec<(G1Affine, G2Affine)> = read_pubkeys_g2(pubkeys)?
+                .into_iter()
+                .zip(messages)
+                .map(|(pubkey, message)| (hash_to_g1(message, dst), pubkey))
+                .collect();
+            terms.push((signature, -G2Affine::generator()));
+            terms
+        }
+    };
+
+    Ok(is_pairing_identity(&terms))
+}
+
+/// Sums the given public keys into a single compressed public key.
+///
+/// This is only safe for keys whose proof of possession has been checked, as in the
+/// `_POP_` scheme. Otherwise a rogue key chosen as a function of the others lets its owner
+/// forge a signature that verifies for the whole set.
+pub fn aggregate_pubkeys(variant: Variant, pubkeys: &[&[u8]]) -> CryptoResult<Vec<u8>> {
+    if pubkeys.is_empty() {
+        return Err(CryptoError::generic_err("No public keys to aggregate"));
+    }
+
+    Ok(match variant {
+        Variant::MinPk => {
+            let sum = read_pubkeys_g1(pubkeys)?
+                .iter()
+                .fold(G1Projective::identity(), |acc, pubkey| acc + pubkey);
+            G1Affine::from(sum).to_compressed().to_vec()
+        }
+        Variant::MinSig => {
+            let sum = read_pubkeys_g2(pubkeys)?
+                .iter()
+                .fold(G2Projective::identity(), |acc, pubkey| acc + pubkey);
+            G2Affine::from(sum).to_compressed().to_vec()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bls12_381::Scalar;
+
+    const DST_MIN_PK: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+    const DST_MIN_SIG: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+
+    // Returns (pubkey, signature) for the given secret key and message.
+    fn sign(variant: Variant, secret: u64, message: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let sk = Scalar::from(secret);
+        match variant {
+            Variant::MinPk => (
+                G1Affine::from(G1Affine::generator() * sk).to_compressed().to_vec(),
+                G2Affine::from(hash_to_g2(message, DST_MIN_PK) * sk).to_compressed().to_vec(),
+            ),
+            Variant::MinSig => (
+                G2Affine::from(G2Affine::generator() * sk).to_compressed().to_vec(),
+                G1Affine::from(hash_to_g1(message, DST_MIN_SIG) * sk).to_compressed().to_vec(),
+            ),
+        }
+    }
+
+    fn dst(variant: Variant) -> &'static [u8] {
+        match variant {
+            Variant::MinPk => DST_MIN_PK,
+            Variant::MinSig => DST_MIN_SIG,
+        }
+    }
+
+    fn add_signatures(variant: Variant, a: &[u8], b: &[u8]) -> Vec<u8> {
+        match variant {
+            Variant::MinPk => {
+                let a = read_g2(a, CryptoError::invalid_signature_format).unwrap();
+                let b = read_g2(b, CryptoError::invalid_signature_format).unwrap();
+                G2Affine::from(G2Projective::from(a) + b).to_compressed().to_vec()
+            }
+            Variant::MinSig => {
+                let a = read_g1(a, CryptoError::invalid_signature_format).unwrap();
+                let b = read_g1(b, CryptoError::invalid_signature_format).unwrap();
+                G1Affine::from(G1Projective::from(a) + b).to_compressed().to_vec()
+            }
+        }
+    }
+
+    #[test]
+    fn verify_test() {
+        for variant in [Variant::MinPk, Variant::MinSig].iter().copied() {
+            let (pubkey, signature) = sign(variant, 42, b"band");
+            assert!(verify(variant, dst(variant), b"band", &signature, &pubkey).unwrap());
+            assert!(!verify(variant, dst(variant), b"other", &signature, &pubkey).unwrap());
+            assert!(!verify(variant, b"OTHER_DST", b"band", &signature, &pubkey).unwrap());
+
+            let (other_pubkey, _) = sign(variant, 43, b"band");
+            assert!(!verify(variant, dst(variant), b"band", &signature, &other_pubkey).unwrap());
+        }
+    }
+
+    #[test]
+    fn verify_fail_test() {
+        let (pubkey, signature) = sign(Variant::MinPk, 42, b"band");
+        match verify(Variant::MinPk, DST_MIN_PK, b"band", &signature, &pubkey[1..]).err().unwrap() {
+            CryptoError::InvalidPubkeyFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+        match verify(Variant::MinPk, DST_MIN_PK, b"band", &signature[1..], &pubkey).err().unwrap() {
+            CryptoError::InvalidSignatureFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+        // A MinPk key is a valid MinSig signature but has the wrong length for a MinSig key.
+        match verify(Variant::MinSig, DST_MIN_SIG, b"band", &pubkey, &pubkey).err().unwrap() {
+            CryptoError::InvalidPubkeyFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+
+        let mut not_on_curve = pubkey.clone();
+        not_on_curve[47] ^= 1;
+        match verify(Variant::MinPk, DST_MIN_PK, b"band", &signature, &not_on_curve).err().unwrap()
+        {
+            CryptoError::InvalidPointOnCurve { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+
+        let identity = G1Affine::identity().to_compressed();
+        match verify(Variant::MinPk, DST_MIN_PK, b"band", &signature, &identity).err().unwrap() {
+            CryptoError::InvalidPubkeyFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+
+    #[test]
+    fn aggregate_verify_test() {
+        for variant in [Variant::MinPk, Variant::MinSig].iter().copied() {
+            let (pubkey1, signature1) = sign(variant, 42, b"first");
+            let (pubkey2, signature2) = sign(variant, 43, b"second");
+            let signature = add_signatures(variant, &signature1, &signature2);
+            assert!(aggregate_verify(
+                variant,
+                dst(variant),
+                &[b"first", b"second"],
+                &signature,
+                &[&pubkey1, &pubkey2]
+            )
+            .unwrap());
+            assert!(!aggregate_verify(
+                variant,
+                dst(variant),
+                &[b"second", b"first"],
+                &signature,
+                &[&pubkey1, &pubkey2]
+            )
+            .unwrap());
+            assert!(aggregate_verify(variant, dst(variant), &[b"first"], &signature, &[]).is_err());
+
+            let (pubkey3, signature3) = sign(variant, 44, b"first");
+            let signature = add_signatures(variant, &signature1, &signature3);
+            match aggregate_verify(
+                variant,
+                dst(variant),
+                &[b"first", b"first"],
+                &signature,
+                &[&pubkey1, &pubkey3],
+            )
+            .err()
+            .unwrap()
+            {
+                CryptoError::GenericErr { .. } => {}
+                _ => panic!("wrong error type!"),
+            }
+        }
+    }
+
+    #[test]
+    fn aggregate_pubkeys_test() {
+        for variant in [Variant::MinPk, Variant::MinSig].iter().copied() {
+            let (pubkey1, signature1) = sign(variant, 42, b"band");
+            let (pubkey2, signature2) = sign(variant, 43, b"band");
+            let (expected, _) = sign(variant, 85, b"band");
+            let aggregated = aggregate_pubkeys(variant, &[&pubkey1, &pubkey2]).unwrap();
+            assert_eq!(aggregated, expected);
+
+            let signature = add_signatures(variant, &signature1, &signature2);
+            assert!(verify(variant, dst(variant), b"band", &signature, &aggregated).unwrap());
+            assert!(aggregate_pubkeys(variant, &[]).is_err());
+        }
+    }
+}
@@ -1,260 +1,490 @@
 use crate::error::{CryptoError, CryptoResult};
-use gmp::mpz::Mpz;
-use hex::decode;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::scalar::{clamp_integer, Scalar};
+use num_bigint::BigUint;
+use rand_core::{CryptoRng, RngCore};
 use sha2::{Digest, Sha512};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// The ECVRF cipher suites over edwards25519 with SHA-512. Both share the proof layout
+/// and nonce generation. ELL2 keeps the draft-09 challenge that existing on-chain proofs
+/// use, while TAI follows RFC 9381 and also binds the public key into the challenge.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Suite {
+    /// ECVRF-EDWARDS25519-SHA512-TAI, hashing to the curve by try-and-increment.
+    Edwards25519Sha512Tai,
+    /// ECVRF-EDWARDS25519-SHA512-ELL2, hashing to the curve with Elligator2.
+    #[default]
+    Edwards25519Sha512Ell2,
+}
+
+/// How a suite maps `(Y, alpha)` to the point H.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HashToCurve {
+    TryAndIncrement,
+    Elligator2,
+}
+
+impl Suite {
+    fn suite_string(self) -> u8 {
+        match self {
+            Suite::Edwards25519Sha512Tai => 0x03,
+            Suite::Edwards25519Sha512Ell2 => 0x04,
+        }
+    }
+
+    fn hash_to_curve(self) -> HashToCurve {
+        match self {
+            Suite::Edwards25519Sha512Tai => HashToCurve::TryAndIncrement,
+            Suite::Edwards25519Sha512Ell2 => HashToCurve::Elligator2,
+        }
+    }
+
+    // RFC 9381 adds the public key to the challenge. Draft-09 suites leave it out.
+    fn challenge_includes_pubkey(self) -> bool {
+        match self {
+            Suite::Edwards25519Sha512Tai => true,
+            Suite::Edwards25519Sha512Ell2 => false,
+        }
+    }
+}
 
 lazy_static! {
-    static ref SUITE_STRING: Vec<u8> = decode("04").unwrap();
-    static ref BITS: usize = 256;
-    static ref PRIME: Mpz =
+    static ref PRIME: BigUint =
         "57896044618658097711785492504343953926634992332820282019728792003956564819949"
-            .parse::<Mpz>()
-            .unwrap();
-    static ref ORDER: Mpz =
-        "7237005577332262213973186563042994240857116359379907606001950938285454250989"
-            .parse::<Mpz>()
+            .parse::<BigUint>()
             .unwrap();
-    static ref COFACTOR: Mpz = "8".parse::<Mpz>().unwrap();
-    static ref TWO_INV: Mpz =
-        "28948022309329048855892746252171976963317496166410141009864396001978282409975"
-            .parse::<Mpz>()
-            .unwrap();
-    static ref II: Mpz =
+    static ref II: BigUint =
         "19681161376707505956807079304988542015446066515923890162744021073123829784752"
-            .parse::<Mpz>()
-            .unwrap();
-    static ref A: Mpz = "486662".parse::<Mpz>().unwrap();
-    static ref D: Mpz =
-        "37095705934669439343138083508754565189542113879843219016388785533085940283555"
-            .parse::<Mpz>()
+            .parse::<BigUint>()
             .unwrap();
-    static ref SQRT_MINUS_A_PLUS_2: Mpz =
+    static ref A: BigUint = "486662".parse::<BigUint>().unwrap();
+    static ref SQRT_MINUS_A_PLUS_2: BigUint =
         "6853475219497561581579357271197624642482790079785650197046958215289687604742"
-            .parse::<Mpz>()
+            .parse::<BigUint>()
             .unwrap();
-    static ref BASE_X: Mpz =
-        "15112221349535400772501151409588531511454012693041857206046113283949847762202"
-            .parse::<Mpz>()
-            .unwrap();
-    static ref BASE_Y: Mpz =
-        "46316835694926478169428394003475163141307993866256225615783033603165251855960"
-            .parse::<Mpz>()
-            .unwrap();
-    static ref BASE: (Mpz, Mpz) = (
-        "15112221349535400772501151409588531511454012693041857206046113283949847762202"
-            .parse::<Mpz>()
-            .unwrap(),
-        "46316835694926478169428394003475163141307993866256225615783033603165251855960"
-            .parse::<Mpz>()
-            .unwrap()
-    );
 }
 
-fn x_recover(y: &Mpz) -> Mpz {
-    let xx = (y * y - 1) * inverse(&((&*D) * (y * y) + 1));
-    let mut x = Mpz::from(xx.powm(&((&*PRIME + Mpz::from(3u64)) >> 3), &*PRIME));
-    if (&x * &x - xx).modulus(&PRIME) != Mpz::zero() {
-        x = (&x * &*II).modulus(&PRIME);
+/// An ECVRF secret key, the 32-byte Ed25519 seed. The bytes are wiped on drop.
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SecretKey([u8; 32]);
+
+impl SecretKey {
+    pub fn from_slice(bytes: &[u8]) -> CryptoResult<Self> {
+        let bytes: &[u8; 32] =
+            bytes.try_into().map_err(|_| CryptoError::invalid_secret_key_format())?;
+        Ok(SecretKey(*bytes))
     }
-    if &x & Mpz::one() != Mpz::zero() {
-        &*PRIME - x
-    } else {
-        x
+
+    /// Returns a copy of the key bytes, which is wiped when dropped.
+    pub fn to_bytes(&self) -> Zeroizing<[u8; 32]> {
+        Zeroizing::new(self.0)
     }
-}
 
-fn is_on_curve(x: &Mpz, y: &Mpz) -> bool {
-    let x_2 = x * x;
-    let y_2 = y * y;
+    pub fn public_key(&self) -> PublicKey {
+        let (x, _) = self.expand();
+        PublicKey(encode_point(&(&*x * ED25519_BASEPOINT_TABLE)))
+    }
 
-    (&y_2 - &x_2 - 1 - x_2 * y_2 * (&*D)).modulus(&PRIME) == Mpz::zero()
+    // Returns the secret scalar and the nonce-generation prefix as specified in RFC 8032.
+    // Every intermediate copy of the hashed key is wiped before returning.
+    fn expand(&self) -> (Zeroizing<Scalar>, Zeroizing<[u8; 32]>) {
+        let mut hashed = Sha512::digest(&self.0);
+        let mut scalar_bytes = Zeroizing::new([0u8; 32]);
+        scalar_bytes.copy_from_slice(&hashed[..32]);
+        let mut prefix = Zeroizing::new([0u8; 32]);
+        prefix.copy_from_slice(&hashed[32..]);
+        hashed[..].zeroize();
+        (Zeroizing::new(Scalar::from_bytes_mod_order(clamp_integer(*scalar_bytes))), prefix)
+    }
 }
 
-fn encode_point(p: &(Mpz, Mpz)) -> Vec<u8> {
-    let mut q: Mpz = (&p.1 & ((Mpz::one() << 255) - 1)) + ((&p.0 & Mpz::one()) << 255);
-    let mut q_bytes_little: Vec<u8> = vec![0; 32];
-    for i in 0..32 {
-        q_bytes_little[i] = u8::from_str_radix(&(&q & Mpz::from(255)).to_string(), 10).unwrap();
-        q >>= 8;
-        if q < Mpz::one() {
-            break;
-        }
+impl From<[u8; 32]> for SecretKey {
+    fn from(bytes: [u8; 32]) -> Self {
+        SecretKey(bytes)
     }
-    q_bytes_little
 }
 
-fn parse_rev_bytes(bz: &[u8]) -> Mpz {
-    let mut rv = Vec::<u8>::from(bz);
-    rv.reverse();
-    Mpz::from(rv.as_slice())
-}
+/// An ECVRF public key, the compressed Edwards point `x * B`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublicKey([u8; 32]);
 
-fn decode_point(s: &[u8]) -> CryptoResult<(Mpz, Mpz)> {
-    if s.len() == 0 {
-        return Err(CryptoError::invalid_hash_format());
+impl PublicKey {
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
     }
-    let y = parse_rev_bytes(s) & ((Mpz::from(1u64) << 255) - 1);
-    let mut x = x_recover(&y);
 
-    if &x & Mpz::one() != Mpz::from(((s.last().unwrap() >> 7u8) & 1) as u32) {
-        x = &*PRIME - x;
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0
     }
-    if is_on_curve(&x, &y) {
-        Ok((x, y))
-    } else {
-        Err(CryptoError::invalid_point_on_curve())
+}
+
+impl From<[u8; 32]> for PublicKey {
+    fn from(bytes: [u8; 32]) -> Self {
+        PublicKey(bytes)
     }
 }
 
-fn inverse(a: &Mpz) -> Mpz {
-    a.invert(&*PRIME).unwrap_or(Mpz::one())
+/// A secret key together with its derived public key.
+#[derive(Clone)]
+pub struct Keypair {
+    pub secret: SecretKey,
+    pub public: PublicKey,
 }
 
-fn edwards_add(a: &(Mpz, Mpz), b: &(Mpz, Mpz)) -> (Mpz, Mpz) {
-    let x1_y2 = &a.0 * &b.1;
-    let x2_y1 = &a.1 * &b.0;
-    let all = &*D * &x1_y2 * &x2_y1;
-    let x3 = (x1_y2 + x2_y1) * inverse(&(1 + &all));
-    let y3 = ((&a.0 * &b.0) + (&a.1 * &b.1)) * inverse(&(1 - &all));
-    (x3.modulus(&PRIME), y3.modulus(&PRIME))
+impl From<SecretKey> for Keypair {
+    fn from(secret: SecretKey) -> Self {
+        let public = secret.public_key();
+        Keypair { secret, public }
+    }
+}
+
+/// Generates a fresh keypair from a cryptographically secure random number generator.
+pub fn keygen<R: RngCore + CryptoRng>(rng: &mut R) -> Keypair {
+    let mut bytes = Zeroizing::new([0u8; 32]);
+    rng.fill_bytes(&mut *bytes);
+    Keypair::from(SecretKey(*bytes))
+}
+
+fn inverse(a: &BigUint) -> BigUint {
+    a.modpow(&(&*PRIME - 2u32), &PRIME)
 }
 
-fn scalar_multiply(p: &(Mpz, Mpz), scalar: &Mpz) -> (Mpz, Mpz) {
-    if *scalar == Mpz::zero() {
-        return (Mpz::zero(), Mpz::one());
+fn negate(a: &BigUint) -> BigUint {
+    (&*PRIME - a % &*PRIME) % &*PRIME
+}
+
+fn sqrt(a: &BigUint) -> Option<BigUint> {
+    let a = a % &*PRIME;
+    let mut r = a.modpow(&((&*PRIME + 3u32) >> 3), &PRIME);
+    if (&r * &r) % &*PRIME != a {
+        r = (r * &*II) % &*PRIME;
     }
+    if (&r * &r) % &*PRIME == a {
+        Some(r)
+    } else {
+        None
+    }
+}
 
-    let mut q = p.clone();
-    for i in scalar.to_str_radix(2)[1..].chars() {
-        q = edwards_add(&q, &q);
-        if i == '1' {
-            q = edwards_add(&q, &p);
-        }
+fn encode_point(p: &EdwardsPoint) -> [u8; 32] {
+    p.compress().to_bytes()
+}
+
+fn decode_point(s: &[u8]) -> CryptoResult<EdwardsPoint> {
+    if s.is_empty() {
+        return Err(CryptoError::invalid_hash_format());
+    }
+    let point = CompressedEdwardsY::from_slice(s)
+        .ok()
+        .and_then(|compressed| compressed.decompress())
+        .ok_or_else(CryptoError::invalid_point_on_curve)?;
+    // Decompression reduces y mod p and ignores the sign bit when x = 0, so several
+    // encodings can map to the same point. Only the one RFC 8032 produces is accepted.
+    if point.compress().as_bytes()[..] != *s {
+        return Err(CryptoError::non_canonical_point());
     }
-    q
+    Ok(point)
 }
 
-fn ecvrf_decode_proof(pi: &[u8]) -> CryptoResult<((Mpz, Mpz), Mpz, Mpz)> {
+fn ecvrf_decode_proof(pi: &[u8]) -> CryptoResult<(EdwardsPoint, Scalar, Scalar)> {
+    if pi.len() != 80 {
+        return Err(CryptoError::invalid_proof_format());
+    }
     let gamma = decode_point(&pi[0..32])?;
-    let c = parse_rev_bytes(&pi[32..48]);
-    let s = parse_rev_bytes(&pi[48..]);
 
-    if s >= *ORDER {
-        return Err(CryptoError::generic_err("s is out of range"));
-    }
+    let mut c_bytes = [0u8; 32];
+    c_bytes[..16].copy_from_slice(&pi[32..48]);
+    let c = Scalar::from_bytes_mod_order(c_bytes);
+
+    let mut s_bytes = [0u8; 32];
+    s_bytes.copy_from_slice(&pi[48..80]);
+    let s = Option::<Scalar>::from(Scalar::from_canonical_bytes(s_bytes))
+        .ok_or_else(CryptoError::non_canonical_scalar)?;
 
     Ok((gamma, c, s))
 }
 
-fn expand_message_xmd(msg: &[u8]) -> Vec<u8> {
-    let dst_prime = vec![
-        69, 67, 86, 82, 70, 95, 101, 100, 119, 97, 114, 100, 115, 50, 53, 53, 49, 57, 95, 88, 77,
-        68, 58, 83, 72, 65, 45, 53, 49, 50, 95, 69, 76, 76, 50, 95, 78, 85, 95, 4, 40,
-    ];
-    let msg_prime = [&[0u8; 128], msg, &[0, 48], &[0], &dst_prime].concat();
-    Sha512::digest(&[Sha512::digest(&msg_prime).as_slice(), &[1u8], &dst_prime].concat()).to_vec()
+// "ECVRF_edwards25519_XMD:SHA-512_ELL2_NU_\x04" followed by its length.
+const DST_PRIME: [u8; 41] = [
+    69, 67, 86, 82, 70, 95, 101, 100, 119, 97, 114, 100, 115, 50, 53, 53, 49, 57, 95, 88, 77, 68,
+    58, 83, 72, 65, 45, 53, 49, 50, 95, 69, 76, 76, 50, 95, 78, 85, 95, 4, 40,
+];
+
+// expand_message_xmd for a 48-byte output, which a single SHA-512 block b_1 covers. The
+// message is fed in parts so that `y || alpha` is never copied into one buffer.
+fn expand_message_xmd(msg: &[&[u8]]) -> [u8; 64] {
+    let mut hasher = Sha512::new().chain(&[0u8; 128][..]);
+    for part in msg {
+        hasher.update(part);
+    }
+    let b_0 = hasher.chain(&[0u8, 48, 0][..]).chain(&DST_PRIME[..]).finalize();
+    let b_1 = Sha512::new().chain(b_0).chain(&[1u8][..]).chain(&DST_PRIME[..]).finalize();
+    let mut output = [0u8; 64];
+    output.copy_from_slice(&b_1);
+    output
 }
 
-fn hash_to_field(msg: &[u8]) -> Mpz {
-    Mpz::from(&expand_message_xmd(msg)[..48]).modulus(&PRIME)
+fn hash_to_field(msg: &[&[u8]]) -> BigUint {
+    BigUint::from_bytes_be(&expand_message_xmd(msg)[..48]) % &*PRIME
 }
 
 fn ecvrf_hash_to_curve_elligator2_25519(y: &[u8], alpha: &[u8]) -> CryptoResult<Vec<u8>> {
-    let u = hash_to_field(&[y, alpha].concat());
+    let u = hash_to_field(&[y, alpha]);
 
-    let mut tv1 = &u * &u;
-    // tv1 = modulus(&(&Mpz::from(2) * &tv1), &*PRIME);
-    tv1 = (Mpz::from(2u64) * tv1).modulus(&PRIME);
-    if tv1 == Mpz::from(&*PRIME - 1) {
-        tv1 = Mpz::zero();
+    let mut tv1 = (BigUint::from(2u32) * &u * &u) % &*PRIME;
+    if tv1 == &*PRIME - 1u32 {
+        tv1 = BigUint::from(0u32);
     }
 
-    let x1 = inverse(&(&tv1 + 1).modulus(&PRIME));
-    let x1 = ((-&*A) * &x1).modulus(&PRIME);
+    let x1 = (negate(&A) * inverse(&(&tv1 + 1u32))) % &*PRIME;
+    let gx1 = ((((&x1 + &*A) * &x1 + 1u32) % &*PRIME) * &x1) % &*PRIME;
+    let x2 = negate(&(&x1 + &*A));
+    let gx2 = (&tv1 * &gx1) % &*PRIME;
 
-    let gx1 = (&x1 + &*A).modulus(&PRIME);
-    let gx1 = (&gx1 * &x1).modulus(&PRIME);
-    let gx1 = (&gx1 + 1).modulus(&PRIME);
-    let gx1 = (&gx1 * &x1).modulus(&PRIME);
+    let e2 = gx1.modpow(&((&*PRIME - 1u32) >> 1), &PRIME) <= BigUint::from(1u32);
+    let (x, gx) = if e2 { (x1, gx1) } else { (x2, gx2) };
 
-    let x2 = (-&x1 - &*A).modulus(&PRIME);
+    // The Montgomery v-coordinate is odd exactly when gx1 is square.
+    let mut v = sqrt(&gx).ok_or_else(|| CryptoError::generic_err("gx is not square"))?;
+    if v.bit(0) != e2 {
+        v = negate(&v);
+    }
 
-    let gx2 = (&tv1 * &gx1).modulus(&PRIME);
+    // Map (u, v) to the birationally equivalent Edwards point
+    // (sqrt(-(A + 2)) * u / v, (u - 1) / (u + 1)), then clear the cofactor.
+    let edwards_x = ((&*SQRT_MINUS_A_PLUS_2 * &x) % &*PRIME * inverse(&v)) % &*PRIME;
+    let edwards_y = ((&x + &*PRIME - 1u32) * inverse(&(&x + 1u32))) % &*PRIME;
+    let mut h_prelim = [0u8; 32];
+    let edwards_y_bytes = edwards_y.to_bytes_le();
+    h_prelim[..edwards_y_bytes.len()].copy_from_slice(&edwards_y_bytes);
+    h_prelim[31] |= (edwards_x.bit(0) as u8) << 7;
 
-    let e2 = gx1.powm(&(Mpz::from(&*PRIME - 1) >> 1), &*PRIME) <= Mpz::from(1u64);
-    let (x, gx) = if e2 { (x1, gx1) } else { (x2, gx2) };
+    Ok(encode_point(&decode_point(&h_prelim)?.mul_by_cofactor()).to_vec())
+}
+
+fn ecvrf_hash_to_curve_try_and_increment(y: &[u8], alpha: &[u8]) -> CryptoResult<Vec<u8>> {
+    let suite_string = Suite::Edwards25519Sha512Tai.suite_string();
+    // The prefix is hashed once and its state reused for each counter value.
+    let prefix = Sha512::new().chain(&[suite_string, 1u8][..]).chain(y).chain(alpha);
+    for ctr in 0..=255u8 {
+        let hash_string = prefix.clone().chain(&[ctr, 0u8][..]).finalize();
+        if let Ok(h) = decode_point(&hash_string[..32]) {
+            return Ok(encode_point(&h.mul_by_cofactor()).to_vec());
+        }
+    }
+    Err(CryptoError::generic_err("no valid point found"))
+}
 
-    let edwards_y = (Mpz::from(&x - 1) * inverse(&(&x + 1))).modulus(&PRIME);
-    let edwards_y_rev: Vec<u8> = Vec::from(&edwards_y).into_iter().rev().collect();
-    let mut h_prelim = decode_point(edwards_y_rev.as_slice())?;
-    let y_coordinate = ((&*SQRT_MINUS_A_PLUS_2 * &x) * inverse(&h_prelim.0)).modulus(&PRIME);
+/// ECVRF proving and verification for one cipher suite. Everything that differs between
+/// suites comes from the `Suite`, so the proof logic below is shared by all of them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Ecvrf {
+    suite: Suite,
+}
 
-    if (&y_coordinate * &y_coordinate).modulus(&PRIME) != gx {
-        return Err(CryptoError::generic_err("xx"));
+impl Ecvrf {
+    pub fn new(suite: Suite) -> Self {
+        Ecvrf { suite }
     }
 
-    let e3 = u8::from_str_radix(&(&y_coordinate & Mpz::one()).to_string(), 10).unwrap() == 1u8;
-    if e2 ^ e3 {
-        h_prelim.0 = -h_prelim.0.modulus(&PRIME);
+    pub fn suite(&self) -> Suite {
+        self.suite
     }
 
-    Ok(encode_point(&scalar_multiply(&h_prelim, &*COFACTOR)))
-}
+    fn hash_to_curve(&self, y: &[u8], alpha: &[u8]) -> CryptoResult<Vec<u8>> {
+        match self.suite.hash_to_curve() {
+            HashToCurve::TryAndIncrement => ecvrf_hash_to_curve_try_and_increment(y, alpha),
+            HashToCurve::Elligator2 => ecvrf_hash_to_curve_elligator2_25519(y, alpha),
+        }
+    }
 
-fn ecvrf_hash_points(p1: &(Mpz, Mpz), p2: &(Mpz, Mpz), p3: &(Mpz, Mpz), p4: &(Mpz, Mpz)) -> Mpz {
-    let s_string = [
-        &SUITE_STRING[..],
-        &vec![2u8][..],
-        &encode_point(p1)[..],
-        &encode_point(p2)[..],
-        &encode_point(p3)[..],
-        &encode_point(p4)[..],
-        &vec![0u8][..],
-    ]
-    .concat();
+    fn hash_points(&self, points: &[&EdwardsPoint]) -> Scalar {
+        let mut hasher = Sha512::new().chain(&[self.suite.suite_string(), 2u8][..]);
+        for point in points {
+            hasher.update(encode_point(point));
+        }
+        let c_string = hasher.chain(&[0u8][..]).finalize();
+        let mut truncated_c_string = [0u8; 32];
+        truncated_c_string[..16].copy_from_slice(&c_string[..16]);
 
-    let c_string = Sha512::digest(&s_string);
-    let mut truncated_c_string: Vec<u8> = Vec::new();
-    truncated_c_string.extend(c_string[0..16].iter().rev());
+        Scalar::from_bytes_mod_order(truncated_c_string)
+    }
 
-    Mpz::from(truncated_c_string.as_slice())
-}
+    fn challenge(
+        &self,
+        y: &EdwardsPoint,
+        h: &EdwardsPoint,
+        gamma: &EdwardsPoint,
+        u: &EdwardsPoint,
+        v: &EdwardsPoint,
+    ) -> Scalar {
+        if self.suite.challenge_includes_pubkey() {
+            self.hash_points(&[y, h, gamma, u, v])
+        } else {
+            self.hash_points(&[h, gamma, u, v])
+        }
+    }
 
-pub fn ecvrf_verify(y: &[u8], pi: &[u8], alpha: &[u8]) -> CryptoResult<bool> {
-    if y.len() != 32 {
-        return Err(CryptoError::invalid_pubkey_format());
+    pub fn verify(&self, y: &[u8], pi: &[u8], alpha: &[u8]) -> CryptoResult<bool> {
+        if y.len() != 32 {
+            return Err(CryptoError::invalid_pubkey_format());
+        }
+
+        if pi.len() != 80 {
+            return Err(CryptoError::invalid_proof_format());
+        }
+
+        let (gamma, c, s) = ecvrf_decode_proof(pi)?;
+
+        let h = self.hash_to_curve(y, alpha)?;
+        let y_point = decode_point(y)?;
+
+        let h_point = decode_point(&h)?;
+
+        // U = s*B - c*Y and V = s*H - c*Gamma. The points are negated rather than the
+        // scalars so that small-order components are handled exactly like integer
+        // multiplication.
+        let u = EdwardsPoint::vartime_double_scalar_mul_basepoint(&c, &-y_point, &s);
+        let v = s * h_point + c * -gamma;
+
+        let cp = self.challenge(&y_point, &h_point, &gamma, &u, &v);
+
+        Ok(c == cp)
     }
 
-    if pi.len() != 80 {
-        return Err(CryptoError::invalid_proof_format());
+    /// Produces an 80-byte proof `pi` over `alpha` that `verify` accepts under the secret
+    /// key's public key.
+    pub fn prove(&self, secret_key: &SecretKey, alpha: &[u8]) -> CryptoResult<Vec<u8>> {
+        // Everything derived from the secret key goes through dalek's constant-time scalar
+        // arithmetic and fixed-window multiplications, and is wiped once the proof is built.
+        // Only public inputs (the public key and alpha) reach the variable-time big integer
+        // code in hash_to_curve.
+        let (x, prefix) = secret_key.expand();
+        let y = secret_key.public_key();
+
+        let h = self.hash_to_curve(y.as_bytes(), alpha)?;
+        let h_point = decode_point(&h)?;
+        let gamma = *x * h_point;
+
+        let mut k_hash = Sha512::new().chain(&prefix[..]).chain(&h).finalize();
+        let mut k_string = Zeroizing::new([0u8; 64]);
+        k_string.copy_from_slice(&k_hash);
+        k_hash[..].zeroize();
+        let k = Zeroizing::new(Scalar::from_bytes_mod_order_wide(&k_string));
+        let c = self.challenge(
+            &decode_point(y.as_bytes())?,
+            &h_point,
+            &gamma,
+            &(&*k * ED25519_BASEPOINT_TABLE),
+            &(*k * h_point),
+        );
+        let s = *k + c * *x;
+
+        Ok([&encode_point(&gamma)[..], &c.as_bytes()[..16], s.as_bytes()].concat())
     }
 
-    let (gamma, c, s) = ecvrf_decode_proof(pi)?;
+    /// Derives the 64-byte VRF output `beta` from a proof's Gamma point. This does not
+    /// verify the proof, so it should only be called on proofs already accepted by `verify`.
+    pub fn proof_to_hash(&self, pi: &[u8]) -> CryptoResult<[u8; 64]> {
+        let (gamma, _, _) = ecvrf_decode_proof(pi)?;
 
-    let h = ecvrf_hash_to_curve_elligator2_25519(y, alpha)?;
-    let y_point = decode_point(y)?;
+        let beta_string = Sha512::new()
+            .chain(&[self.suite.suite_string(), 3u8][..])
+            .chain(encode_point(&gamma.mul_by_cofactor()))
+            .chain(&[0u8][..])
+            .finalize();
+        let mut beta = [0u8; 64];
+        beta.copy_from_slice(&beta_string);
 
-    let h_point = decode_point(&h)?;
+        Ok(beta)
+    }
+}
 
-    let s_b = scalar_multiply(&*BASE, &s);
-    let c_y = scalar_multiply(&y_point, &c);
-    let nc_y = (&*PRIME - c_y.0, c_y.1);
-    let u = edwards_add(&s_b, &nc_y);
+pub fn ecvrf_verify(y: &[u8], pi: &[u8], alpha: &[u8]) -> CryptoResult<bool> {
+    Ecvrf::default().verify(y, pi, alpha)
+}
 
-    let s_h = scalar_multiply(&h_point, &s);
-    let c_g = scalar_multiply(&gamma, &c);
-    let nc_g = (&*PRIME - c_g.0, c_g.1);
-    let v = edwards_add(&nc_g, &s_h);
+pub fn ecvrf_verify_with_suite(
+    suite: Suite,
+    y: &[u8],
+    pi: &[u8],
+    alpha: &[u8],
+) -> CryptoResult<bool> {
+    Ecvrf::new(suite).verify(y, pi, alpha)
+}
 
-    let cp = ecvrf_hash_points(&h_point, &gamma, &u, &v);
+/// Produces an 80-byte proof `pi` over `alpha` that `ecvrf_verify` accepts under the
+/// secret key's public key.
+pub fn ecvrf_prove(secret_key: &SecretKey, alpha: &[u8]) -> CryptoResult<Vec<u8>> {
+    Ecvrf::default().prove(secret_key, alpha)
+}
 
-    Ok(c == cp)
+pub fn ecvrf_prove_with_suite(
+    suite: Suite,
+    secret_key: &SecretKey,
+    alpha: &[u8],
+) -> CryptoResult<Vec<u8>> {
+    Ecvrf::new(suite).prove(secret_key, alpha)
+}
+
+/// Derives the 64-byte VRF output `beta` from a proof's Gamma point. This does not verify
+/// the proof, so it should only be called on proofs already accepted by `ecvrf_verify`.
+pub fn ecvrf_proof_to_hash(pi: &[u8]) -> CryptoResult<[u8; 64]> {
+    Ecvrf::default().proof_to_hash(pi)
+}
+
+pub fn ecvrf_proof_to_hash_with_suite(suite: Suite, pi: &[u8]) -> CryptoResult<[u8; 64]> {
+    Ecvrf::new(suite).proof_to_hash(pi)
+}
+
+/// Maps a VRF output to a uniform integer in `[0, range)`, or returns None if `range` is 0
+/// as the range is then empty. The whole of `beta` is reduced as one big-endian integer,
+/// so for a 64-byte output the bias from the modulo is below 2^-448.
+pub fn rand_from_vrf(beta: &[u8], range: u64) -> Option<u64> {
+    if range == 0 {
+        return None;
+    }
+    let range = range as u128;
+    Some(beta.iter().fold(0u128, |acc, byte| ((acc << 8) | *byte as u128) % range) as u64)
+}
+
+/// Expands a VRF output into `n` pseudorandom bytes as SHA-512(beta || counter) blocks,
+/// for scripts that need more randomness than a single output provides.
+pub fn rand_bytes_from_vrf(beta: &[u8], n: usize) -> Vec<u8> {
+    let mut output = Vec::with_capacity(n);
+    let mut counter = 0u64;
+    while output.len() < n {
+        let block = Sha512::new().chain(beta).chain(counter.to_be_bytes()).finalize();
+        let take = core::cmp::min(block.len(), n - output.len());
+        output.extend_from_slice(&block[..take]);
+        counter += 1;
+    }
+    output
+}
+
+/// Exposes the proof parsing internals to the fuzz targets in `fuzz/`.
+#[cfg(fuzzing)]
+pub mod fuzzing {
+    use super::*;
+
+    pub fn decode_point(s: &[u8]) -> CryptoResult<EdwardsPoint> {
+        super::decode_point(s)
+    }
+
+    pub fn ecvrf_decode_proof(pi: &[u8]) -> CryptoResult<(EdwardsPoint, Scalar, Scalar)> {
+        super::ecvrf_decode_proof(pi)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use hex::encode;
+    use curve25519_dalek::traits::Identity;
+    use hex::{decode, encode};
 
     #[test]
     fn hash_test() {
@@ -267,92 +497,31 @@ mod tests {
     }
 
     #[test]
-    fn is_on_curve_test() {
-        assert_eq!(is_on_curve(&Mpz::from(0u64), &Mpz::from(1u64)), true);
-        assert_eq!(
-            is_on_curve(
-                &"2467584584982761739087903239975580076073426676744013905948960903141708961180"
-                    .parse::<Mpz>()
-                    .unwrap(),
-                &"4882184778386801025813782108981700325881234329435150280746293678017607916296"
-                    .parse::<Mpz>()
-                    .unwrap()
-            ),
-            true
-        );
-        assert_eq!(
-            is_on_curve(
-                &"2467584584982761739087903239975580076073426676744013905948960903141708961180"
-                    .parse::<Mpz>()
-                    .unwrap(),
-                &"4882184778386801025813782108981700325881234329435150280746293678017607916295"
-                    .parse::<Mpz>()
-                    .unwrap()
-            ),
-            false
-        );
-        assert_eq!(
-            is_on_curve(
-                &"2467584584982761739087903239975580076073426676744013905948960903141708961181"
-                    .parse::<Mpz>()
-                    .unwrap(),
-                &"4882184778386801025813782108981700325881234329435150280746293678017607916296"
-                    .parse::<Mpz>()
-                    .unwrap()
-            ),
-            false
-        );
-    }
-
-    #[test]
-    fn x_recover_test() {
-        assert_eq!(x_recover(&"1".parse::<Mpz>().unwrap()), "0".parse::<Mpz>().unwrap());
-        assert_eq!(
-            x_recover(&"1000000".parse::<Mpz>().unwrap()),
-            "42264365937216995767569786311423113212193185317045903349677162665330205787882"
-                .parse::<Mpz>()
-                .unwrap()
-        );
-        assert_eq!(
-            x_recover(
-                &"5490344842503262896049970157107921391700051501439740859138324399589050432176"
-                    .parse::<Mpz>()
-                    .unwrap()
-            ),
-            "40693201237000043021686838142473729874979326212385650705970612165939555930168"
-                .parse::<Mpz>()
-                .unwrap()
-        );
+    fn sqrt_test() {
+        for a in ["4", "1000000", "3185713857305035135"] {
+            let a = a.parse::<BigUint>().unwrap();
+            let r = sqrt(&(&a * &a)).unwrap();
+            assert!(r == a || r == negate(&a));
+        }
+        // 2 is not a square modulo 2^255 - 19.
+        assert_eq!(sqrt(&BigUint::from(2u32)), None);
+        assert_eq!((&*II * &*II) % &*PRIME, &*PRIME - 1u32);
     }
 
     #[test]
     fn encode_point_test() {
         assert_eq!(
-            encode_point(&(Mpz::from(0), Mpz::from(1))),
+            encode_point(&EdwardsPoint::identity()).to_vec(),
             decode("0100000000000000000000000000000000000000000000000000000000000000").unwrap()
         );
-        assert_eq!(
-            encode_point(&(
-                "5490344842503262896049970157107921391700051501439740859138324399589050432176"
-                    .parse::<Mpz>()
-                    .unwrap(),
-                "6892623829087957149769104661949562962747386908121354426791544695725788966110"
-                    .parse::<Mpz>()
-                    .unwrap()
-            )),
-            decode("de2c8f6440ccc8b39a44cbb881a0c8ba2be8082f641e285e049c24033b163d0f").unwrap()
-        );
-        assert_eq!(
-            encode_point(&(
-                "11765910627670138205555954470128887569457785139558335884609577674421928602465"
-                    .parse::<Mpz>()
-                    .unwrap(),
-                "18209892540234382838474494422429649302902580183111935078055540371838462697257"
-                    .parse::<Mpz>()
-                    .unwrap()
-            )),
-            decode("299f6d20010556799ff82f2ad721bd15732f7533cfc6ad8bf333cd22166f42a8").unwrap()
-        );
+        for hex in [
+            "7b0f068bdde1d396d95b97579ed07cc9cabc5af128b7fa3338f7aca485dc170b",
+            "299f6d20010556799ff82f2ad721bd15732f7533cfc6ad8bf333cd22166f42a8",
+            "085b44f4058db2c0bb9609ee13b414aa6b9e85cbcffa956a83397d16fa37cb0a",
+        ] {
+            let bytes = decode(hex).unwrap();
+            assert_eq!(encode_point(&decode_point(&bytes).unwrap()).to_vec(), bytes);
+        }
     }
 
     #[test]
@@ -363,199 +532,185 @@ mod tests {
                     .unwrap()
             )
             .unwrap(),
-            ("0".parse::<Mpz>().unwrap(), "1".parse::<Mpz>().unwrap())
+            EdwardsPoint::identity()
+        );
+        assert_eq!(decode_point(&decode("").unwrap()), Err(CryptoError::invalid_hash_format()));
+        assert_eq!(
+            decode_point(&decode("11").unwrap()),
+            Err(CryptoError::invalid_point_on_curve())
         );
+        // y = 2 has no corresponding x on the curve.
         assert_eq!(
             decode_point(
-                &decode("7b0f068bdde1d396d95b97579ed07cc9cabc5af128b7fa3338f7aca485dc170b")
-                    .unwrap()
-            )
-            .unwrap(),
-            (
-                "18738815168440011986408904409747661355966848393371742103586138146960616269896"
-                    .parse::<Mpz>()
-                    .unwrap(),
-                "5017600804117403562852659704574511322216896914205922652106168593697487589243"
-                    .parse::<Mpz>()
+                &decode("0200000000000000000000000000000000000000000000000000000000000000")
                     .unwrap()
-            )
+            ),
+            Err(CryptoError::invalid_point_on_curve())
         );
+        // y = p is decompressed as y = 0.
         assert_eq!(
             decode_point(
-                &decode("299f6d20010556799ff82f2ad721bd15732f7533cfc6ad8bf333cd22166f42a8")
+                &decode("edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f")
                     .unwrap()
-            )
-            .unwrap(),
-            (
-                "11765910627670138205555954470128887569457785139558335884609577674421928602465"
-                    .parse::<Mpz>()
-                    .unwrap(),
-                "18209892540234382838474494422429649302902580183111935078055540371838462697257"
-                    .parse::<Mpz>()
-                    .unwrap()
-            )
+            ),
+            Err(CryptoError::non_canonical_point())
         );
-        assert_eq!(decode_point(&decode("").unwrap()), Err(CryptoError::invalid_hash_format()));
+        // The identity with its sign bit set.
         assert_eq!(
-            decode_point(&decode("11").unwrap()),
-            Err(CryptoError::invalid_point_on_curve())
+            decode_point(
+                &decode("0100000000000000000000000000000000000000000000000000000000000080")
+                    .unwrap()
+            ),
+            Err(CryptoError::non_canonical_point())
         );
     }
 
     #[test]
     fn ecvrf_decode_proof_test() {
+        let (gamma, c, s) = ecvrf_decode_proof(
+            &decode("a80954531c41b09280438b805fb8264e20791a0fd011a18f6def7b9cc48315c9f4b41e93d8f4140c1ffc917c67640a45c66e7ce47d754462ab40aa0cce09c11b0234c0a8ba265e5fd27ed1d67bc4a701")
+                .unwrap()
+        )
+        .unwrap();
         assert_eq!(
-            ecvrf_decode_proof(
-                &decode("a80954531c41b09280438b805fb8264e20791a0fd011a18f6def7b9cc48315c9f4b41e93d8f4140c1ffc917c67640a45c66e7ce47d754462ab40aa0cce09c11b0234c0a8ba265e5fd27ed1d67bc4a701")
+            encode(encode_point(&gamma)),
+            "a80954531c41b09280438b805fb8264e20791a0fd011a18f6def7b9cc48315c9"
+        );
+        assert_eq!(c, Scalar::from(91770691117758273713681408009594385652u128));
+        assert_eq!(
+            s,
+            // 748732389381679406359389955750217672883708317852412390845739987821316042438
+            Scalar::from_bytes_mod_order(
+                decode("c66e7ce47d754462ab40aa0cce09c11b0234c0a8ba265e5fd27ed1d67bc4a701")
+                    .unwrap()
+                    .try_into()
                     .unwrap()
             )
-            .unwrap(),
-            (
-                (
-                    "27697607651988823115975462172016124959043654960543528824774351294042131512091"
-                        .parse::<Mpz>()
-                        .unwrap(),
-                    "33056851164339470258906459114062521442851091569965281854821725995490451130792"
-                        .parse::<Mpz>()
-                        .unwrap()
-                ),
-                "91770691117758273713681408009594385652"
-                .parse::<Mpz>()
-                .unwrap(),
-                "748732389381679406359389955750217672883708317852412390845739987821316042438"
-                .parse::<Mpz>()
+        );
+
+        let (gamma, c, s) = ecvrf_decode_proof(
+            &decode("9061d3a7c68c64efecda0463eb2163ef7793d7049785510b07e3c381f2bbdd62e11d9b22504c906a80b74cff39ccf52389c1cc3b9fc5c7c3a5a716cbac23541a8267a18750ca7f1f26b9ef4dcb226a0f")
                 .unwrap()
-            )
+        )
+        .unwrap();
+        assert_eq!(
+            encode(encode_point(&gamma)),
+            "9061d3a7c68c64efecda0463eb2163ef7793d7049785510b07e3c381f2bbdd62"
         );
+        assert_eq!(c, Scalar::from(47799234789388919003118978975460433377u128));
         assert_eq!(
-            ecvrf_decode_proof(
-                &decode("9061d3a7c68c64efecda0463eb2163ef7793d7049785510b07e3c381f2bbdd62e11d9b22504c906a80b74cff39ccf52389c1cc3b9fc5c7c3a5a716cbac23541a8267a18750ca7f1f26b9ef4dcb226a0f")
+            s,
+            // 6972218658068131753903599998180446075911404073082034377012199676502466150793
+            Scalar::from_bytes_mod_order(
+                decode("89c1cc3b9fc5c7c3a5a716cbac23541a8267a18750ca7f1f26b9ef4dcb226a0f")
+                    .unwrap()
+                    .try_into()
                     .unwrap()
-            )
-            .unwrap(),
-            (
-                (
-                    "20145088686991237763563330138422416133011020304089967570913862140895427216188"
-                        .parse::<Mpz>()
-                        .unwrap(),
-                    "44718429527015941074873329327942383821260886483403263181075854270961588330896"
-                        .parse::<Mpz>()
-                        .unwrap()
-                ),
-                "47799234789388919003118978975460433377"
-                .parse::<Mpz>()
-                .unwrap(),
-                "6972218658068131753903599998180446075911404073082034377012199676502466150793"
-                .parse::<Mpz>()
-                .unwrap()
             )
         );
     }
 
     #[test]
     fn ecvrf_decode_proof_fail_s_out_of_range_test() {
-        assert!(ecvrf_decode_proof(
-            &decode("a80954531c41b09280438b805fb8264e20791a0fd011a18f6def7b9cc48315c9f4b41e93d8f4140c1ffc917c67640a45c66e7ce47d754462ab40aa0cce09c11b0234c0a8ba265e5fd27ed1d67bc4a7ff")
-                .unwrap()
-        ).is_err());
+        assert_eq!(
+            ecvrf_decode_proof(
+                &decode("a80954531c41b09280438b805fb8264e20791a0fd011a18f6def7b9cc48315c9f4b41e93d8f4140c1ffc917c67640a45c66e7ce47d754462ab40aa0cce09c11b0234c0a8ba265e5fd27ed1d67bc4a7ff")
+                    .unwrap()
+            ),
+            Err(CryptoError::non_canonical_scalar())
+        );
+    }
+
+    #[test]
+    fn ecvrf_verify_rejects_malleated_proof_test() {
+        let y = decode("d4e03360381b0b07bb005090a389de57542e01a3e33fea4340ddcd5059016670").unwrap();
+        let pi = decode("a80954531c41b09280438b805fb8264e20791a0fd011a18f6def7b9cc48315c9f4b41e93d8f4140c1ffc917c67640a45c66e7ce47d754462ab40aa0cce09c11b0234c0a8ba265e5fd27ed1d67bc4a701").unwrap();
+        let alpha = decode("c3f2b31660de8bc95902b9103262cdb941f77376f5d3dbb7a3d5a387797f").unwrap();
+        assert_eq!(ecvrf_verify(&y, &pi, &alpha), Ok(true));
+
+        // s + L is congruent to s but is not the canonical encoding.
+        let l = decode("edd3f55c1a631258d69cf7a2def9de1400000000000000000000000000000010").unwrap();
+        let mut malleated = pi.clone();
+        let mut carry = 0u16;
+        for i in 0..32 {
+            let sum = malleated[48 + i] as u16 + l[i] as u16 + carry;
+            malleated[48 + i] = sum as u8;
+            carry = sum >> 8;
+        }
+        assert_eq!(carry, 0);
+        assert_eq!(ecvrf_verify(&y, &malleated, &alpha), Err(CryptoError::non_canonical_scalar()));
+
+        // Setting the sign bit of a point with x = 0 leaves it unchanged on decompression.
+        let mut malleated = pi;
+        malleated[..32].copy_from_slice(
+            &decode("0100000000000000000000000000000000000000000000000000000000000080").unwrap(),
+        );
+        assert_eq!(ecvrf_verify(&y, &malleated, &alpha), Err(CryptoError::non_canonical_point()));
     }
 
     #[test]
     fn ecvrf_hash_points_test() {
+        let point = |hex: &str| decode_point(&decode(hex).unwrap()).unwrap();
         assert_eq!(
-            ecvrf_hash_points(
-                &(Mpz::from(1), Mpz::from(2)),
-                &(Mpz::from(3), Mpz::from(4)),
-                &(Mpz::from(5), Mpz::from(6)),
-                &(Mpz::from(7), Mpz::from(8)),
-            ),
-            "161209729549110407160776210096078431864".parse::<Mpz>().unwrap()
-        );
-        assert_eq!(
-            ecvrf_hash_points(
-                &(
-                    "20145088686991237763563330138422416133011020304089967570913862140895427216188"
-                        .parse::<Mpz>()
-                        .unwrap(),
-                    "44718429527015941074873329327942383821260886483403263181075854270961588330896"
-                        .parse::<Mpz>()
-                        .unwrap()
-                ),
-                &(
-                    "5313863158657921736192767953913786084044359767756713289178739762614964543209"
-                        .parse::<Mpz>()
-                        .unwrap(),
-                    "50988912630131679334181337403790444623412258884662970567487510258466540553771"
-                        .parse::<Mpz>()
-                        .unwrap()
-                ),
-                &(
-                    "7107631960465767869535429853349295352031173980104103285621849487667722533297"
-                        .parse::<Mpz>()
-                        .unwrap(),
-                    "42878079319476036336137946623896330600009697504370825498274853352471200872065"
-                        .parse::<Mpz>()
-                        .unwrap()
-                ),
-                &(
-                    "12156183745850511073089323218562745643254017618359848732866684019020326374996"
-                        .parse::<Mpz>()
-                        .unwrap(),
-                    "28984688919812345790446526728176753506503314096611481498246417562994872970561"
-                        .parse::<Mpz>()
-                        .unwrap()
-                ),
-            ),
-            "233782579309306465553849508530338471250".parse::<Mpz>().unwrap()
+            Ecvrf::new(Suite::Edwards25519Sha512Ell2).hash_points(&[
+                &point("9061d3a7c68c64efecda0463eb2163ef7793d7049785510b07e3c381f2bbdd62"),
+                &point("2b0665af7f54b1fda6c2a98510f9ad84be77e35a3dbe5f5207f50a72abb3baf0"),
+                &point("813669878c8f1ab81d0db4650bf69974b415abfac36ce297049c256a0322ccde"),
+                &point("416d1803483ae3c3458f8675c7ae19790c00da7afb26504f08fed92ba8c01440"),
+            ]),
+            Scalar::from(233782579309306465553849508530338471250u128)
         );
     }
 
     #[test]
     fn expand_message_xmd_test() {
         assert_eq!(
-            expand_message_xmd(&vec![]),
+            expand_message_xmd(&[]).to_vec(),
             decode("de5b8109b80da1d4861defe3e20710c8ac2efe65d815bb79d0b0087ddb0667718adb94fa478843979611e80749109ca55881a12b9d64c9ae5f7b36075f8e0354").unwrap()
         );
         assert_eq!(
-            expand_message_xmd(&decode("0102040810204080ff").unwrap()),
+            expand_message_xmd(&[&decode("0102040810204080ff").unwrap()]).to_vec(),
             decode("916b471e7c4d60e8a4ba6d0310e4e8de5a59d94011c4e8d2843d452a1651b9f854f5582788dec477b3811cd56973dbbba346a98877ffd1b61d045caccbdddbe8").unwrap()
         );
         assert_eq!(
-            expand_message_xmd(&decode("756f547ab8accc336a280f96343cfdbe9621935dcb452bba4f3460ef8f090883").unwrap()),
+            expand_message_xmd(&[&decode("756f547ab8accc336a280f96343cfdbe9621935dcb452bba4f3460ef8f090883").unwrap()]).to_vec(),
             decode("365d2351f19838da62f7b68464f61e961a01cbc3fdde0099bdc3db6b3a9c3f8d23eeacc1865e570b063263d3e8ded3c4cd4a11566f96ca5f63d06bb65d815bb8").unwrap()
         );
+        // Splitting the message into parts does not change the output.
+        assert_eq!(
+            expand_message_xmd(&[&[1, 2, 4], &[], &[8, 16, 32, 64, 128, 255]]),
+            expand_message_xmd(&[&[1, 2, 4, 8, 16, 32, 64, 128, 255]])
+        );
     }
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn hash_to_field_test() {
         assert_eq!(
             hash_to_field(&vec![]),
             "19984796091926620114398603282246129530205018809106914407141744082303129033320"
-                .parse::<Mpz>()
+                .parse::<BigUint>()
                 .unwrap()
         );
         assert_eq!(
-            hash_to_field(&decode("0102040810204080ff").unwrap()),
+            hash_to_field(&[&decode("0102040810204080ff").unwrap()]),
             "40866905167524404221649250981304847553674991259516901614549124933108104064175"
-                .parse::<Mpz>()
+                .parse::<BigUint>()
                 .unwrap()
         );
         assert_eq!(
-            hash_to_field(
-                &decode("6073bd567edb2e1d6ef03cb70a54017ffd5b874b136bbbddfbc5a8af6606b697")
-                    .unwrap(),
-            ),
+            hash_to_field(&[&decode(
+                "6073bd567edb2e1d6ef03cb70a54017ffd5b874b136bbbddfbc5a8af6606b697"
+            )
+            .unwrap()]),
             "42190151610809284644600066009282933920020180701265092905748556772002395560942"
-                .parse::<Mpz>()
+                .parse::<BigUint>()
                 .unwrap()
         );
         assert_eq!(
-            hash_to_field(
-                &decode("1152c7e217f100d85a6b7e51cb8e6c838a8fc8c95a5ab43ac7412a085cd67307431cd149b898b98c017fe1003bf848ad1dc2254b093497bfab90159ea54c5559")
-                    .unwrap(),
-            ),
+            hash_to_field(&[&decode("1152c7e217f100d85a6b7e51cb8e6c838a8fc8c95a5ab43ac7412a085cd67307431cd149b898b98c017fe1003bf848ad1dc2254b093497bfab90159ea54c5559").unwrap()]),
             "7289615016767941863395051431412729080032480398674317575538643993554362504793"
-                .parse::<Mpz>()
+                .parse::<BigUint>()
                 .unwrap()
         );
     }
@@ -593,184 +748,15 @@ mod tests {
 
     #[test]
     fn inverse_test() {
-        let a = "115792089237316195423570234324123".parse::<Mpz>().unwrap();
+        let a = "115792089237316195423570234324123".parse::<BigUint>().unwrap();
         let b = "50185070121833820750509717279311425478202465867786279873084127885179732477785"
-            .parse::<Mpz>()
+            .parse::<BigUint>()
             .unwrap();
         assert_eq!(b, inverse(&a));
     }
 
     #[test]
-    fn ecc_sqrt_test() {
-        assert_eq!(
-            "35634419551235720116798594689937697774970528779494777598852457192116356634056"
-                .parse::<Mpz>()
-                .unwrap(),
-            x_recover(
-                &"50185070121833820750509717279311425478202465867786279873084127885179732477785"
-                    .parse::<Mpz>()
-                    .unwrap()
-            )
-        );
-        assert_eq!(
-            "53301587420761876222207658879710286820900298918325969647217375986994648841896"
-                .parse::<Mpz>()
-                .unwrap(),
-            x_recover(&"3185713857305035135".parse::<Mpz>().unwrap())
-        );
-        assert_eq!(
-            "46177144718970195273346399805952030171392250782719158809116863111243864153332"
-                .parse::<Mpz>()
-                .unwrap(),
-            x_recover(&"87305764600495522745247520759120714246727049616".parse::<Mpz>().unwrap())
-        );
-    }
-
-    #[test]
-    fn edwards_add_test() {
-        assert_eq!(
-            edwards_add(&(Mpz::from(1), Mpz::from(2)), &(Mpz::from(3), Mpz::from(4)),),
-            (
-                "30669472807527669052310166413469871322722837873560156671152128699509420332835"
-                    .parse::<Mpz>()
-                    .unwrap(),
-                "32803760088457211740806219601341938367891502708272204402052114923463521408048"
-                    .parse::<Mpz>()
-                    .unwrap()
-            )
-        );
-        assert_eq!(
-            edwards_add(
-                &(
-                    "105245200036929210524520003692921052452000369292".parse::<Mpz>().unwrap(),
-                    "636368388952114463636838895211446363683889521144".parse::<Mpz>().unwrap()
-                ),
-                &(
-                    "365761262312465236576126231246523657612623124652".parse::<Mpz>().unwrap(),
-                    "599638831716981459963883171698145996388317169814".parse::<Mpz>().unwrap()
-                ),
-            ),
-            (
-                "16094028690776613779404630311380383789228303041060010793878272985304591730114"
-                    .parse::<Mpz>()
-                    .unwrap(),
-                "56509461539446191492739335780640787740284013129997346250692191322113562145891"
-                    .parse::<Mpz>()
-                    .unwrap()
-            )
-        );
-    }
-
-    #[test]
-    fn scalar_multiply_test() {
-        assert_eq!(
-            scalar_multiply(
-                &(
-                    "2504841017466682250484101746668225048410174666822504841017466682"
-                        .parse::<Mpz>()
-                        .unwrap(),
-                    "1956113754237990195611375423799019561137542379901956113754237990"
-                        .parse::<Mpz>()
-                        .unwrap()
-                ),
-                &"7126414032541130712641403254113071264140325411307126414032541130"
-                    .parse::<Mpz>()
-                    .unwrap()
-            ),
-            (
-                "3717741300534171586596133929728979624065571837388221471827653882295568582734"
-                    .parse::<Mpz>()
-                    .unwrap(),
-                "1221637037450835314506423104277906057339963056664048728491680523116867554868"
-                    .parse::<Mpz>()
-                    .unwrap()
-            )
-        );
-        assert_eq!(
-            scalar_multiply(
-                &(
-                    "2504841017466682250484101746668225048410174666822504841017466682"
-                        .parse::<Mpz>()
-                        .unwrap(),
-                    "1513453546461956113754237990195611375423799019561137542379901956113754237990"
-                        .parse::<Mpz>()
-                        .unwrap(),
-                ),
-                &"74830380039917927238342598863222899552394587271096264578218486964046080567388"
-                    .parse::<Mpz>()
-                    .unwrap(),
-            ),
-            (
-                "10451491913815505047931853002078552559328154600536681248542806488509264630860"
-                    .parse::<Mpz>()
-                    .unwrap(),
-                "1891777415277742323394479244063570290330034114551949119047672059968424552778"
-                    .parse::<Mpz>()
-                    .unwrap(),
-            )
-        );
-
-        assert_eq!(
-            scalar_multiply(
-                &(
-                    "41580769168035012703902357280663015773275161554063216603182338549261711251193"
-                        .parse::<Mpz>()
-                        .unwrap(),
-                    "24911656077204456209601399282188369610223880089588176348139024489849710828841"
-                        .parse::<Mpz>()
-                        .unwrap(),
-                ),
-                &"112366451224199189657043841110239819447199235354327421131306119208159432979989"
-                    .parse::<Mpz>()
-                    .unwrap(),
-            ),
-            (
-                "8072112576901302001883587473420904198649999849925609514862948818584399467310"
-                    .parse::<Mpz>()
-                    .unwrap(),
-                "35299203632341130723598861202244935989969207066742744119141421954087584890438"
-                    .parse::<Mpz>()
-                    .unwrap(),
-            )
-        );
-
-        assert_eq!(
-            scalar_multiply(&(Mpz::zero(), Mpz::zero()), &Mpz::zero(),),
-            (Mpz::zero(), Mpz::one(),)
-        );
-    }
-
-    #[test]
-    fn ecvrf_verify_from_draft09_test() {
-        assert_eq!(
-            ecvrf_verify(
-                &decode("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a").unwrap(),
-                &decode("7d9c633ffeee27349264cf5c667579fc583b4bda63ab71d001f89c10003ab46f25898f6bd7d4ed4c75f0282b0f7bb9d0e61b387b76db60b3cbf34bf09109ccb33fab742a8bddc0c8ba3caf5c0b75bb04").unwrap(),
-                &[]
-            ).unwrap(),
-            true
-        );
-        assert_eq!(
-            ecvrf_verify(
-                &decode("3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c")
-                    .unwrap(),
-                &decode("47b327393ff2dd81336f8a2ef10339112401253b3c714eeda879f12c509072ef9bf1a234f833f72d8fff36075fd9b836da28b5569e74caa418bae7ef521f2ddd35f5727d271ecc70b4a83c1fc8ebc40c").unwrap(),
-                &[114]
-            ).unwrap(),
-            true
-        );
-        assert_eq!(
-            ecvrf_verify(
-                &decode("fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025")
-                    .unwrap(),
-                &decode("926e895d308f5e328e7aa159c06eddbe56d06846abf5d98c2512235eaa57fdce6187befa109606682503b3a1424f0f729ca0418099fbd86a48093e6a8de26307b8d93e02da927e6dd5b73c8f119aee0f").unwrap(),
-                &[175, 130]
-            ).unwrap(),
-            true
-        );
-    }
-
-    #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn ecvrf_verify_additional_test() {
         assert_eq!(
             ecvrf_verify(
@@ -834,4 +820,186 @@ mod tests {
             Err(CryptoError::invalid_proof_format())
         );
     }
+
+    #[test]
+    fn ecvrf_verify_mismatch_test() {
+        let y = decode("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a").unwrap();
+        let pi = decode("7d9c633ffeee27349264cf5c667579fc583b4bda63ab71d001f89c10003ab46f25898f6bd7d4ed4c75f0282b0f7bb9d0e61b387b76db60b3cbf34bf09109ccb33fab742a8bddc0c8ba3caf5c0b75bb04").unwrap();
+        assert_eq!(ecvrf_verify(&y, &pi, &[1]), Ok(false));
+
+        let mut tampered = pi.clone();
+        tampered[40] ^= 1;
+        assert_eq!(ecvrf_verify(&y, &tampered, &[]), Ok(false));
+    }
+
+    #[test]
+    fn public_key_test() {
+        let secret_key = SecretKey::from_slice(
+            &decode("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            encode(secret_key.public_key().to_bytes()),
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+        );
+        assert_eq!(Keypair::from(secret_key.clone()).public, secret_key.public_key());
+
+        match SecretKey::from_slice(&[0u8; 31]).err().unwrap() {
+            CryptoError::InvalidSecretKeyFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+
+    #[test]
+    fn ecvrf_prove_verify_roundtrip_test() {
+        let keypair = Keypair::from(SecretKey::from([7u8; 32]));
+        for alpha in [&b""[..], b"band", &[0xffu8; 300]].iter() {
+            let pi = ecvrf_prove(&keypair.secret, alpha).unwrap();
+            assert!(ecvrf_verify(keypair.public.as_bytes(), &pi, alpha).unwrap());
+            assert!(!ecvrf_verify(keypair.public.as_bytes(), &pi, b"other").unwrap());
+        }
+    }
+
+    #[test]
+    fn secret_key_zeroize_test() {
+        let mut secret_key = SecretKey::from([7u8; 32]);
+        secret_key.zeroize();
+        assert_eq!(*secret_key.to_bytes(), [0u8; 32]);
+    }
+
+    #[test]
+    fn ecvrf_verify_off_curve_pubkey_test() {
+        let pi = decode("7d9c633ffeee27349264cf5c667579fc583b4bda63ab71d001f89c10003ab46f25898f6bd7d4ed4c75f0282b0f7bb9d0e61b387b76db60b3cbf34bf09109ccb33fab742a8bddc0c8ba3caf5c0b75bb04").unwrap();
+        // y = 2 has no matching x on the curve.
+        let mut y = [0u8; 32];
+        y[0] = 2;
+        match ecvrf_verify(&y, &pi, &[]).err().unwrap() {
+            CryptoError::InvalidPointOnCurve { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+
+    struct Vector {
+        sk: Vec<u8>,
+        pk: Vec<u8>,
+        alpha: Vec<u8>,
+        pi: Vec<u8>,
+        beta: Vec<u8>,
+    }
+
+    // Parses vectors laid out as in the RFC: `name = hex` lines, one blank-line separated
+    // block per vector, with `#` comments.
+    fn load_vectors(contents: &str) -> Vec<Vector> {
+        contents
+            .split("\n\n")
+            .map(|block| {
+                block
+                    .lines()
+                    .filter(|line| !line.starts_with('#'))
+                    .map(|line| {
+                        let (name, value) = line.split_once('=').unwrap();
+                        (name.trim(), decode(value.trim()).unwrap())
+                    })
+                    .collect::<std::collections::HashMap<_, _>>()
+            })
+            .filter(|fields| !fields.is_empty())
+            .map(|mut fields| Vector {
+                sk: fields.remove("SK").unwrap(),
+                pk: fields.remove("PK").unwrap(),
+                alpha: fields.remove("alpha").unwrap(),
+                pi: fields.remove("pi").unwrap(),
+                beta: fields.remove("beta").unwrap(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ecvrf_conformance_test() {
+        let suites = [
+            (Suite::Edwards25519Sha512Ell2, include_str!("../testdata/ecvrf/ell2_draft09.txt")),
+            (Suite::Edwards25519Sha512Tai, include_str!("../testdata/ecvrf/tai_rfc9381.txt")),
+        ];
+        for (suite, contents) in suites.iter() {
+            let ecvrf = Ecvrf::new(*suite);
+            let vectors = load_vectors(contents);
+            assert_eq!(vectors.len(), 3);
+            for vector in vectors {
+                let secret_key = SecretKey::from_slice(&vector.sk).unwrap();
+                assert_eq!(secret_key.public_key().to_bytes().to_vec(), vector.pk);
+                assert_eq!(ecvrf.prove(&secret_key, &vector.alpha).unwrap(), vector.pi);
+                assert!(ecvrf.verify(&vector.pk, &vector.pi, &vector.alpha).unwrap());
+                assert_eq!(ecvrf.proof_to_hash(&vector.pi).unwrap().to_vec(), vector.beta);
+                // The free functions are thin wrappers over the same front-end.
+                assert!(
+                    ecvrf_verify_with_suite(*suite, &vector.pk, &vector.pi, &vector.alpha).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ecvrf_suites_are_distinct_test() {
+        let vectors = load_vectors(include_str!("../testdata/ecvrf/tai_rfc9381.txt"));
+        for vector in vectors {
+            assert!(!ecvrf_verify(&vector.pk, &vector.pi, &vector.alpha).unwrap());
+        }
+    }
+
+    #[test]
+    fn ecvrf_default_suite_test() {
+        assert_eq!(Ecvrf::default().suite(), Suite::Edwards25519Sha512Ell2);
+        assert_eq!(Ecvrf::new(Suite::Edwards25519Sha512Tai).suite(), Suite::Edwards25519Sha512Tai);
+    }
+
+    #[test]
+    fn ecvrf_proof_to_hash_fail_test() {
+        match ecvrf_proof_to_hash(&[0u8; 79]).err().unwrap() {
+            CryptoError::InvalidProofFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+
+    #[test]
+    fn rand_from_vrf_test() {
+        for vector in load_vectors(include_str!("../testdata/ecvrf/ell2_draft09.txt")) {
+            let beta = BigUint::from_bytes_be(&vector.beta);
+            for range in [1u64, 2, 6, 1000, u64::MAX / 3 * 2, u64::MAX].iter() {
+                let expected = &beta % BigUint::from(*range);
+                assert_eq!(BigUint::from(rand_from_vrf(&vector.beta, *range).unwrap()), expected);
+            }
+            assert_eq!(rand_from_vrf(&vector.beta, 0), None);
+        }
+        assert_eq!(rand_from_vrf(&[], 10), Some(0));
+        assert_eq!(rand_from_vrf(&[1, 2], 0), None);
+    }
+
+    #[test]
+    fn rand_bytes_from_vrf_test() {
+        let beta = [7u8; 64];
+        let bytes = rand_bytes_from_vrf(&beta, 150);
+        assert_eq!(bytes.len(), 150);
+        assert_eq!(rand_bytes_from_vrf(&beta, 10), bytes[..10].to_vec());
+        assert_eq!(
+            rand_bytes_from_vrf(&beta, 64),
+            Sha512::digest(&[&beta[..], &[0u8; 8]].concat()).to_vec()
+        );
+        assert_ne!(bytes[..64], bytes[64..128]);
+        assert!(rand_bytes_from_vrf(&beta, 0).is_empty());
+    }
+
+    #[test]
+    fn keygen_test() {
+        use rand_chacha::rand_core::SeedableRng;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let keypair = keygen(&mut rng);
+        assert_eq!(keypair.public, keypair.secret.public_key());
+        assert_ne!(keygen(&mut rng).public, keypair.public);
+
+        for suite in [Suite::Edwards25519Sha512Ell2, Suite::Edwards25519Sha512Tai].iter() {
+            let pi = ecvrf_prove_with_suite(*suite, &keypair.secret, b"band").unwrap();
+            assert!(
+                ecvrf_verify_with_suite(*suite, keypair.public.as_bytes(), &pi, b"band").unwrap()
+            );
+        }
+    }
 }
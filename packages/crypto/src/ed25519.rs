@@ -0,0 +1,147 @@
+//! Ed25519 signature verification following RFC 8032, with these rules enforced:
+//!
+//! - Public keys are 32 bytes and signatures are 64 bytes.
+//! - The public key must decode to a point on the curve and must not be of small order.
+//! - The signature's R must not be of small order and its S must be canonical (`S < L`).
+//! - The cofactorless equation `[S]B = R + [k]A` must hold.
+use crate::error::{CryptoError, CryptoResult};
+use core::convert::TryInto;
+use ed25519_dalek::{Signature, VerifyingKey};
+
+pub const PUBKEY_LEN: usize = 32;
+pub const SIGNATURE_LEN: usize = 64;
+
+fn read_pubkey(pubkey: &[u8]) -> CryptoResult<VerifyingKey> {
+    let pubkey: &[u8; PUBKEY_LEN] =
+        pubkey.try_into().map_err(|_| CryptoError::invalid_pubkey_format())?;
+    VerifyingKey::from_bytes(pubkey).map_err(|_| CryptoError::invalid_point_on_curve())
+}
+
+fn read_signature(signature: &[u8]) -> CryptoResult<Signature> {
+    Signature::from_slice(signature).map_err(|_| CryptoError::invalid_signature_format())
+}
+
+pub fn verify(message: &[u8], signature: &[u8], pubkey: &[u8]) -> CryptoResult<bool> {
+    let signature = read_signature(signature)?;
+    let pubkey = read_pubkey(pubkey)?;
+
+    Ok(pubkey.verify_strict(message, &signature).is_ok())
+}
+
+/// Returns true only if every `(message, signature, pubkey)` triple verifies. Each triple
+/// is checked with the same rules as `verify`, so a batch never accepts a signature that
+/// would be rejected on its own.
+pub fn batch_verify(
+    messages: &[&[u8]],
+    signatures: &[&[u8]],
+    pubkeys: &[&[u8]],
+) -> CryptoResult<bool> {
+    if messages.len() != signatures.len() || messages.len() != pubkeys.len() {
+        return Err(CryptoError::generic_err(
+            "Mismatched number of messages, signatures and public keys",
+        ));
+    }
+
+    for ((message, signature), pubkey) in messages.iter().zip(signatures).zip(pubkeys) {
+        if !verify(message, signature, pubkey)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex::decode;
+
+    // Test vectors 1-3 of RFC 8032 section 7.1.
+    const VECTORS: [(&str, &str, &str); 3] = [
+        (
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            "",
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+        ),
+        (
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            "72",
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+        ),
+        (
+            "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            "af82",
+            "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a",
+        ),
+    ];
+
+    fn vectors() -> Vec<(Vec<u8>, Vec<u8>, Vec<u8>)> {
+        VECTORS
+            .iter()
+            .map(|(pk, msg, sig)| (decode(pk).unwrap(), decode(msg).unwrap(), decode(sig).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn verify_from_rfc8032_test() {
+        for (pk, msg, sig) in vectors() {
+            assert!(verify(&msg, &sig, &pk).unwrap());
+            assert!(!verify(b"other", &sig, &pk).unwrap());
+        }
+    }
+
+    #[test]
+    fn verify_fail_test() {
+        let (pk, msg, sig) = vectors().remove(0);
+        match verify(&msg, &sig[1..], &pk).err().unwrap() {
+            CryptoError::InvalidSignatureFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+        match verify(&msg, &sig, &pk[1..]).err().unwrap() {
+            CryptoError::InvalidPubkeyFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+
+    #[test]
+    fn verify_rejects_non_canonical_s_test() {
+        let (pk, msg, mut sig) = vectors().remove(0);
+        // Adding L to S keeps the equation true but makes S non-canonical.
+        let l = decode("edd3f55c1a631258d69cf7a2def9de1400000000000000000000000000000010").unwrap();
+        let mut carry = 0u16;
+        for i in 0..32 {
+            let sum = sig[32 + i] as u16 + l[i] as u16 + carry;
+            sig[32 + i] = sum as u8;
+            carry = sum >> 8;
+        }
+        assert!(!verify(&msg, &sig, &pk).unwrap());
+    }
+
+    #[test]
+    fn verify_rejects_small_order_test() {
+        // The identity as public key with R = identity and S = 0 satisfies the
+        // cofactorless equation for any message.
+        let mut identity = [0u8; 32];
+        identity[0] = 1;
+        let sig = [&identity[..], &[0u8; 32]].concat();
+        assert!(!verify(b"message", &sig, &identity).unwrap());
+    }
+
+    #[test]
+    fn batch_verify_test() {
+        let vectors = vectors();
+        let messages: Vec<&[u8]> = vectors.iter().map(|v| &v.1[..]).collect();
+        let signatures: Vec<&[u8]> = vectors.iter().map(|v| &v.2[..]).collect();
+        let pubkeys: Vec<&[u8]> = vectors.iter().map(|v| &v.0[..]).collect();
+        assert!(batch_verify(&messages, &signatures, &pubkeys).unwrap());
+        assert!(batch_verify(&[], &[], &[]).unwrap());
+
+        let mut swapped = pubkeys.clone();
+        swapped.swap(0, 1);
+        assert!(!batch_verify(&messages, &signatures, &swapped).unwrap());
+
+        match batch_verify(&messages[1..], &signatures, &pubkeys).err().unwrap() {
+            CryptoError::GenericErr { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+}
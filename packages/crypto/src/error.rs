@@ -1,6 +1,7 @@
+use alloc::string::String;
+use core::fmt::Debug;
 #[cfg(feature = "backtraces")]
 use std::backtrace::Backtrace;
-use std::fmt::Debug;
 use thiserror::Error;
 
 pub type CryptoResult<T> = core::result::Result<T, CryptoError>;
@@ -33,6 +34,31 @@ pub enum CryptoError {
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
+    #[error("Invalid secret key format")]
+    InvalidSecretKeyFormat {
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
+    #[error("Invalid signature format")]
+    InvalidSignatureFormat {
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
+    #[error("Invalid recovery parameter. Supported values: 0 and 1.")]
+    InvalidRecoveryParam {
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
+    #[error("Non-canonical point encoding")]
+    NonCanonicalPoint {
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
+    #[error("Non-canonical scalar encoding")]
+    NonCanonicalScalar {
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
 }
 
 impl CryptoError {
@@ -72,6 +98,41 @@ impl CryptoError {
         }
     }
 
+    pub fn invalid_secret_key_format() -> Self {
+        CryptoError::InvalidSecretKeyFormat {
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    pub fn invalid_signature_format() -> Self {
+        CryptoError::InvalidSignatureFormat {
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    pub fn invalid_recovery_param() -> Self {
+        CryptoError::InvalidRecoveryParam {
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    pub fn non_canonical_point() -> Self {
+        CryptoError::NonCanonicalPoint {
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    pub fn non_canonical_scalar() -> Self {
+        CryptoError::NonCanonicalScalar {
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
     /// Numeric error code that can easily be passed over the
     /// contract VM boundary.
     pub fn code(&self) -> u32 {
@@ -80,7 +141,12 @@ impl CryptoError {
             CryptoError::InvalidHashFormat { .. } => 3,
             CryptoError::InvalidProofFormat { .. } => 4,
             CryptoError::InvalidPubkeyFormat { .. } => 5,
+            CryptoError::InvalidSecretKeyFormat { .. } => 6,
+            CryptoError::InvalidSignatureFormat { .. } => 7,
+            CryptoError::InvalidRecoveryParam { .. } => 8,
             CryptoError::GenericErr { .. } => 10,
+            CryptoError::NonCanonicalPoint { .. } => 11,
+            CryptoError::NonCanonicalScalar { .. } => 12,
         }
     }
 }
@@ -136,12 +202,62 @@ mod tests {
         }
     }
 
+    #[test]
+    fn invalid_secret_key_format_works() {
+        let error = CryptoError::invalid_secret_key_format();
+        match error {
+            CryptoError::InvalidSecretKeyFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+
+    #[test]
+    fn invalid_signature_format_works() {
+        let error = CryptoError::invalid_signature_format();
+        match error {
+            CryptoError::InvalidSignatureFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+
+    #[test]
+    fn invalid_recovery_param_works() {
+        let error = CryptoError::invalid_recovery_param();
+        match error {
+            CryptoError::InvalidRecoveryParam { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+
+    #[test]
+    fn non_canonical_point_works() {
+        let error = CryptoError::non_canonical_point();
+        match error {
+            CryptoError::NonCanonicalPoint { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+
+    #[test]
+    fn non_canonical_scalar_works() {
+        let error = CryptoError::non_canonical_scalar();
+        match error {
+            CryptoError::NonCanonicalScalar { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+
     #[test]
     fn code_works() {
         assert_eq!(CryptoError::invalid_point_on_curve().code(), 2);
         assert_eq!(CryptoError::invalid_hash_format().code(), 3);
         assert_eq!(CryptoError::invalid_proof_format().code(), 4);
         assert_eq!(CryptoError::invalid_pubkey_format().code(), 5);
+        assert_eq!(CryptoError::invalid_secret_key_format().code(), 6);
+        assert_eq!(CryptoError::invalid_signature_format().code(), 7);
+        assert_eq!(CryptoError::invalid_recovery_param().code(), 8);
         assert_eq!(CryptoError::generic_err("test").code(), 10);
+        assert_eq!(CryptoError::non_canonical_point().code(), 11);
+        assert_eq!(CryptoError::non_canonical_scalar().code(), 12);
     }
 }
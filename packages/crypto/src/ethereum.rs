@@ -0,0 +1,89 @@
+use crate::error::{CryptoError, CryptoResult};
+use crate::hash::keccak256;
+use crate::secp256k1::{recover_pubkey, SIGNATURE_LEN};
+use alloc::format;
+
+pub const ADDRESS_LEN: usize = 20;
+/// Length of an Ethereum signature, `r || s || v`.
+pub const RECOVERABLE_SIGNATURE_LEN: usize = SIGNATURE_LEN + 1;
+
+/// Hashes `message` as `personal_sign` / `eth_sign` do, following EIP-191 version 0x45:
+/// `keccak256("\x19Ethereum Signed Message:\n" || len(message) || message)`.
+pub fn eip191_hash(message: &[u8]) -> [u8; 32] {
+    let prefix = format!("\x19Ethereum Signed Message:\n{}", message.len());
+    keccak256(&[prefix.as_bytes(), message].concat())
+}
+
+/// Returns the address of the key that signed `message_hash`. `signature` is the 65-byte
+/// `r || s || v` form, where `v` is either 27/28 or the raw recovery id 0/1.
+pub fn ecrecover_address(message_hash: &[u8], signature: &[u8]) -> CryptoResult<[u8; 20]> {
+    if signature.len() != RECOVERABLE_SIGNATURE_LEN {
+        return Err(CryptoError::invalid_signature_format());
+    }
+    let recovery_param = match signature[SIGNATURE_LEN] {
+        v @ 0..=1 => v,
+        v @ 27..=28 => v - 27,
+        _ => return Err(CryptoError::invalid_recovery_param()),
+    };
+
+    let pubkey = recover_pubkey(message_hash, &signature[..SIGNATURE_LEN], recovery_param)?;
+    let mut address = [0u8; ADDRESS_LEN];
+    address.copy_from_slice(&keccak256(&pubkey[1..])[12..]);
+    Ok(address)
+}
+
+/// Returns the address that produced a `personal_sign` signature over `message`.
+pub fn recover_personal_sign_address(message: &[u8], signature: &[u8]) -> CryptoResult<[u8; 20]> {
+    ecrecover_address(&eip191_hash(message), signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex::{decode, encode};
+
+    // From the web3.js documentation of `web3.eth.accounts.sign("Some data", privateKey)`.
+    const MESSAGE: &[u8] = b"Some data";
+    const MESSAGE_HASH: &str = "1da44b586eb0729ff70a73c326926f6ed5a25f5b056e7f47fbc6e58d86871655";
+    const SIGNATURE: &str = "b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c";
+    const ADDRESS: &str = "2c7536e3605d9c16a7a3d7b1898e529396a65c23";
+
+    #[test]
+    fn eip191_hash_test() {
+        assert_eq!(encode(eip191_hash(MESSAGE)), MESSAGE_HASH);
+    }
+
+    #[test]
+    fn ecrecover_address_test() {
+        let mut signature = decode(SIGNATURE).unwrap();
+        assert_eq!(
+            encode(ecrecover_address(&decode(MESSAGE_HASH).unwrap(), &signature).unwrap()),
+            ADDRESS
+        );
+        assert_eq!(encode(recover_personal_sign_address(MESSAGE, &signature).unwrap()), ADDRESS);
+
+        // The raw recovery id is accepted in place of 27/28.
+        signature[64] -= 27;
+        assert_eq!(encode(recover_personal_sign_address(MESSAGE, &signature).unwrap()), ADDRESS);
+
+        assert_ne!(
+            encode(recover_personal_sign_address(b"Other data", &signature).unwrap()),
+            ADDRESS
+        );
+    }
+
+    #[test]
+    fn ecrecover_address_fail_test() {
+        let signature = decode(SIGNATURE).unwrap();
+        match recover_personal_sign_address(MESSAGE, &signature[..64]).err().unwrap() {
+            CryptoError::InvalidSignatureFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+        let mut bad_v = signature;
+        bad_v[64] = 29;
+        match recover_personal_sign_address(MESSAGE, &bad_v).err().unwrap() {
+            CryptoError::InvalidRecoveryParam { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+}
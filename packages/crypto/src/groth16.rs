@@ -0,0 +1,277 @@
+//! Groth16 proof verification over BN254 (alt_bn128), for proofs from circom/snarkjs,
+//! gnark and other provers that target the Ethereum precompiles.
+//!
+//! Points use the EIP-197 encoding: a G1 point is `x || y` and a G2 point is
+//! `x.c1 || x.c0 || y.c1 || y.c0`, each coordinate a 32-byte big-endian integer, with all
+//! zeros for the point at infinity. The encoded values are laid out as follows:
+//!
+//! - proof: `A (G1) || B (G2) || C (G1)`, 256 bytes.
+//! - verifying key: `alpha (G1) || beta (G2) || gamma (G2) || delta (G2) || IC[0..=n] (G1)`,
+//!   where `n` is the number of public inputs.
+//! - public inputs: `n` 32-byte big-endian scalars.
+use crate::error::{CryptoError, CryptoResult};
+use alloc::vec::Vec;
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_groth16::{Groth16, Proof, VerifyingKey};
+
+pub const G1_LEN: usize = 64;
+pub const G2_LEN: usize = 128;
+pub const PROOF_LEN: usize = G1_LEN + G2_LEN + G1_LEN;
+pub const INPUT_LEN: usize = 32;
+/// Length of a verifying key without its IC points.
+const VK_BASE_LEN: usize = G1_LEN + 3 * G2_LEN;
+
+fn read_fq(bytes: &[u8]) -> CryptoResult<Fq> {
+    let element = Fq::from_be_bytes_mod_order(bytes);
+    if element.into_bigint().to_bytes_be() != bytes {
+        return Err(CryptoError::non_canonical_point());
+    }
+    Ok(element)
+}
+
+fn read_g1(bytes: &[u8]) -> CryptoResult<G1Affine> {
+    let (x, y) = (read_fq(&bytes[..32])?, read_fq(&bytes[32..64])?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Affine::zero());
+    }
+    let point = G1Affine::new_unchecked(x, y);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(CryptoError::invalid_point_on_curve());
+    }
+    Ok(point)
+}
+
+fn read_g2(bytes: &[u8]) -> CryptoResult<G2Affine> {
+    let x = Fq2::new(read_fq(&bytes[32..64])?, read_fq(&bytes[..32])?);
+    let y = Fq2::new(read_fq(&bytes[96..128])?, read_fq(&bytes[64..96])?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2Affine::zero());
+    }
+    let point = G2Affine::new_unchecked(x, y);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(CryptoError::invalid_point_on_curve());
+    }
+    Ok(point)
+}
+
+fn read_fr(bytes: &[u8]) -> CryptoResult<Fr> {
+    let element = Fr::from_be_bytes_mod_order(bytes);
+    if element.into_bigint().to_bytes_be() != bytes {
+        return Err(CryptoError::non_canonical_scalar());
+    }
+    Ok(element)
+}
+
+fn read_proof(proof: &[u8]) -> CryptoResult<Proof<Bn254>> {
+    if proof.len() != PROOF_LEN {
+        return Err(CryptoError::invalid_proof_format());
+    }
+    Ok(Proof {
+        a: read_g1(&proof[..G1_LEN])?,
+        b: read_g2(&proof[G1_LEN..G1_LEN + G2_LEN])?,
+        c: read_g1(&proof[G1_LEN + G2_LEN..])?,
+    })
+}
+
+fn read_vk(vk: &[u8]) -> CryptoResult<VerifyingKey<Bn254>> {
+    if input_count(vk.len()).is_none() {
+        return Err(CryptoError::generic_err("Invalid verifying key length"));
+    }
+    let g2 = |index: usize| read_g2(&vk[G1_LEN + index * G2_LEN..G1_LEN + (index + 1) * G2_LEN]);
+    Ok(VerifyingKey {
+        alpha_g1: read_g1(&vk[..G1_LEN])?,
+        beta_g2: g2(0)?,
+        gamma_g2: g2(1)?,
+        delta_g2: g2(2)?,
+        gamma_abc_g1: vk[VK_BASE_LEN..].chunks(G1_LEN).map(read_g1).collect::<CryptoResult<_>>()?,
+    })
+}
+
+/// Number of public inputs a verifying key of `vk_len` bytes expects, if the length is valid.
+pub fn input_count(vk_len: usize) -> Option<usize> {
+    let ic_len = vk_len.checked_sub(VK_BASE_LEN)?;
+    match (ic_len / G1_LEN, ic_len % G1_LEN) {
+        (count, 0) if count > 0 => Some(count - 1),
+        _ => None,
+    }
+}
+
+/// Verifies a Groth16 proof against a verifying key and the concatenated public inputs.
+/// Encodings that are malformed, off the curve or outside the prime-order subgroup are
+/// errors. A well-formed proof that does not verify returns `Ok(false)`.
+pub fn verify(vk: &[u8], proof: &[u8], public_inputs: &[u8]) -> CryptoResult<bool> {
+    let vk = read_vk(vk)?;
+    let proof = read_proof(proof)?;
+    if public_inputs.len() != (vk.gamma_abc_g1.len() - 1) * INPUT_LEN {
+        return Err(CryptoError::generic_err(
+            "Number of public inputs does not match the verifying key",
+        ));
+    }
+    let public_inputs: Vec<Fr> =
+        public_inputs.chunks(INPUT_LEN).map(read_fr).collect::<CryptoResult<_>>()?;
+
+    let pvk = ark_groth16::prepare_verifying_key(&vk);
+    Ok(Groth16::<Bn254>::verify_proof(&pvk, &proof, &public_inputs).unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::CurveGroup;
+    use ark_relations::lc;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+    use num_bigint::BigUint;
+    use rand_chacha::rand_core::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    // Proves knowledge of x and y such that x * y = z for a public z.
+    struct MultiplyCircuit {
+        x: Option<Fr>,
+        y: Option<Fr>,
+    }
+
+    impl ConstraintSynthesizer<Fr> for MultiplyCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let x = cs.new_witness_variable(|| self.x.ok_or(SynthesisError::AssignmentMissing))?;
+            let y = cs.new_witness_variable(|| self.y.ok_or(SynthesisError::AssignmentMissing))?;
+            let z = cs.new_input_variable(|| {
+                Ok(self.x.ok_or(SynthesisError::AssignmentMissing)?
+                    * self.y.ok_or(SynthesisError::AssignmentMissing)?)
+            })?;
+            cs.enforce_constraint(lc!() + x, lc!() + y, lc!() + z)?;
+            Ok(())
+        }
+    }
+
+    fn encode_fq(element: &Fq) -> Vec<u8> {
+        element.into_bigint().to_bytes_be()
+    }
+
+    fn encode_g1(point: &G1Affine) -> Vec<u8> {
+        match point.xy() {
+            Some((x, y)) => [encode_fq(&x), encode_fq(&y)].concat(),
+            None => vec![0u8; G1_LEN],
+        }
+    }
+
+    fn encode_g2(point: &G2Affine) -> Vec<u8> {
+        match point.xy() {
+            Some((x, y)) => {
+                [encode_fq(&x.c1), encode_fq(&x.c0), encode_fq(&y.c1), encode_fq(&y.c0)].concat()
+            }
+            None => vec![0u8; G2_LEN],
+        }
+    }
+
+    fn encode_vk(vk: &VerifyingKey<Bn254>) -> Vec<u8> {
+        let mut bytes =
+            [encode_g1(&vk.alpha_g1), encode_g2(&vk.beta_g2), encode_g2(&vk.gamma_g2)].concat();
+        bytes.extend(encode_g2(&vk.delta_g2));
+        vk.gamma_abc_g1.iter().for_each(|point| bytes.extend(encode_g1(point)));
+        bytes
+    }
+
+    fn encode_proof(proof: &Proof<Bn254>) -> Vec<u8> {
+        [encode_g1(&proof.a), encode_g2(&proof.b), encode_g1(&proof.c)].concat()
+    }
+
+    fn setup() -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let pk = Groth16::<Bn254>::generate_random_parameters_with_reduction(
+            MultiplyCircuit { x: None, y: None },
+            &mut rng,
+        )
+        .unwrap();
+        let circuit = MultiplyCircuit { x: Some(Fr::from(3u64)), y: Some(Fr::from(11u64)) };
+        let proof =
+            Groth16::<Bn254>::create_random_proof_with_reduction(circuit, &pk, &mut rng).unwrap();
+        (encode_vk(&pk.vk), encode_proof(&proof), Fr::from(33u64).into_bigint().to_bytes_be())
+    }
+
+    fn decimal(value: &str) -> Vec<u8> {
+        let bytes = BigUint::parse_bytes(value.as_bytes(), 10).unwrap().to_bytes_be();
+        [vec![0u8; 32 - bytes.len()], bytes].concat()
+    }
+
+    #[test]
+    fn read_point_eip197_test() {
+        // The G1 and G2 generators as used by the Ethereum pairing precompile.
+        let g1 = [decimal("1"), decimal("2")].concat();
+        assert_eq!(read_g1(&g1).unwrap(), G1Affine::generator());
+        let g2 = [
+            decimal(
+                "11559732032986387107991004021392285783925812861821192530917403151452391805634",
+            ),
+            decimal(
+                "10857046999023057135944570762232829481370756359578518086990519993285655852781",
+            ),
+            decimal("4082367875863433681332203403145435568316851327593401208105741076214120093531"),
+            decimal("8495653923123431417604973247489272438418190587263600148770280649306958101930"),
+        ]
+        .concat();
+        assert_eq!(read_g2(&g2).unwrap(), G2Affine::generator());
+        assert_eq!(read_g1(&[0u8; G1_LEN]).unwrap(), G1Affine::zero());
+        assert_eq!(read_g2(&[0u8; G2_LEN]).unwrap(), G2Affine::zero());
+
+        let mut off_curve = g1.clone();
+        off_curve[63] = 3;
+        assert_eq!(read_g1(&off_curve), Err(CryptoError::invalid_point_on_curve()));
+        // x = p is congruent to 0 but is not a canonical coordinate.
+        let p = decimal(
+            "21888242871839275222246405745257275088696311157297823662689037894645226208583",
+        );
+        assert_eq!(read_g1(&[p, decimal("2")].concat()), Err(CryptoError::non_canonical_point()));
+    }
+
+    #[test]
+    fn verify_test() {
+        let (vk, proof, inputs) = setup();
+        assert_eq!(input_count(vk.len()), Some(1));
+        assert!(verify(&vk, &proof, &inputs).unwrap());
+        assert!(!verify(&vk, &proof, &Fr::from(34u64).into_bigint().to_bytes_be()).unwrap());
+
+        // Swapping A and C keeps the proof well-formed but invalid.
+        let swapped =
+            [&proof[G1_LEN + G2_LEN..], &proof[G1_LEN..G1_LEN + G2_LEN], &proof[..G1_LEN]].concat();
+        assert!(!verify(&vk, &swapped, &inputs).unwrap());
+
+        // A proof with its A negated is a point on the curve that fails verification.
+        let a = read_g1(&proof[..G1_LEN]).unwrap();
+        let negated =
+            [encode_g1(&(-a.into_group()).into_affine()), proof[G1_LEN..].to_vec()].concat();
+        assert!(!verify(&vk, &negated, &inputs).unwrap());
+    }
+
+    #[test]
+    fn verify_fail_test() {
+        let (vk, proof, inputs) = setup();
+        match verify(&vk, &proof[1..], &inputs).err().unwrap() {
+            CryptoError::InvalidProofFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+        match verify(&vk[..vk.len() - 1], &proof, &inputs).err().unwrap() {
+            CryptoError::GenericErr { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+        match verify(&vk, &proof, &[inputs.clone(), inputs.clone()].concat()).err().unwrap() {
+            CryptoError::GenericErr { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+        // The scalar field modulus as a public input.
+        let r = decimal(
+            "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+        );
+        match verify(&vk, &proof, &r).err().unwrap() {
+            CryptoError::NonCanonicalScalar { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+        let mut bad_proof = proof;
+        bad_proof[63] ^= 1;
+        match verify(&vk, &bad_proof, &inputs).err().unwrap() {
+            CryptoError::InvalidPointOnCurve { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+}
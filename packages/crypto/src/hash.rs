@@ -0,0 +1,145 @@
+use alloc::vec::Vec;
+use blake2::Blake2b;
+use ripemd160::Ripemd160;
+use sha2::{Digest, Sha256, Sha512};
+use sha3::Keccak256;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashFunction {
+    Sha256,
+    Sha512,
+    Keccak256,
+    Ripemd160,
+    Blake2b,
+}
+
+/// Fixed gas charged for every hash regardless of input length.
+pub const BASE_GAS: u64 = 750_000_000;
+
+impl HashFunction {
+    /// Length of the digest in bytes.
+    pub fn output_len(self) -> usize {
+        match self {
+            HashFunction::Sha256 | HashFunction::Keccak256 => 32,
+            HashFunction::Sha512 | HashFunction::Blake2b => 64,
+            HashFunction::Ripemd160 => 20,
+        }
+    }
+
+    /// Number of input bytes consumed by one compression (or permutation) round.
+    pub fn block_len(self) -> usize {
+        match self {
+            HashFunction::Sha256 | HashFunction::Ripemd160 => 64,
+            HashFunction::Sha512 | HashFunction::Blake2b => 128,
+            HashFunction::Keccak256 => 136,
+        }
+    }
+
+    /// Gas for one block, using the VM's scale of 1 gas per femtosecond of running time.
+    pub fn block_gas(self) -> u64 {
+        match self {
+            HashFunction::Sha256 => 300_000_000,
+            HashFunction::Sha512 => 400_000_000,
+            HashFunction::Keccak256 => 400_000_000,
+            HashFunction::Ripemd160 => 250_000_000,
+            HashFunction::Blake2b => 200_000_000,
+        }
+    }
+
+    /// Gas for hashing `input_len` bytes. Padding always adds at least one block, so
+    /// an empty input is charged for one.
+    pub fn gas(self, input_len: usize) -> u64 {
+        let blocks = (input_len / self.block_len()) as u64 + 1;
+        BASE_GAS.saturating_add(blocks.saturating_mul(self.block_gas()))
+    }
+
+    pub fn digest(self, input: &[u8]) -> Vec<u8> {
+        match self {
+            HashFunction::Sha256 => sha256(input).to_vec(),
+            HashFunction::Sha512 => sha512(input).to_vec(),
+            HashFunction::Keccak256 => keccak256(input).to_vec(),
+            HashFunction::Ripemd160 => ripemd160(input).to_vec(),
+            HashFunction::Blake2b => blake2b(input).to_vec(),
+        }
+    }
+}
+
+pub fn sha256(input: &[u8]) -> [u8; 32] {
+    Sha256::digest(input).into()
+}
+
+pub fn sha512(input: &[u8]) -> [u8; 64] {
+    let mut output = [0u8; 64];
+    output.copy_from_slice(&Sha512::digest(input));
+    output
+}
+
+pub fn keccak256(input: &[u8]) -> [u8; 32] {
+    Keccak256::digest(input).into()
+}
+
+pub fn ripemd160(input: &[u8]) -> [u8; 20] {
+    Ripemd160::digest(input).into()
+}
+
+/// BLAKE2b with a 64-byte output and no key.
+pub fn blake2b(input: &[u8]) -> [u8; 64] {
+    let mut output = [0u8; 64];
+    output.copy_from_slice(&Blake2b::digest(input));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex::encode;
+
+    const ALL: [HashFunction; 5] = [
+        HashFunction::Sha256,
+        HashFunction::Sha512,
+        HashFunction::Keccak256,
+        HashFunction::Ripemd160,
+        HashFunction::Blake2b,
+    ];
+
+    #[test]
+    fn digest_test() {
+        assert_eq!(
+            encode(sha256(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            encode(sha512(b"abc")),
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+        );
+        assert_eq!(
+            encode(keccak256(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(encode(ripemd160(b"abc")), "8eb208f7e05d987a9b044a8e98c6b087f15a0bfc");
+        assert_eq!(
+            encode(blake2b(b"abc")),
+            "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+        );
+    }
+
+    #[test]
+    fn uniform_api_test() {
+        for function in ALL.iter().copied() {
+            let output = function.digest(b"band");
+            assert_eq!(output.len(), function.output_len());
+            assert_ne!(output, function.digest(b"other"));
+        }
+    }
+
+    #[test]
+    fn gas_test() {
+        for function in ALL.iter().copied() {
+            let block_len = function.block_len();
+            assert_eq!(function.gas(0), BASE_GAS + function.block_gas());
+            assert_eq!(function.gas(block_len - 1), function.gas(0));
+            assert_eq!(function.gas(block_len), BASE_GAS + 2 * function.block_gas());
+            assert!(function.gas(10 * block_len) > function.gas(block_len));
+        }
+    }
+}
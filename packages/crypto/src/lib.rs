@@ -1,5 +1,18 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 #[macro_use]
 extern crate lazy_static;
 
+pub mod bls12381;
 pub mod ecvrf;
+pub mod ed25519;
 pub mod error;
+pub mod ethereum;
+pub mod groth16;
+pub mod hash;
+pub mod merkle;
+#[cfg(feature = "poseidon")]
+pub mod poseidon;
+pub mod schnorr;
+pub mod secp256k1;
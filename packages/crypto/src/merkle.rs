@@ -0,0 +1,231 @@
+//! ICS-23 existence proof verification for Cosmos SDK state.
+//!
+//! A value in a Cosmos SDK chain is proven in two steps: an IAVL proof from the key to the
+//! root of its module store, and a Tendermint simple Merkle proof from the store name to
+//! the app hash, with the store root as its value. Proofs are protobuf-encoded
+//! `CommitmentProof`s, as returned by an ABCI query with `prove = true`.
+use crate::error::{CryptoError, CryptoResult};
+use crate::hash;
+use alloc::vec::Vec;
+use blake2::Blake2s;
+use ics23::{iavl_spec, tendermint_spec, CommitmentProof, HostFunctionsProvider, ProofSpec};
+use prost::Message;
+use sha2::Digest;
+
+// Backs ics23's hashing with the implementations in `hash`.
+struct HostFunctions;
+
+impl HostFunctionsProvider for HostFunctions {
+    fn sha2_256(message: &[u8]) -> [u8; 32] {
+        hash::sha256(message)
+    }
+
+    fn sha2_512(message: &[u8]) -> [u8; 64] {
+        hash::sha512(message)
+    }
+
+    fn sha2_512_truncated(message: &[u8]) -> [u8; 32] {
+        let mut output = [0u8; 32];
+        output.copy_from_slice(&hash::sha512(message)[..32]);
+        output
+    }
+
+    fn keccak_256(message: &[u8]) -> [u8; 32] {
+        hash::keccak256(message)
+    }
+
+    fn ripemd160(message: &[u8]) -> [u8; 20] {
+        hash::ripemd160(message)
+    }
+
+    fn blake2b_512(message: &[u8]) -> [u8; 64] {
+        hash::blake2b(message)
+    }
+
+    fn blake2s_256(message: &[u8]) -> [u8; 32] {
+        Blake2s::digest(message).into()
+    }
+
+    fn blake3(message: &[u8]) -> [u8; 32] {
+        blake3::hash(message).into()
+    }
+}
+
+fn decode_proof(proof: &[u8]) -> CryptoResult<CommitmentProof> {
+    CommitmentProof::decode(proof).map_err(|_| CryptoError::invalid_proof_format())
+}
+
+fn verify_membership(
+    proof: &CommitmentProof,
+    spec: &ProofSpec,
+    root: &[u8],
+    key: &[u8],
+    value: &[u8],
+) -> bool {
+    ics23::verify_membership::<HostFunctions>(proof, spec, &root.to_vec(), key, value)
+}
+
+/// Verifies that `key` maps to `value` in an IAVL tree with the given root.
+pub fn verify_iavl_membership(
+    proof: &[u8],
+    root: &[u8],
+    key: &[u8],
+    value: &[u8],
+) -> CryptoResult<bool> {
+    Ok(verify_membership(&decode_proof(proof)?, &iavl_spec(), root, key, value))
+}
+
+/// Verifies that `key` maps to `value` in the store named `store_key` under `app_hash`.
+/// `iavl_proof` proves the key within the store and `store_proof` proves the store root
+/// within the app hash.
+pub fn verify_chained_membership(
+    iavl_proof: &[u8],
+    store_proof: &[u8],
+    app_hash: &[u8],
+    store_key: &[u8],
+    key: &[u8],
+    value: &[u8],
+) -> CryptoResult<bool> {
+    let iavl_proof = decode_proof(iavl_proof)?;
+    let store_proof = decode_proof(store_proof)?;
+
+    // The store root is taken from the store proof and is only trusted once both
+    // proofs check out against it.
+    let store_root: Vec<u8> = match &store_proof.proof {
+        Some(ics23::commitment_proof::Proof::Exist(exist)) => exist.value.clone(),
+        _ => return Ok(false),
+    };
+
+    Ok(verify_membership(&iavl_proof, &iavl_spec(), &store_root, key, value)
+        && verify_membership(&store_proof, &tendermint_spec(), app_hash, store_key, &store_root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ics23::{commitment_proof, ExistenceProof, HashOp, InnerOp, LeafOp, LengthOp};
+
+    fn iavl_exist(key: &[u8], value: &[u8], sibling: [u8; 32]) -> ExistenceProof {
+        ExistenceProof {
+            key: key.to_vec(),
+            value: value.to_vec(),
+            // height 0, size 1, version 1 as zigzag varints.
+            leaf: Some(LeafOp {
+                hash: HashOp::Sha256.into(),
+                prehash_key: HashOp::NoHash.into(),
+                prehash_value: HashOp::Sha256.into(),
+                length: LengthOp::VarProto.into(),
+                prefix: vec![0, 2, 2],
+            }),
+            // height 1, size 2, version 1, followed by the length of the left child.
+            path: vec![InnerOp {
+                hash: HashOp::Sha256.into(),
+                prefix: vec![2, 4, 2, 32],
+                suffix: [&[32u8][..], &sibling].concat(),
+            }],
+        }
+    }
+
+    fn store_exist(store_key: &[u8], store_root: &[u8], sibling: [u8; 32]) -> ExistenceProof {
+        ExistenceProof {
+            key: store_key.to_vec(),
+            value: store_root.to_vec(),
+            leaf: Some(LeafOp {
+                hash: HashOp::Sha256.into(),
+                prehash_key: HashOp::NoHash.into(),
+                prehash_value: HashOp::Sha256.into(),
+                length: LengthOp::VarProto.into(),
+                prefix: vec![0],
+            }),
+            path: vec![InnerOp {
+                hash: HashOp::Sha256.into(),
+                prefix: vec![1],
+                suffix: sibling.to_vec(),
+            }],
+        }
+    }
+
+    fn encode(exist: &ExistenceProof) -> Vec<u8> {
+        CommitmentProof { proof: Some(commitment_proof::Proof::Exist(exist.clone())) }
+            .encode_to_vec()
+    }
+
+    fn root(exist: &ExistenceProof) -> Vec<u8> {
+        ics23::calculate_existence_root::<HostFunctions>(exist).unwrap()
+    }
+
+    #[test]
+    fn verify_iavl_membership_test() {
+        let exist = iavl_exist(b"price", b"100", [7u8; 32]);
+        let proof = encode(&exist);
+        let root = root(&exist);
+        assert!(verify_iavl_membership(&proof, &root, b"price", b"100").unwrap());
+        assert!(!verify_iavl_membership(&proof, &root, b"price", b"101").unwrap());
+        assert!(!verify_iavl_membership(&proof, &root, b"other", b"100").unwrap());
+        assert!(!verify_iavl_membership(&proof, &[0u8; 32], b"price", b"100").unwrap());
+    }
+
+    #[test]
+    fn verify_chained_membership_test() {
+        let iavl = iavl_exist(b"price", b"100", [7u8; 32]);
+        let store_root = root(&iavl);
+        let store = store_exist(b"oracle", &store_root, [9u8; 32]);
+        let app_hash = root(&store);
+        let (iavl_proof, store_proof) = (encode(&iavl), encode(&store));
+
+        assert!(verify_chained_membership(
+            &iavl_proof,
+            &store_proof,
+            &app_hash,
+            b"oracle",
+            b"price",
+            b"100"
+        )
+        .unwrap());
+        assert!(!verify_chained_membership(
+            &iavl_proof,
+            &store_proof,
+            &app_hash,
+            b"bank",
+            b"price",
+            b"100"
+        )
+        .unwrap());
+        assert!(!verify_chained_membership(
+            &iavl_proof,
+            &store_proof,
+            &app_hash,
+            b"oracle",
+            b"price",
+            b"999"
+        )
+        .unwrap());
+
+        // A store proof for a different store root must not vouch for the IAVL proof.
+        let other_store = store_exist(b"oracle", &[1u8; 32], [9u8; 32]);
+        assert!(!verify_chained_membership(
+            &iavl_proof,
+            &encode(&other_store),
+            &root(&other_store),
+            b"oracle",
+            b"price",
+            b"100"
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn verify_rejects_wrong_spec_test() {
+        // A Tendermint-style leaf does not satisfy the IAVL spec.
+        let store = store_exist(b"price", b"100", [9u8; 32]);
+        assert!(!verify_iavl_membership(&encode(&store), &root(&store), b"price", b"100").unwrap());
+    }
+
+    #[test]
+    fn decode_proof_fail_test() {
+        match verify_iavl_membership(&[0xff, 0xff], &[0u8; 32], b"k", b"v").err().unwrap() {
+            CryptoError::InvalidProofFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Poseidon over the BN254 scalar field with the circomlib parameters (x^5 S-box, 8 full
+//! rounds), matching `poseidon` in circomlib and circomlibjs. Inputs and output are
+//! 32-byte big-endian field elements.
+use crate::error::{CryptoError, CryptoResult};
+use ark_bn254::Fr;
+use light_poseidon::{Poseidon, PoseidonBytesHasher, PoseidonError};
+
+pub const INPUT_LEN: usize = 32;
+pub const OUTPUT_LEN: usize = 32;
+/// The circomlib parameters go up to a width of 13.
+pub const MAX_INPUTS: usize = 12;
+
+/// Hashes 1 to `MAX_INPUTS` field elements. An input that is not below the field modulus
+/// is rejected rather than reduced, so every element has exactly one encoding.
+pub fn hash(inputs: &[&[u8]]) -> CryptoResult<[u8; OUTPUT_LEN]> {
+    if inputs.is_empty() || inputs.len() > MAX_INPUTS {
+        return Err(CryptoError::generic_err("Poseidon takes between 1 and 12 inputs"));
+    }
+    if inputs.iter().any(|input| input.len() != INPUT_LEN) {
+        return Err(CryptoError::generic_err("Poseidon inputs must be 32 bytes"));
+    }
+
+    let mut poseidon = Poseidon::<Fr>::new_circom(inputs.len())
+        .map_err(|err| CryptoError::generic_err(err.to_string()))?;
+    poseidon.hash_bytes_be(inputs).map_err(|err| match err {
+        PoseidonError::InputLargerThanModulus => CryptoError::non_canonical_scalar(),
+        err => CryptoError::generic_err(err.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex::{decode, encode};
+
+    fn element(value: u8) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes[31] = value;
+        bytes
+    }
+
+    // Outputs of `poseidon` from circomlibjs.
+    #[test]
+    fn hash_from_circomlib_test() {
+        assert_eq!(
+            encode(hash(&[&element(1)]).unwrap()),
+            "29176100eaa962bdc1fe6c654d6a3c130e96a4d1168b33848b897dc502820133"
+        );
+        assert_eq!(
+            encode(hash(&[&element(1), &element(2)]).unwrap()),
+            "115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a"
+        );
+        assert_ne!(
+            hash(&[&element(1), &element(2)]).unwrap(),
+            hash(&[&element(2), &element(1)]).unwrap()
+        );
+    }
+
+    #[test]
+    fn hash_fail_test() {
+        match hash(&[]).err().unwrap() {
+            CryptoError::GenericErr { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+        let one = element(1);
+        let inputs = [&one[..]; MAX_INPUTS + 1];
+        assert!(hash(&inputs[..MAX_INPUTS]).is_ok());
+        match hash(&inputs).err().unwrap() {
+            CryptoError::GenericErr { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+        match hash(&[&element(1)[1..]]).err().unwrap() {
+            CryptoError::GenericErr { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+
+        // The BN254 scalar field modulus itself.
+        let modulus =
+            decode("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001").unwrap();
+        match hash(&[&modulus]).err().unwrap() {
+            CryptoError::NonCanonicalScalar { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::error::{CryptoError, CryptoResult};
+use core::convert::TryFrom;
+use k256::schnorr::{Signature, VerifyingKey};
+
+pub const PUBKEY_LEN: usize = 32;
+pub const SIGNATURE_LEN: usize = 64;
+
+/// Verifies a BIP-340 Schnorr signature over `message` against a 32-byte x-only public
+/// key. The message is used as-is, so callers following BIP-340 strictly should pass a
+/// 32-byte hash.
+pub fn verify(message: &[u8], signature: &[u8], pubkey: &[u8]) -> CryptoResult<bool> {
+    if signature.len() != SIGNATURE_LEN {
+        return Err(CryptoError::invalid_signature_format());
+    }
+    if pubkey.len() != PUBKEY_LEN {
+        return Err(CryptoError::invalid_pubkey_format());
+    }
+
+    // r must be a field element and s a scalar, as required by BIP-340. Signatures that
+    // fail this are rejected rather than reduced.
+    let signature = match Signature::try_from(signature) {
+        Ok(signature) => signature,
+        Err(_) => return Ok(false),
+    };
+    let pubkey =
+        VerifyingKey::from_bytes(pubkey).map_err(|_| CryptoError::invalid_point_on_curve())?;
+
+    Ok(pubkey.verify_raw(message, &signature).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex::decode;
+
+    const PUBKEY: &str = "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659";
+    const MESSAGE: &str = "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89";
+
+    fn check(pubkey: &str, message: &str, signature: &str) -> CryptoResult<bool> {
+        verify(&decode(message).unwrap(), &decode(signature).unwrap(), &decode(pubkey).unwrap())
+    }
+
+    // Vectors from https://github.com/bitcoin/bips/blob/master/bip-0340/test-vectors.csv
+    #[test]
+    fn verify_from_bip340_test() {
+        assert!(check(
+            "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA821525F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0"
+        )
+        .unwrap());
+        assert!(check(
+            PUBKEY,
+            MESSAGE,
+            "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE33418906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A"
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn verify_fail_from_bip340_test() {
+        // R has an odd y-coordinate.
+        assert!(!check(
+            PUBKEY,
+            MESSAGE,
+            "FFF97BD5755EEEA420453A14355235D382F6472F8568A18B2F057A14602975563CC27944640AC607CD107AE10923D9EF7A73C643E166BE5EBEAFA34B1AC553E2"
+        )
+        .unwrap());
+        // Public key is not on the curve.
+        match check(
+            "EEFDEA4CDB677750A420FEE807EACF21EB9898AE79B9768766E4FAA04A2D4A34",
+            MESSAGE,
+            "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E17776969E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+        )
+        .err()
+        .unwrap()
+        {
+            CryptoError::InvalidPointOnCurve { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+        // s is equal to the curve order.
+        assert!(!check(
+            PUBKEY,
+            MESSAGE,
+            "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141"
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn verify_format_fail_test() {
+        let signature = [0u8; 64];
+        match verify(&[], &signature[1..], &[0u8; 32]).err().unwrap() {
+            CryptoError::InvalidSignatureFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+        match verify(&[], &signature, &[0u8; 33]).err().unwrap() {
+            CryptoError::InvalidPubkeyFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+}
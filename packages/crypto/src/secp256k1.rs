@@ -0,0 +1,184 @@
+use crate::error::{CryptoError, CryptoResult};
+use alloc::string::ToString;
+use k256::ecdsa::signature::hazmat::PrehashVerifier;
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+pub const MESSAGE_HASH_LEN: usize = 32;
+pub const SIGNATURE_LEN: usize = 64;
+pub const COMPRESSED_PUBKEY_LEN: usize = 33;
+pub const UNCOMPRESSED_PUBKEY_LEN: usize = 65;
+
+fn read_message_hash(message_hash: &[u8]) -> CryptoResult<&[u8]> {
+    if message_hash.len() != MESSAGE_HASH_LEN {
+        return Err(CryptoError::invalid_hash_format());
+    }
+    Ok(message_hash)
+}
+
+fn read_signature(signature: &[u8]) -> CryptoResult<Signature> {
+    if signature.len() != SIGNATURE_LEN {
+        return Err(CryptoError::invalid_signature_format());
+    }
+    Signature::from_slice(signature).map_err(|_| CryptoError::invalid_signature_format())
+}
+
+fn read_pubkey(pubkey: &[u8]) -> CryptoResult<VerifyingKey> {
+    match (pubkey.len(), pubkey.first()) {
+        (COMPRESSED_PUBKEY_LEN, Some(0x02)) | (COMPRESSED_PUBKEY_LEN, Some(0x03)) => {}
+        (UNCOMPRESSED_PUBKEY_LEN, Some(0x04)) => {}
+        _ => return Err(CryptoError::invalid_pubkey_format()),
+    }
+    VerifyingKey::from_sec1_bytes(pubkey).map_err(|_| CryptoError::invalid_point_on_curve())
+}
+
+/// Verifies a 64-byte `r || s` signature over a 32-byte message hash against a SEC1
+/// encoded public key, either compressed (33 bytes) or uncompressed (65 bytes).
+/// High-S signatures are normalized before verification.
+pub fn verify(message_hash: &[u8], signature: &[u8], pubkey: &[u8]) -> CryptoResult<bool> {
+    let message_hash = read_message_hash(message_hash)?;
+    let mut signature = read_signature(signature)?;
+    let pubkey = read_pubkey(pubkey)?;
+
+    if let Some(normalized) = signature.normalize_s() {
+        signature = normalized;
+    }
+
+    Ok(pubkey.verify_prehash(message_hash, &signature).is_ok())
+}
+
+/// Recovers the uncompressed 65-byte public key that produced `signature` over
+/// `message_hash`. `recovery_param` is the y-parity of the signature's R point.
+pub fn recover_pubkey(
+    message_hash: &[u8],
+    signature: &[u8],
+    recovery_param: u8,
+) -> CryptoResult<[u8; UNCOMPRESSED_PUBKEY_LEN]> {
+    let message_hash = read_message_hash(message_hash)?;
+    let signature = read_signature(signature)?;
+    let recovery_id = match recovery_param {
+        0 | 1 => RecoveryId::from_byte(recovery_param).unwrap(),
+        _ => return Err(CryptoError::invalid_recovery_param()),
+    };
+
+    let pubkey = VerifyingKey::recover_from_prehash(message_hash, &signature, recovery_id)
+        .map_err(|err| CryptoError::generic_err(err.to_string()))?;
+    decompress_pubkey(pubkey.to_encoded_point(true).as_bytes())
+}
+
+/// Converts a compressed or uncompressed public key to its 33-byte compressed form.
+pub fn compress_pubkey(pubkey: &[u8]) -> CryptoResult<[u8; COMPRESSED_PUBKEY_LEN]> {
+    let mut compressed = [0u8; COMPRESSED_PUBKEY_LEN];
+    compressed.copy_from_slice(read_pubkey(pubkey)?.to_encoded_point(true).as_bytes());
+    Ok(compressed)
+}
+
+/// Converts a compressed or uncompressed public key to its 65-byte uncompressed form.
+pub fn decompress_pubkey(pubkey: &[u8]) -> CryptoResult<[u8; UNCOMPRESSED_PUBKEY_LEN]> {
+    let mut uncompressed = [0u8; UNCOMPRESSED_PUBKEY_LEN];
+    uncompressed.copy_from_slice(read_pubkey(pubkey)?.to_encoded_point(false).as_bytes());
+    Ok(uncompressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex::decode;
+    use k256::ecdsa::SigningKey;
+    use sha2::{Digest, Sha256};
+
+    // The generator point, i.e. the public key of secret key 1.
+    const G_COMPRESSED: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+    const G_UNCOMPRESSED: &str = "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+
+    fn sign(message: &[u8]) -> (Vec<u8>, Vec<u8>, u8, Vec<u8>) {
+        let signing_key = SigningKey::from_slice(&[0x42u8; 32]).unwrap();
+        let message_hash = Sha256::digest(message).to_vec();
+        let (signature, recovery_id) = signing_key.sign_prehash_recoverable(&message_hash).unwrap();
+        let pubkey = signing_key.verifying_key().to_encoded_point(false).as_bytes().to_vec();
+        (message_hash, signature.to_bytes().to_vec(), recovery_id.to_byte(), pubkey)
+    }
+
+    #[test]
+    fn compress_decompress_test() {
+        let compressed = decode(G_COMPRESSED).unwrap();
+        let uncompressed = decode(G_UNCOMPRESSED).unwrap();
+        assert_eq!(compress_pubkey(&uncompressed).unwrap().to_vec(), compressed);
+        assert_eq!(compress_pubkey(&compressed).unwrap().to_vec(), compressed);
+        assert_eq!(decompress_pubkey(&compressed).unwrap().to_vec(), uncompressed);
+        assert_eq!(decompress_pubkey(&uncompressed).unwrap().to_vec(), uncompressed);
+    }
+
+    #[test]
+    fn read_pubkey_fail_test() {
+        let mut wrong_prefix = decode(G_COMPRESSED).unwrap();
+        wrong_prefix[0] = 0x04;
+        let mut not_on_curve = decode(G_UNCOMPRESSED).unwrap();
+        not_on_curve[64] ^= 1;
+        match compress_pubkey(&wrong_prefix).err().unwrap() {
+            CryptoError::InvalidPubkeyFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+        match compress_pubkey(&[]).err().unwrap() {
+            CryptoError::InvalidPubkeyFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+        match compress_pubkey(&not_on_curve).err().unwrap() {
+            CryptoError::InvalidPointOnCurve { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+
+    #[test]
+    fn verify_test() {
+        let (message_hash, signature, _, pubkey) = sign(b"band");
+        assert!(verify(&message_hash, &signature, &pubkey).unwrap());
+        assert!(verify(&message_hash, &signature, &compress_pubkey(&pubkey).unwrap()).unwrap());
+
+        let other_hash = Sha256::digest(b"other");
+        assert!(!verify(&other_hash, &signature, &pubkey).unwrap());
+        assert!(!verify(&message_hash, &signature, &decode(G_UNCOMPRESSED).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn verify_high_s_test() {
+        let (message_hash, signature, _, pubkey) = sign(b"band");
+        let low_s = Signature::from_slice(&signature).unwrap();
+        let (r, s) = low_s.split_scalars();
+        let high_s = Signature::from_scalars(r, -*s).unwrap();
+        assert!(verify(&message_hash, &high_s.to_bytes(), &pubkey).unwrap());
+    }
+
+    #[test]
+    fn verify_fail_test() {
+        let (message_hash, signature, _, pubkey) = sign(b"band");
+        match verify(&message_hash[1..], &signature, &pubkey).err().unwrap() {
+            CryptoError::InvalidHashFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+        match verify(&message_hash, &signature[1..], &pubkey).err().unwrap() {
+            CryptoError::InvalidSignatureFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+        match verify(&message_hash, &[0u8; 64], &pubkey).err().unwrap() {
+            CryptoError::InvalidSignatureFormat { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+
+    #[test]
+    fn recover_pubkey_test() {
+        let (message_hash, signature, recovery_param, pubkey) = sign(b"band");
+        assert_eq!(
+            recover_pubkey(&message_hash, &signature, recovery_param).unwrap().to_vec(),
+            pubkey
+        );
+        assert_ne!(
+            recover_pubkey(&message_hash, &signature, 1 - recovery_param).unwrap().to_vec(),
+            pubkey
+        );
+        match recover_pubkey(&message_hash, &signature, 2).err().unwrap() {
+            CryptoError::InvalidRecoveryParam { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Attribute macros for oracle script entry points. These are the counterparts of the
+//! `prepare_entry_point!` and `execute_entry_point!` macros in owasm-kit, with errors that
+//! point at the offending part of the function signature.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{FnArg, ItemFn, ReturnType, Type};
+
+/// Exports the annotated function as the `prepare` entry point. The function must take
+/// the OBI-decodable input as its only argument and return nothing.
+#[proc_macro_attribute]
+pub fn oracle_prepare(attr: TokenStream, item: TokenStream) -> TokenStream {
+    expand(Phase::Prepare, attr.into(), item.into()).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+/// Exports the annotated function as the `execute` entry point. The function must take
+/// the OBI-decodable input as its only argument and return the OBI-encodable output.
+#[proc_macro_attribute]
+pub fn oracle_execute(attr: TokenStream, item: TokenStream) -> TokenStream {
+    expand(Phase::Execute, attr.into(), item.into()).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+#[derive(Clone, Copy)]
+enum Phase {
+    Prepare,
+    Execute,
+}
+
+fn expand(phase: Phase, attr: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    if !attr.is_empty() {
+        return Err(syn::Error::new_spanned(attr, "this attribute takes no arguments"));
+    }
+    let func: ItemFn = syn::parse2(item)?;
+    let input = input_type(&func)?;
+    let name = &func.sig.ident;
+    let decode = quote! {
+        <#input as ::owasm_kit::__private::obi::OBIDecode>::try_from_slice(
+            &::owasm_kit::__private::oei::get_calldata(),
+        )
+            .expect("failed to decode calldata")
+    };
+    let export = match (phase, &func.sig.output) {
+        (Phase::Prepare, ReturnType::Default) => quote! {
+            #[no_mangle]
+            pub fn prepare() {
+                #name(#decode);
+            }
+        },
+        (Phase::Prepare, ReturnType::Type(_, ty)) => {
+            return Err(syn::Error::new_spanned(ty, "prepare must not return a value"));
+        }
+        (Phase::Execute, ReturnType::Type(_, _)) => quote! {
+            #[no_mangle]
+            pub fn execute() {
+                ::owasm_kit::__private::oei::save_return_data(
+                    &::owasm_kit::__private::obi::OBIEncode::try_to_vec(&#name(#decode))
+                        .expect("failed to encode return data"),
+                );
+            }
+        },
+        (Phase::Execute, ReturnType::Default) => {
+            return Err(syn::Error::new_spanned(&func.sig, "execute must return the output"));
+        }
+    };
+    Ok(quote! {
+        #func
+        #export
+    })
+}
+
+// Checks the parts of the signature shared by both phases and returns the input type.
+fn input_type(func: &ItemFn) -> syn::Result<&Type> {
+    let sig = &func.sig;
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(syn::Error::new_spanned(asyncness, "entry points cannot be async"));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&sig.generics, "entry points cannot be generic"));
+    }
+    let mut inputs = sig.inputs.iter();
+    match (inputs.next(), inputs.next()) {
+        (Some(FnArg::Typed(arg)), None) => Ok(&arg.ty),
+        (Some(FnArg::Receiver(receiver)), _) => {
+            Err(syn::Error::new_spanned(receiver, "entry points cannot take self"))
+        }
+        _ => Err(syn::Error::new_spanned(
+            &sig.inputs,
+            "entry points must take the input as their only argument",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_err(phase: Phase, item: TokenStream2) -> String {
+        expand(phase, TokenStream2::new(), item).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn expand_test() {
+        let out = expand(
+            Phase::Execute,
+            quote!(),
+            quote!(
+                fn run(i: Input) -> Output {
+                    todo!()
+                }
+            ),
+        )
+        .unwrap()
+        .to_string();
+        assert!(out.contains("pub fn execute ()"));
+        assert!(out.contains("< Input as :: owasm_kit :: __private :: obi :: OBIDecode >"));
+        assert!(!out.contains(":: owasm_kit :: oei"));
+        let out = expand(
+            Phase::Prepare,
+            quote!(),
+            quote!(
+                fn ask(i: Input) {}
+            ),
+        )
+        .unwrap()
+        .to_string();
+        assert!(out.contains("pub fn prepare ()"));
+    }
+
+    #[test]
+    fn signature_error_test() {
+        assert_eq!(
+            expand_err(
+                Phase::Prepare,
+                quote!(
+                    fn ask(i: Input) -> u64 {
+                        0
+                    }
+                )
+            ),
+            "prepare must not return a value"
+        );
+        assert_eq!(
+            expand_err(
+                Phase::Execute,
+                quote!(
+                    fn run(i: Input) {}
+                )
+            ),
+            "execute must return the output"
+        );
+        assert_eq!(
+            expand_err(
+                Phase::Execute,
+                quote!(
+                    fn run(a: A, b: B) -> C {
+                        todo!()
+                    }
+                )
+            ),
+            "entry points must take the input as their only argument"
+        );
+        assert_eq!(
+            expand_err(
+                Phase::Prepare,
+                quote!(
+                    fn ask() {}
+                )
+            ),
+            "entry points must take the input as their only argument"
+        );
+        assert_eq!(
+            expand_err(
+                Phase::Prepare,
+                quote!(
+                    async fn ask(i: Input) {}
+                )
+            ),
+            "entry points cannot be async"
+        );
+        assert_eq!(
+            expand_err(
+                Phase::Prepare,
+                quote!(
+                    fn ask<T>(i: T) {}
+                )
+            ),
+            "entry points cannot be generic"
+        );
+        assert_eq!(
+            expand(
+                Phase::Prepare,
+                quote!(foo),
+                quote!(
+                    fn ask(i: Input) {}
+                )
+            )
+            .unwrap_err()
+            .to_string(),
+            "this attribute takes no arguments"
+        );
+    }
+}
@@ -0,0 +1,321 @@
+//! Ethereum ABI encoding of oracle script input and output, for scripts whose results are
+//! consumed by EVM contracts. A top-level tuple is encoded as a list of parameters, as
+//! Solidity's `abi.encode(a, b)` does, so the output can be read with
+//! `abi.decode(data, (A, B))` using the types given by `type_string`.
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::convert::TryFrom;
+use ethabi::{Address, ParamType, Token, Uint};
+
+/// A Rust type with a fixed ABI type.
+pub trait AbiType: Sized {
+    /// Returns the ABI type of the value.
+    fn param_type() -> ParamType;
+
+    /// Converts the value into an ABI token.
+    fn into_token(self) -> Token;
+
+    /// Converts an ABI token back into the value, or returns None if it has the wrong
+    /// type or is out of range.
+    fn from_token(token: Token) -> Option<Self>;
+}
+
+/// Bytes encoded as ABI `bytes` rather than as the `uint8[]` of a `Vec<u8>`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bytes(pub Vec<u8>);
+
+/// Encodes the value, with a top-level tuple encoded as a list of parameters.
+pub fn encode<T: AbiType>(value: T) -> Vec<u8> {
+    match value.into_token() {
+        Token::Tuple(tokens) => ethabi::encode(&tokens),
+        token => ethabi::encode(&[token]),
+    }
+}
+
+/// Decodes data written by `encode`, or returns None if it is not a valid encoding.
+pub fn decode<T: AbiType>(data: &[u8]) -> Option<T> {
+    let token = match T::param_type() {
+        ParamType::Tuple(types) => Token::Tuple(ethabi::decode(&types, data).ok()?),
+        ty => ethabi::decode(&[ty], data).ok()?.pop()?,
+    };
+    T::from_token(token)
+}
+
+/// Returns the canonical Solidity type of the value, such as `(string,uint64[],bytes)`.
+pub fn type_string<T: AbiType>() -> String {
+    T::param_type().to_string()
+}
+
+/// Returns the ABI schema of an oracle script with the given input and output types, in
+/// the same `input/output` form as `schema::obi_schema`, such as
+/// `(string,uint64)/(uint64[],bytes)`.
+pub fn abi_schema<I: AbiType, O: AbiType>() -> String {
+    format!("{}/{}", type_string::<I>(), type_string::<O>())
+}
+
+impl AbiType for bool {
+    fn param_type() -> ParamType {
+        ParamType::Bool
+    }
+
+    fn into_token(self) -> Token {
+        Token::Bool(self)
+    }
+
+    fn from_token(token: Token) -> Option<Self> {
+        match token {
+            Token::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl AbiType for String {
+    fn param_type() -> ParamType {
+        ParamType::String
+    }
+
+    fn into_token(self) -> Token {
+        Token::String(self)
+    }
+
+    fn from_token(token: Token) -> Option<Self> {
+        match token {
+            Token::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl AbiType for Bytes {
+    fn param_type() -> ParamType {
+        ParamType::Bytes
+    }
+
+    fn into_token(self) -> Token {
+        Token::Bytes(self.0)
+    }
+
+    fn from_token(token: Token) -> Option<Self> {
+        match token {
+            Token::Bytes(value) => Some(Bytes(value)),
+            _ => None,
+        }
+    }
+}
+
+impl AbiType for Address {
+    fn param_type() -> ParamType {
+        ParamType::Address
+    }
+
+    fn into_token(self) -> Token {
+        Token::Address(self)
+    }
+
+    fn from_token(token: Token) -> Option<Self> {
+        match token {
+            Token::Address(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl AbiType for Uint {
+    fn param_type() -> ParamType {
+        ParamType::Uint(256)
+    }
+
+    fn into_token(self) -> Token {
+        Token::Uint(self)
+    }
+
+    fn from_token(token: Token) -> Option<Self> {
+        match token {
+            Token::Uint(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! impl_abi_uint {
+    ($($t:ty => $bits:expr),+) => {
+        $(
+            impl AbiType for $t {
+                fn param_type() -> ParamType {
+                    ParamType::Uint($bits)
+                }
+
+                fn into_token(self) -> Token {
+                    Token::Uint(Uint::from(self))
+                }
+
+                fn from_token(token: Token) -> Option<Self> {
+                    match token {
+                        Token::Uint(value) if value.bits() <= $bits => {
+                            <$t>::try_from(value.as_u128()).ok()
+                        }
+                        _ => None,
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_abi_uint!(u8 => 8, u16 => 16, u32 => 32, u64 => 64, u128 => 128);
+
+// Signed integers are two's complement over 256 bits, so a negative value is the bitwise
+// complement of its own complement, which is nonnegative.
+macro_rules! impl_abi_int {
+    ($($t:ty => $bits:expr),+) => {
+        $(
+            impl AbiType for $t {
+                fn param_type() -> ParamType {
+                    ParamType::Int($bits)
+                }
+
+                fn into_token(self) -> Token {
+                    let value = self as i128;
+                    match value < 0 {
+                        true => Token::Int(!Uint::from(!value as u128)),
+                        false => Token::Int(Uint::from(value as u128)),
+                    }
+                }
+
+                fn from_token(token: Token) -> Option<Self> {
+                    match token {
+                        Token::Int(value) if value.bit(255) && (!value).bits() < $bits => {
+                            <$t>::try_from(!((!value).as_u128() as i128)).ok()
+                        }
+                        Token::Int(value) if value.bits() < $bits => {
+                            <$t>::try_from(value.as_u128()).ok()
+                        }
+                        _ => None,
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_abi_int!(i8 => 8, i16 => 16, i32 => 32, i64 => 64, i128 => 128);
+
+impl<T: AbiType> AbiType for Vec<T> {
+    fn param_type() -> ParamType {
+        ParamType::Array(Box::new(T::param_type()))
+    }
+
+    fn into_token(self) -> Token {
+        Token::Array(self.into_iter().map(T::into_token).collect())
+    }
+
+    fn from_token(token: Token) -> Option<Self> {
+        match token {
+            Token::Array(tokens) => tokens.into_iter().map(T::from_token).collect(),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! impl_abi_tuple {
+    ($(($($name:ident),+))+) => {
+        $(
+            impl<$($name: AbiType),+> AbiType for ($($name,)+) {
+                fn param_type() -> ParamType {
+                    ParamType::Tuple(vec![$($name::param_type()),+])
+                }
+
+                #[allow(non_snake_case)]
+                fn into_token(self) -> Token {
+                    let ($($name,)+) = self;
+                    Token::Tuple(vec![$($name.into_token()),+])
+                }
+
+                fn from_token(token: Token) -> Option<Self> {
+                    match token {
+                        Token::Tuple(tokens) => {
+                            let mut tokens = tokens.into_iter();
+                            let value = ($($name::from_token(tokens.next()?)?,)+);
+                            match tokens.next() {
+                                Some(_) => None,
+                                None => Some(value),
+                            }
+                        }
+                        _ => None,
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_abi_tuple! {
+    (A)
+    (A, B)
+    (A, B, C)
+    (A, B, C, D)
+    (A, B, C, D, E)
+    (A, B, C, D, E, F)
+    (A, B, C, D, E, F, G)
+    (A, B, C, D, E, F, G, H)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn type_string_test() {
+        assert_eq!(type_string::<u64>(), "uint64");
+        assert_eq!(type_string::<i32>(), "int32");
+        assert_eq!(type_string::<Bytes>(), "bytes");
+        assert_eq!(type_string::<Vec<u8>>(), "uint8[]");
+        assert_eq!(type_string::<Vec<Vec<String>>>(), "string[][]");
+        assert_eq!(
+            type_string::<(String, Vec<(u64, Bytes)>, (bool, Address))>(),
+            "(string,(uint64,bytes)[],(bool,address))"
+        );
+        assert_eq!(abi_schema::<(String, u64), Vec<Uint>>(), "(string,uint64)/uint256[]");
+    }
+
+    #[test]
+    fn encode_decode_test() {
+        let value = (
+            String::from("BTC"),
+            vec![(64000u64, Bytes(vec![0xbe, 0xeb])), (3200, Bytes(vec![]))],
+            (-42i64, vec![true, false]),
+        );
+        let data = encode(value.clone());
+        // A top-level tuple is encoded as parameters, so its first word is the offset of
+        // the string rather than of the tuple.
+        assert_eq!(data[31], 0x60);
+        assert_eq!(decode::<(String, Vec<(u64, Bytes)>, (i64, Vec<bool>))>(&data), Some(value));
+
+        assert_eq!(decode::<u64>(&encode(7u64)), Some(7));
+        assert_eq!(decode::<Bytes>(&encode(Bytes(vec![1, 2]))), Some(Bytes(vec![1, 2])));
+        assert_eq!(
+            decode::<Vec<String>>(&encode(vec![String::from("a")])),
+            Some(vec![String::from("a")])
+        );
+        assert_eq!(decode::<u64>(&[0]), None);
+    }
+
+    #[test]
+    fn integer_range_test() {
+        for value in [0i64, 1, -1, i64::MIN, i64::MAX].iter().copied() {
+            assert_eq!(i64::from_token(value.into_token()), Some(value));
+        }
+        assert_eq!(i8::from_token(Token::Int(Uint::from(127))), Some(127));
+        assert_eq!(i8::from_token(Token::Int(Uint::from(128))), None);
+        assert_eq!(i8::from_token((-128i64).into_token()), Some(-128));
+        assert_eq!(i8::from_token((-129i64).into_token()), None);
+        assert_eq!(u8::from_token(Token::Uint(Uint::from(255))), Some(255));
+        assert_eq!(u8::from_token(Token::Uint(Uint::from(256))), None);
+        assert_eq!(u64::from_token(Token::Int(Uint::from(1))), None);
+        assert_eq!(u128::from_token(u128::MAX.into_token()), Some(u128::MAX));
+        assert_eq!(i128::from_token(i128::MIN.into_token()), Some(i128::MIN));
+    }
+}
@@ -0,0 +1,139 @@
+use alloc::vec::Vec;
+use num::{Float, Num};
+
+use crate::ext::stats;
+
+/// A pipeline of filtering, quorum and aggregation steps over a data set, such as
+/// `Aggregate::new(data).filter_outliers_mad(3.0).require_min(5).median()`. Once a
+/// requirement fails, the remaining steps are skipped and the result is None.
+#[derive(Clone, Debug)]
+pub struct Aggregate<T> {
+    data: Option<Vec<T>>,
+}
+
+impl<T> Aggregate<T> {
+    pub fn new(data: Vec<T>) -> Self {
+        Aggregate { data: Some(data) }
+    }
+
+    /// Keeps only the values for which the predicate returns true.
+    pub fn filter<F>(self, predicate: F) -> Self
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.map(|data| Some(data.into_iter().filter(predicate).collect()))
+    }
+
+    /// Fails the pipeline unless at least `count` values remain.
+    pub fn require_min(self, count: usize) -> Self {
+        self.map(|data| if data.len() >= count { Some(data) } else { None })
+    }
+
+    /// Returns the remaining values, or None if a requirement failed.
+    pub fn into_vec(self) -> Option<Vec<T>> {
+        self.data
+    }
+
+    fn map<F>(self, f: F) -> Self
+    where
+        F: FnOnce(Vec<T>) -> Option<Vec<T>>,
+    {
+        Aggregate { data: self.data.and_then(f) }
+    }
+}
+
+impl<T> Aggregate<T>
+where
+    T: Float,
+{
+    /// See `stats::filter_outliers_iqr`.
+    pub fn filter_outliers_iqr(self, k: T) -> Self {
+        self.map(|data| Some(stats::filter_outliers_iqr(data, k)))
+    }
+
+    /// See `stats::filter_outliers_zscore`.
+    pub fn filter_outliers_zscore(self, threshold: T) -> Self {
+        self.map(|data| Some(stats::filter_outliers_zscore(data, threshold)))
+    }
+
+    /// See `stats::filter_outliers_mad`.
+    pub fn filter_outliers_mad(self, threshold: T) -> Self {
+        self.map(|data| Some(stats::filter_outliers_mad(data, threshold)))
+    }
+
+    /// See `stats::median_float`.
+    pub fn median(self) -> Option<T> {
+        stats::median_float(self.data?)
+    }
+}
+
+impl<T> Aggregate<T>
+where
+    T: Num,
+{
+    /// See `stats::average`.
+    pub fn average(self) -> Option<T> {
+        stats::average(self.data?)
+    }
+}
+
+impl<T> Aggregate<T>
+where
+    T: Ord + Num + Clone,
+{
+    /// See `stats::median_integer`.
+    pub fn median_integer(self) -> Option<T> {
+        stats::median_integer(self.data?)
+    }
+}
+
+impl<T> Aggregate<T>
+where
+    T: PartialEq,
+{
+    /// See `stats::majority`.
+    pub fn majority(self) -> Option<T> {
+        stats::majority(self.data?)
+    }
+}
+
+impl<T> Aggregate<T>
+where
+    T: Ord,
+{
+    /// See `stats::mode`.
+    pub fn mode(self) -> Option<T> {
+        stats::mode(self.data?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pipeline_test() {
+        let vals = vec![10.0, 11.0, 9.0, 10.5, 9.5, 1000.0];
+        assert_eq!(
+            Aggregate::new(vals.clone()).filter_outliers_mad(3.0).require_min(5).median(),
+            Some(10.0)
+        );
+        assert_eq!(
+            Aggregate::new(vals.clone()).filter_outliers_mad(3.0).require_min(6).median(),
+            None
+        );
+        assert_eq!(
+            Aggregate::new(vals).require_min(6).filter(|v| *v < 100.0).average(),
+            Some(10.0)
+        );
+    }
+
+    #[test]
+    fn requirement_failure_skips_later_steps_test() {
+        let agg = Aggregate::new(vec![1, 2, 3]).require_min(4).filter(|_| true);
+        assert_eq!(agg.clone().into_vec(), None);
+        assert_eq!(agg.clone().median_integer(), None);
+        assert_eq!(agg.majority(), None);
+        assert_eq!(Aggregate::new(vec!["a", "b", "a"]).require_min(3).mode(), Some("a"));
+    }
+}
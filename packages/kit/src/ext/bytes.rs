@@ -0,0 +1,74 @@
+use alloc::vec::Vec;
+use core::convert::TryInto;
+
+/// Returns the `len` bytes starting at `offset`, or None if they are out of bounds.
+pub fn slice(data: &[u8], offset: usize, len: usize) -> Option<&[u8]> {
+    data.get(offset..offset.checked_add(len)?)
+}
+
+/// Returns the `N` bytes starting at `offset` as an array, or None if they are out of
+/// bounds.
+pub fn array<const N: usize>(data: &[u8], offset: usize) -> Option<[u8; N]> {
+    slice(data, offset, N)?.try_into().ok()
+}
+
+/// Returns the concatenation of the given byte strings.
+pub fn concat(parts: &[&[u8]]) -> Vec<u8> {
+    parts.concat()
+}
+
+macro_rules! impl_read_int {
+    ($($t:ty => $be:ident, $le:ident;)+) => {
+        $(
+            #[doc = concat!("Reads a big-endian `", stringify!($t), "` at `offset`, or None if out of bounds.")]
+            pub fn $be(data: &[u8], offset: usize) -> Option<$t> {
+                array(data, offset).map(<$t>::from_be_bytes)
+            }
+
+            #[doc = concat!("Reads a little-endian `", stringify!($t), "` at `offset`, or None if out of bounds.")]
+            pub fn $le(data: &[u8], offset: usize) -> Option<$t> {
+                array(data, offset).map(<$t>::from_le_bytes)
+            }
+        )+
+    };
+}
+
+impl_read_int! {
+    u16 => read_u16_be, read_u16_le;
+    u32 => read_u32_be, read_u32_le;
+    u64 => read_u64_be, read_u64_le;
+    u128 => read_u128_be, read_u128_le;
+    i16 => read_i16_be, read_i16_le;
+    i32 => read_i32_be, read_i32_le;
+    i64 => read_i64_be, read_i64_le;
+    i128 => read_i128_be, read_i128_le;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slice_test() {
+        let data = [1, 2, 3, 4, 5];
+        assert_eq!(slice(&data, 1, 3), Some(&data[1..4]));
+        assert_eq!(slice(&data, 5, 0), Some(&[][..]));
+        assert_eq!(slice(&data, 3, 3), None);
+        assert_eq!(slice(&data, usize::MAX, 2), None);
+        assert_eq!(array::<2>(&data, 3), Some([4, 5]));
+        assert_eq!(array::<2>(&data, 4), None);
+        assert_eq!(concat(&[b"band", b"", b"chain"]), b"bandchain".to_vec());
+    }
+
+    #[test]
+    fn read_int_test() {
+        let data = [0xff, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08];
+        assert_eq!(read_u64_be(&data, 1), Some(0x0102030405060708));
+        assert_eq!(read_u64_le(&data, 1), Some(0x0807060504030201));
+        assert_eq!(read_u64_be(&data, 2), None);
+        assert_eq!(read_u16_be(&data, 0), Some(0xff01));
+        assert_eq!(read_i16_be(&data, 0), Some(-255));
+        assert_eq!(read_i32_le(&[0xff; 4], 0), Some(-1));
+        assert_eq!(read_u128_be(&[0; 16], 0), Some(0));
+    }
+}
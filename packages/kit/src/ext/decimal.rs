@@ -0,0 +1,266 @@
+use core::fmt;
+use core::ops::{Add, Div, Mul, Rem, Sub};
+use core::str::FromStr;
+
+use num::{Num, One, Zero};
+
+use crate::ext::math::mul_div_u128;
+
+/// An unsigned fixed-point number with `SCALE` decimal places, stored as a u128 mantissa.
+/// Arithmetic is exact integer arithmetic, so results are the same on every validator.
+/// It implements `num::Num`, so `stats::average` and `stats::median_integer` accept it.
+/// Like the primitive integers, the operators panic on overflow and division by zero,
+/// while the `checked_` methods return None instead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Decimal<const SCALE: u32 = 18>(u128);
+
+/// The error returned when parsing a `Decimal` fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseDecimalError;
+
+impl fmt::Display for ParseDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid decimal string")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseDecimalError {}
+
+impl<const SCALE: u32> Decimal<SCALE> {
+    /// The mantissa of one, 10^SCALE.
+    pub const ONE: u128 = 10u128.pow(SCALE);
+
+    /// Creates a decimal from its mantissa, i.e. `mantissa / 10^SCALE`.
+    pub const fn from_mantissa(mantissa: u128) -> Self {
+        Decimal(mantissa)
+    }
+
+    pub const fn mantissa(self) -> u128 {
+        self.0
+    }
+
+    /// Creates a decimal equal to the given integer, or None if it does not fit.
+    pub fn from_integer(value: u128) -> Option<Self> {
+        value.checked_mul(Self::ONE).map(Decimal)
+    }
+
+    /// Returns the integer part, rounded down.
+    pub fn trunc(self) -> u128 {
+        self.0 / Self::ONE
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(Decimal)
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Decimal)
+    }
+
+    /// Multiplies two decimals, rounding down to `SCALE` places.
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        mul_div_u128(self.0, rhs.0, Self::ONE).map(Decimal)
+    }
+
+    /// Divides two decimals, rounding down to `SCALE` places.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        if rhs.0 == 0 {
+            return None;
+        }
+        mul_div_u128(self.0, Self::ONE, rhs.0).map(Decimal)
+    }
+}
+
+impl<const SCALE: u32> FromStr for Decimal<SCALE> {
+    type Err = ParseDecimalError;
+
+    /// Parses a string of digits with an optional decimal point, such as `"1234.5678"`.
+    /// Digits beyond `SCALE` decimal places are truncated.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_decimal_scaled(s, SCALE).map(Decimal)
+    }
+}
+
+/// Parses a decimal string such as `"1234.5678"` into an integer scaled by `10^scale`,
+/// without going through f64. Digits beyond `scale` decimal places are truncated, so
+/// `parse_decimal_scaled("1234.5678", 2)` is `Ok(123456)`.
+pub fn parse_decimal_scaled(s: &str, scale: u32) -> Result<u128, ParseDecimalError> {
+    let (int, frac) = match s.find('.') {
+        Some(idx) => (&s[..idx], &s[idx + 1..]),
+        None => (s, ""),
+    };
+    if int.is_empty() && frac.is_empty() {
+        return Err(ParseDecimalError);
+    }
+    let mut mantissa: u128 = 0;
+    let digits =
+        int.bytes().chain(frac.bytes().chain(core::iter::repeat(b'0')).take(scale as usize));
+    for b in digits {
+        if !b.is_ascii_digit() {
+            return Err(ParseDecimalError);
+        }
+        mantissa = mantissa
+            .checked_mul(10)
+            .and_then(|m| m.checked_add((b - b'0') as u128))
+            .ok_or(ParseDecimalError)?;
+    }
+    // Truncated digits must still be digits.
+    if !frac.bytes().skip(scale as usize).all(|b| b.is_ascii_digit()) {
+        return Err(ParseDecimalError);
+    }
+    Ok(mantissa)
+}
+
+impl<const SCALE: u32> fmt::Display for Decimal<SCALE> {
+    /// Formats the decimal without trailing zeros, such as `"1234.5678"`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (int, mut frac) = (self.0 / Self::ONE, self.0 % Self::ONE);
+        if frac == 0 {
+            return write!(f, "{}", int);
+        }
+        let mut width = SCALE as usize;
+        while frac % 10 == 0 {
+            frac /= 10;
+            width -= 1;
+        }
+        write!(f, "{}.{:0width$}", int, frac, width = width)
+    }
+}
+
+impl<const SCALE: u32> Add for Decimal<SCALE> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self.checked_add(rhs).expect("decimal overflow")
+    }
+}
+
+impl<const SCALE: u32> Sub for Decimal<SCALE> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.checked_sub(rhs).expect("decimal overflow")
+    }
+}
+
+impl<const SCALE: u32> Mul for Decimal<SCALE> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        self.checked_mul(rhs).expect("decimal overflow")
+    }
+}
+
+impl<const SCALE: u32> Div for Decimal<SCALE> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        self.checked_div(rhs).expect("decimal overflow or division by zero")
+    }
+}
+
+impl<const SCALE: u32> Rem for Decimal<SCALE> {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self {
+        Decimal(self.0 % rhs.0)
+    }
+}
+
+impl<const SCALE: u32> Zero for Decimal<SCALE> {
+    fn zero() -> Self {
+        Decimal(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl<const SCALE: u32> One for Decimal<SCALE> {
+    fn one() -> Self {
+        Decimal(Self::ONE)
+    }
+}
+
+impl<const SCALE: u32> Num for Decimal<SCALE> {
+    type FromStrRadixErr = ParseDecimalError;
+
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        if radix != 10 {
+            return Err(ParseDecimalError);
+        }
+        s.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ext::stats;
+
+    type D = Decimal<18>;
+
+    fn d(s: &str) -> D {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_test() {
+        assert_eq!(d("1"), D::from_mantissa(1_000_000_000_000_000_000));
+        assert_eq!(d("1234.5678"), D::from_mantissa(1_234_567_800_000_000_000_000));
+        assert_eq!(d(".5"), d("0.5"));
+        assert_eq!(d("5."), d("5"));
+        assert_eq!(d("0.1234567890123456789"), D::from_mantissa(123_456_789_012_345_678));
+        assert_eq!(Decimal::<2>::from_str("12.345"), Ok(Decimal::<2>::from_mantissa(1234)));
+        for s in ["", ".", "-1", "+1", "1.2.3", "1e5", " 1", "0.1234567890123456789x"] {
+            assert_eq!(D::from_str(s), Err(ParseDecimalError), "{}", s);
+        }
+        assert_eq!(D::from_str("340282366920938463464"), Err(ParseDecimalError));
+    }
+
+    #[test]
+    fn parse_decimal_scaled_test() {
+        assert_eq!(parse_decimal_scaled("1234.5678", 9), Ok(1_234_567_800_000));
+        assert_eq!(parse_decimal_scaled("1234.5678", 2), Ok(123_456));
+        assert_eq!(parse_decimal_scaled("1234.5678", 0), Ok(1234));
+        assert_eq!(parse_decimal_scaled("0.1", 38), Ok(10u128.pow(37)));
+        assert_eq!(parse_decimal_scaled("1", 39), Err(ParseDecimalError));
+        assert_eq!(parse_decimal_scaled("1.2x", 9), Err(ParseDecimalError));
+    }
+
+    #[test]
+    fn display_test() {
+        assert_eq!(d("1234.5678").to_string(), "1234.5678");
+        assert_eq!(d("0.000000000000000001").to_string(), "0.000000000000000001");
+        assert_eq!(d("42").to_string(), "42");
+        assert_eq!(D::zero().to_string(), "0");
+    }
+
+    #[test]
+    fn arithmetic_test() {
+        assert_eq!(d("1.5") + d("2.25"), d("3.75"));
+        assert_eq!(d("3.75") - d("2.25"), d("1.5"));
+        assert_eq!(d("1000000") * d("1000000"), d("1000000000000"));
+        assert_eq!(d("0.1") * d("0.1"), d("0.01"));
+        assert_eq!(d("1") / d("3"), d("0.333333333333333333"));
+        assert_eq!(d("10") % d("3"), d("1"));
+        assert_eq!(d("1").checked_sub(d("2")), None);
+        assert_eq!(d("1").checked_div(D::zero()), None);
+        assert_eq!(D::from_mantissa(u128::MAX).checked_mul(d("2")), None);
+        assert_eq!(
+            D::from_mantissa(u128::MAX).checked_mul(d("0.5")),
+            Some(D::from_mantissa(u128::MAX / 2))
+        );
+        assert_eq!(D::from_integer(7).unwrap().trunc(), 7);
+        assert_eq!(D::from_integer(u128::MAX), None);
+    }
+
+    #[test]
+    fn stats_test() {
+        let vals = vec![d("100.5"), d("101.25"), d("99.75"), d("100.0")];
+        assert_eq!(stats::average(vals.clone()), Some(d("100.375")));
+        assert_eq!(stats::median_integer(vals), Some(d("100.25")));
+    }
+}
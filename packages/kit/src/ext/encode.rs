@@ -0,0 +1,154 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// The error returned when decoding hex or base64 text fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input length is not valid for the encoding.
+    InvalidLength,
+    /// The input contains a character outside the alphabet, or misplaced padding.
+    InvalidCharacter,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidLength => write!(f, "invalid input length"),
+            DecodeError::InvalidCharacter => write!(f, "invalid character"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// Encodes the bytes as lowercase hex without a prefix.
+pub fn hex_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len() * 2);
+    for b in data {
+        out.push(HEX_CHARS[(b >> 4) as usize] as char);
+        out.push(HEX_CHARS[(b & 0xf) as usize] as char);
+    }
+    out
+}
+
+/// Decodes hex text in either case, with an optional `0x` prefix.
+pub fn hex_decode(text: &str) -> Result<Vec<u8>, DecodeError> {
+    let text = text.strip_prefix("0x").unwrap_or(text).as_bytes();
+    #[allow(clippy::manual_is_multiple_of)]
+    if text.len() % 2 != 0 {
+        return Err(DecodeError::InvalidLength);
+    }
+    text.chunks(2).map(|pair| Ok(hex_value(pair[0])? << 4 | hex_value(pair[1])?)).collect()
+}
+
+fn hex_value(c: u8) -> Result<u8, DecodeError> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(DecodeError::InvalidCharacter),
+    }
+}
+
+/// Encodes the bytes as standard base64 with padding.
+pub fn base64_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_CHARS[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Decodes standard base64 text. Padding is optional, but unused trailing bits must be
+/// zero so that every byte string has exactly one accepted encoding.
+pub fn base64_decode(text: &str) -> Result<Vec<u8>, DecodeError> {
+    let text = text.as_bytes();
+    #[allow(clippy::manual_is_multiple_of)]
+    let padded = text.len() % 4 == 0 && text.ends_with(b"=");
+    let text = match padded {
+        true if text.ends_with(b"==") => &text[..text.len() - 2],
+        true => &text[..text.len() - 1],
+        false => text,
+    };
+    if text.len() % 4 == 1 {
+        return Err(DecodeError::InvalidLength);
+    }
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.chunks(4) {
+        let mut n = 0u32;
+        for (i, c) in chunk.iter().enumerate() {
+            n |= base64_value(*c)? << (18 - 6 * i);
+        }
+        let len = chunk.len() - 1;
+        if n & (0xffffff >> (8 * len)) != 0 {
+            return Err(DecodeError::InvalidCharacter);
+        }
+        out.extend((0..len).map(|i| (n >> (16 - 8 * i)) as u8));
+    }
+    Ok(out)
+}
+
+fn base64_value(c: u8) -> Result<u32, DecodeError> {
+    match c {
+        b'A'..=b'Z' => Ok((c - b'A') as u32),
+        b'a'..=b'z' => Ok((c - b'a' + 26) as u32),
+        b'0'..=b'9' => Ok((c - b'0' + 52) as u32),
+        b'+' => Ok(62),
+        b'/' => Ok(63),
+        _ => Err(DecodeError::InvalidCharacter),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_test() {
+        assert_eq!(hex_encode(b""), "");
+        assert_eq!(hex_encode(&[0x00, 0xab, 0xff]), "00abff");
+        assert_eq!(hex_decode("00abff"), Ok(vec![0x00, 0xab, 0xff]));
+        assert_eq!(hex_decode("0x00ABff"), Ok(vec![0x00, 0xab, 0xff]));
+        assert_eq!(hex_decode(""), Ok(vec![]));
+        assert_eq!(hex_decode("abc"), Err(DecodeError::InvalidLength));
+        assert_eq!(hex_decode("zz"), Err(DecodeError::InvalidCharacter));
+        assert_eq!(hex_decode("+1"), Err(DecodeError::InvalidCharacter));
+    }
+
+    #[test]
+    fn base64_test() {
+        let cases: &[(&[u8], &str)] = &[
+            (b"", ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foob", "Zm9vYg=="),
+            (b"fooba", "Zm9vYmE="),
+            (b"foobar", "Zm9vYmFy"),
+            (&[0xfb, 0xff], "+/8="),
+        ];
+        for (data, text) in cases {
+            assert_eq!(base64_encode(data), *text);
+            assert_eq!(base64_decode(text).as_deref(), Ok(*data));
+            assert_eq!(base64_decode(text.trim_end_matches('=')).as_deref(), Ok(*data));
+        }
+        assert_eq!(base64_decode("Zm9vY"), Err(DecodeError::InvalidLength));
+        assert_eq!(base64_decode("Zh=="), Err(DecodeError::InvalidCharacter));
+        assert_eq!(base64_decode("Zm=v"), Err(DecodeError::InvalidCharacter));
+        assert_eq!(base64_decode("Zm9\n"), Err(DecodeError::InvalidCharacter));
+        assert_eq!(base64_decode("Zg="), Err(DecodeError::InvalidCharacter));
+        assert_eq!(base64_decode("Zg==="), Err(DecodeError::InvalidLength));
+    }
+}
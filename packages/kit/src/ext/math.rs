@@ -0,0 +1,115 @@
+use num::traits::{CheckedAdd, CheckedMul, SaturatingAdd, SaturatingMul};
+
+/// Returns `a + b`, or None on overflow.
+pub fn checked_add<T>(a: T, b: T) -> Option<T>
+where
+    T: CheckedAdd,
+{
+    a.checked_add(&b)
+}
+
+/// Returns `a * b`, or None on overflow.
+pub fn checked_mul<T>(a: T, b: T) -> Option<T>
+where
+    T: CheckedMul,
+{
+    a.checked_mul(&b)
+}
+
+/// Returns `a + b`, clamped to the bounds of `T`.
+pub fn saturating_add<T>(a: T, b: T) -> T
+where
+    T: SaturatingAdd,
+{
+    a.saturating_add(&b)
+}
+
+/// Returns `a * b`, clamped to the bounds of `T`.
+pub fn saturating_mul<T>(a: T, b: T) -> T
+where
+    T: SaturatingMul,
+{
+    a.saturating_mul(&b)
+}
+
+/// Returns `a * b / d` rounded down, or None if `d` is zero or the result does not fit in
+/// a u64. The product is computed in u128, so `a * b` itself cannot overflow.
+pub fn mul_div(a: u64, b: u64, d: u64) -> Option<u64> {
+    if d == 0 {
+        return None;
+    }
+    let result = a as u128 * b as u128 / d as u128;
+    if result > u64::MAX as u128 {
+        return None;
+    }
+    Some(result as u64)
+}
+
+/// Like `mul_div`, but returns u64::MAX if the result does not fit. Panics if `d` is zero.
+pub fn saturating_mul_div(a: u64, b: u64, d: u64) -> u64 {
+    assert!(d != 0, "division by zero");
+    mul_div(a, b, d).unwrap_or(u64::MAX)
+}
+
+/// Returns `a * b / d` rounded down, or None if `d` is zero or the result does not fit in
+/// a u128. The product is computed in 256 bits, so it cannot overflow.
+pub fn mul_div_u128(a: u128, b: u128, d: u128) -> Option<u128> {
+    const MASK: u128 = u64::MAX as u128;
+    let (a1, a0, b1, b0) = (a >> 64, a & MASK, b >> 64, b & MASK);
+    let (p00, p01, p10, p11) = (a0 * b0, a0 * b1, a1 * b0, a1 * b1);
+    let mid = (p00 >> 64) + (p01 & MASK) + (p10 & MASK);
+    let lo = (p00 & MASK) | (mid << 64);
+    let hi = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+    if d == 0 || hi >= d {
+        return None;
+    }
+    // Binary long division of hi:lo by d. The remainder stays below d, so shifting it
+    // left can carry out at most one bit, which means it is at least d.
+    let (mut rem, mut quot) = (hi, 0u128);
+    for i in (0..128).rev() {
+        let carry = rem >> 127;
+        rem = (rem << 1) | ((lo >> i) & 1);
+        quot <<= 1;
+        if carry == 1 || rem >= d {
+            rem = rem.wrapping_sub(d);
+            quot |= 1;
+        }
+    }
+    Some(quot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_and_saturating_test() {
+        assert_eq!(checked_add(u64::MAX - 1, 1), Some(u64::MAX));
+        assert_eq!(checked_add(u64::MAX, 1), None);
+        assert_eq!(checked_mul(i64::MIN, -1), None);
+        assert_eq!(saturating_add(u8::MAX, 1), u8::MAX);
+        assert_eq!(saturating_add(i32::MIN, -1), i32::MIN);
+        assert_eq!(saturating_mul(u64::MAX, 2), u64::MAX);
+        assert_eq!(saturating_mul(i64::MIN, 2), i64::MIN);
+    }
+
+    #[test]
+    fn mul_div_test() {
+        assert_eq!(mul_div(u64::MAX, u64::MAX, u64::MAX), Some(u64::MAX));
+        assert_eq!(mul_div(1_000_000_000_000, 1_000_000, 3), Some(333_333_333_333_333_333));
+        assert_eq!(mul_div(u64::MAX, 2, 1), None);
+        assert_eq!(mul_div(1, 1, 0), None);
+        assert_eq!(saturating_mul_div(u64::MAX, 2, 1), u64::MAX);
+        assert_eq!(saturating_mul_div(10, 10, 3), 33);
+    }
+
+    #[test]
+    fn mul_div_u128_test() {
+        assert_eq!(mul_div_u128(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+        assert_eq!(mul_div_u128(u128::MAX, 3, 4), Some(u128::MAX / 4 * 3 + 2));
+        assert_eq!(mul_div_u128(1 << 100, 1 << 100, 1 << 90), Some(1 << 110));
+        assert_eq!(mul_div_u128(1 << 100, 1 << 100, 1 << 72), None);
+        assert_eq!(mul_div_u128(7, 3, 2), Some(10));
+        assert_eq!(mul_div_u128(7, 3, 0), None);
+    }
+}
@@ -1,36 +1,85 @@
 //! # Owasm Standard Library
 use crate::oei;
+use alloc::string::String;
+use alloc::vec::Vec;
 
+#[cfg(feature = "abi")]
+pub mod abi;
+pub mod aggregate;
+pub mod bytes;
 pub mod cmp;
+pub mod decimal;
+pub mod encode;
+pub mod math;
+pub mod parse;
+pub mod price;
+pub mod random;
+pub mod request;
+pub mod scale;
+#[cfg(feature = "std")]
+pub mod schema;
 pub mod stats;
 
+/// Returns an iterator over the reports of every asked validator for the given external
+/// ID, as the validator index, its report status, and the reported data if successful.
+/// Reports are only read from the host as the iterator advances.
+pub fn reports(eid: i64) -> impl Iterator<Item = (i64, oei::ExternalDataStatus, Option<Vec<u8>>)> {
+    (0..oei::get_ask_count()).map(move |vid| {
+        let (status, data) = oei::get_asked_external_data_with_status(eid, vid);
+        (vid, status, data)
+    })
+}
+
+/// Returns the report of every asked validator for the given external ID, as the validator
+/// index, its report status, and the unparsed data, for report formats that need custom
+/// decoding. The data is empty unless the report is successful.
+pub fn load_raw(eid: i64) -> Vec<(i64, oei::ExternalDataStatus, Vec<u8>)> {
+    reports(eid).map(|(vid, status, data)| (vid, status, data.unwrap_or_default())).collect()
+}
+
 /// Returns an iterator of raw reports for the given external ID with nonzero status.
 pub fn load_input_raw(eid: i64) -> impl Iterator<Item = String> {
-    (0..oei::get_ask_count()).filter_map(move |idx| oei::get_external_data(eid, idx).ok())
+    (0..oei::get_ask_count()).filter_map(move |vid| {
+        let data = oei::get_asked_external_data_with_status(eid, vid).1?;
+        String::from_utf8(data).ok()
+    })
 }
 
 /// Returns an iterator of raw data points for the given external ID, parsed into
-/// the parameterized type using `std::str::FromStr` trait. Skip data points
+/// the parameterized type using `parse::OracleParse` trait. Skip data points
 /// with nonzero status OR cannot be parsed.
 pub fn load_input<T>(eid: i64) -> impl Iterator<Item = T>
 where
-    T: std::str::FromStr,
+    T: parse::OracleParse,
+{
+    load_with(eid, T::parse)
+}
+
+/// Returns an iterator of data points for the given external ID, parsed by the given
+/// function, for data that `OracleParse` does not cover such as JSON payloads. Skip
+/// data points with nonzero status OR for which the function returns None.
+pub fn load_with<T, F>(eid: i64, mut parse: F) -> impl Iterator<Item = T>
+where
+    F: FnMut(&[u8]) -> Option<T>,
 {
-    load_input_raw(eid).filter_map(|e| e.trim_end().parse::<T>().ok())
+    (0..oei::get_ask_count()).filter_map(move |vid| {
+        let data = oei::get_asked_external_data_with_status(eid, vid).1?;
+        parse(&data)
+    })
 }
 
 /// Returns the average value of the given external ID, ignoring unsuccessful reports.
 pub fn load_average<T>(eid: i64) -> Option<T>
 where
-    T: std::str::FromStr + num::Num,
+    T: parse::OracleParse + num::Num,
 {
-    stats::average(load_input(eid).collect())
+    stats::average_iter(load_input(eid))
 }
 
 /// Returns the median value of the given external ID, ignoring unsuccessful reports.
 pub fn load_median_integer<T>(eid: i64) -> Option<T>
 where
-    T: std::str::FromStr + std::cmp::Ord + num::Num + num::NumCast,
+    T: parse::OracleParse + core::cmp::Ord + num::Num + Clone,
 {
     stats::median_integer(load_input(eid).collect())
 }
@@ -38,7 +87,7 @@ where
 /// Returns the median value of the given external ID, ignoring unsuccessful reports.
 pub fn load_median_float<T>(eid: i64) -> Option<T>
 where
-    T: std::str::FromStr + num::Float + num::NumCast,
+    T: parse::OracleParse + num::Float + num::NumCast,
 {
     stats::median_float(load_input(eid).collect())
 }
@@ -46,7 +95,169 @@ where
 /// Returns the majority value of the given external ID, ignoring unsuccessful reports.
 pub fn load_majority<T>(eid: i64) -> Option<T>
 where
-    T: std::str::FromStr + std::cmp::PartialEq,
+    T: parse::OracleParse + core::cmp::PartialEq,
 {
     stats::majority(load_input(eid).collect())
 }
+
+/// Same as `load_majority`, but OBI-decodes each report, so structured payloads can be
+/// consolidated by exact equality. Reports that fail to decode are ignored.
+#[cfg(feature = "std")]
+pub fn load_majority_obi<T>(eid: i64) -> Option<T>
+where
+    T: obi::OBIDecode + core::cmp::PartialEq,
+{
+    stats::majority(load_with(eid, |data| T::try_from_slice(data).ok()).collect())
+}
+
+/// Same as `load_median_integer`, but also returns the number of reports that contributed
+/// and the number of validators asked, so the script can report its confidence.
+pub fn load_median_integer_with_count<T>(eid: i64) -> Option<(T, usize, usize)>
+where
+    T: parse::OracleParse + core::cmp::Ord + num::Num + Clone,
+{
+    let data: Vec<T> = load_input(eid).collect();
+    let count = data.len();
+    Some((stats::median_integer(data)?, count, oei::get_ask_count() as usize))
+}
+
+/// Same as `load_median_float`, but also returns the number of reports that contributed
+/// and the number of validators asked, so the script can report its confidence.
+pub fn load_median_float_with_count<T>(eid: i64) -> Option<(T, usize, usize)>
+where
+    T: parse::OracleParse + num::Float + num::NumCast,
+{
+    let data: Vec<T> = load_input(eid).collect();
+    let count = data.len();
+    Some((stats::median_float(data)?, count, oei::get_ask_count() as usize))
+}
+
+/// Same as `load_majority`, but also returns the number of reports that agree with the
+/// majority value and the number of validators asked.
+pub fn load_majority_with_count<T>(eid: i64) -> Option<(T, usize, usize)>
+where
+    T: parse::OracleParse + core::cmp::PartialEq + Clone,
+{
+    let data: Vec<T> = load_input(eid).collect();
+    let value = stats::majority(data.clone())?;
+    let count = data.iter().filter(|v| **v == value).count();
+    Some((value, count, oei::get_ask_count() as usize))
+}
+
+/// Same as `load_majority`, but only returns the majority value if it makes up at least
+/// `min_ratio` of the successfully parsed reports, such as `2.0 / 3.0` for a two-thirds
+/// super-majority. A strict majority is always required, whatever the ratio.
+pub fn load_majority_with_quorum<T>(eid: i64, min_ratio: f64) -> Option<T>
+where
+    T: parse::OracleParse + core::cmp::PartialEq + Clone,
+{
+    let data: Vec<T> = load_input(eid).collect();
+    let value = stats::majority(data.clone())?;
+    let count = data.iter().filter(|v| **v == value).count();
+    if (count as f64) < min_ratio * data.len() as f64 {
+        return None;
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn set_reports(reports: &[(i64, &str)]) {
+        let mut backend = oei::MemoryBackend::new(64);
+        backend.ask_count = reports.len() as i64;
+        for (vid, &(status, data)) in reports.iter().enumerate() {
+            backend.reports.insert((1, vid as i64), (status, data.as_bytes().to_vec()));
+        }
+        oei::set_backend(Rc::new(backend));
+    }
+
+    #[test]
+    fn load_raw_test() {
+        set_reports(&[(0, "\x01\x02"), (1, "error"), (-1, "")]);
+        assert_eq!(
+            load_raw(1),
+            vec![
+                (0, oei::ExternalDataStatus::Success, vec![1, 2]),
+                (1, oei::ExternalDataStatus::Failure(1), vec![]),
+                (2, oei::ExternalDataStatus::Pending, vec![]),
+            ]
+        );
+        oei::clear_backend();
+    }
+
+    #[test]
+    fn load_with_test() {
+        set_reports(&[(0, r#"{"price": 10}"#), (0, r#"{"price": 30}"#), (1, "{}"), (0, "{}")]);
+        let prices = load_with(1, |data| parse::json_path(data, "price").ok()?.as_u64());
+        assert_eq!(stats::median_integer(prices.collect()), Some(20));
+        oei::clear_backend();
+    }
+
+    #[test]
+    fn load_majority_obi_test() {
+        use obi::OBIEncode;
+
+        let (a, b) = (vec![100u64, 7].try_to_vec().unwrap(), vec![101u64, 7].try_to_vec().unwrap());
+        let mut backend = oei::MemoryBackend::new(64);
+        backend.ask_count = 4;
+        for (vid, data) in vec![a.clone(), b, a, vec![0xff]].into_iter().enumerate() {
+            backend.reports.insert((1, vid as i64), (0, data));
+        }
+        oei::set_backend(Rc::new(backend));
+        assert_eq!(load_majority_obi::<Vec<u64>>(1), Some(vec![100, 7]));
+        assert_eq!(load_majority_obi::<Vec<u64>>(2), None);
+        oei::clear_backend();
+    }
+
+    #[test]
+    fn load_average_test() {
+        set_reports(&[(0, "10\n"), (0, "30"), (1, "999"), (0, "x"), (-1, "")]);
+        assert_eq!(load_average::<i64>(1), Some(20));
+        assert_eq!(load_average::<f64>(1), Some(20.0));
+        assert_eq!(load_average::<i64>(2), None);
+        oei::clear_backend();
+    }
+
+    #[test]
+    fn load_median_test() {
+        set_reports(&[(0, "10"), (0, "40"), (1, "999"), (0, "20"), (0, "30")]);
+        assert_eq!(load_median_integer::<i64>(1), Some(25));
+        assert_eq!(load_median_float::<f64>(1), Some(25.0));
+        set_reports(&[(0, "2.5"), (0, "nan?"), (0, "1.5"), (0, "0.5")]);
+        assert_eq!(load_median_float::<f64>(1), Some(1.5));
+        assert_eq!(load_median_integer::<i64>(1), None);
+        set_reports(&[(0, "3\n"), (0, "1"), (0, "2")]);
+        assert_eq!(load_median_integer::<usize>(1), Some(2));
+        oei::clear_backend();
+    }
+
+    #[test]
+    fn load_majority_with_quorum_test() {
+        set_reports(&[(0, "a"), (0, "a"), (0, "b"), (1, "b"), (-1, "")]);
+        assert_eq!(load_majority_with_quorum::<String>(1, 0.5), Some(String::from("a")));
+        assert_eq!(load_majority_with_quorum::<String>(1, 2.0 / 3.0), Some(String::from("a")));
+        assert_eq!(load_majority_with_quorum::<String>(1, 0.7), None);
+        set_reports(&[(0, "a"), (0, "b"), (0, "c")]);
+        assert_eq!(load_majority_with_quorum::<String>(1, 0.3), None);
+        assert_eq!(load_majority::<char>(1), None);
+        set_reports(&[(0, "a\n"), (0, "b"), (0, "a")]);
+        assert_eq!(load_majority::<char>(1), Some('a'));
+        oei::clear_backend();
+    }
+
+    #[test]
+    fn load_with_count_test() {
+        set_reports(&[(0, "10\n"), (0, "30"), (1, "999"), (0, "x"), (0, "20")]);
+        assert_eq!(load_median_integer_with_count::<i64>(1), Some((20, 3, 5)));
+        assert_eq!(load_median_float_with_count::<f64>(1), Some((20.0, 3, 5)));
+        assert_eq!(load_majority_with_count::<i64>(1), None);
+
+        set_reports(&[(0, "a"), (0, "a"), (0, "b"), (126, "a")]);
+        assert_eq!(load_majority_with_count::<String>(1), Some((String::from("a"), 2, 4)));
+        assert_eq!(load_median_float_with_count::<f64>(2), None);
+        oei::clear_backend();
+    }
+}
@@ -0,0 +1,121 @@
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::str::FromStr;
+
+use super::ParseError;
+
+/// A record type that can be built from the fields of a delimited line. Implemented for
+/// tuples of up to eight `FromStr` types.
+pub trait FromFields: Sized {
+    /// The number of fields in a record.
+    const COUNT: usize;
+
+    fn from_fields(fields: &[&str]) -> Result<Self, ParseError>;
+}
+
+fn parse_field<T: FromStr>(fields: &[&str], index: usize) -> Result<T, ParseError> {
+    fields[index].parse().map_err(|_| ParseError::InvalidField(index))
+}
+
+macro_rules! impl_from_fields {
+    ($count:expr; $($t:ident $idx:tt),+) => {
+        impl<$($t: FromStr),+> FromFields for ($($t,)+) {
+            const COUNT: usize = $count;
+
+            fn from_fields(fields: &[&str]) -> Result<Self, ParseError> {
+                Ok(($(parse_field::<$t>(fields, $idx)?,)+))
+            }
+        }
+    };
+}
+
+impl_from_fields!(1; A 0);
+impl_from_fields!(2; A 0, B 1);
+impl_from_fields!(3; A 0, B 1, C 2);
+impl_from_fields!(4; A 0, B 1, C 2, D 3);
+impl_from_fields!(5; A 0, B 1, C 2, D 3, E 4);
+impl_from_fields!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+impl_from_fields!(7; A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_from_fields!(8; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+/// Splits a data source's output on the given delimiter and parses each field into the
+/// matching tuple element, such as `split_typed::<(f64, u64, String)>(b"1.5,42,BTC", ',')`.
+/// Surrounding whitespace, including a trailing newline, is trimmed from every field.
+pub fn split_typed<T>(bytes: &[u8], delimiter: char) -> Result<T, ParseError>
+where
+    T: FromFields,
+{
+    let text = core::str::from_utf8(bytes).map_err(|_| ParseError::InvalidUtf8)?;
+    let fields: Vec<&str> = text.split(delimiter).map(str::trim).collect();
+    if fields.len() != T::COUNT {
+        return Err(ParseError::FieldCount { expected: T::COUNT, found: fields.len() });
+    }
+    T::from_fields(&fields)
+}
+
+/// Extracts exactly `N` values separated by whitespace and/or commas, such as a line of
+/// `"64123.45, 1200.5 1718000000"` from a data source that reports several metrics.
+pub fn extract_values<T, const N: usize>(bytes: &[u8]) -> Result<[T; N], ParseError>
+where
+    T: FromStr,
+{
+    let text = core::str::from_utf8(bytes).map_err(|_| ParseError::InvalidUtf8)?;
+    let fields: Vec<&str> =
+        text.split(|c: char| c == ',' || c.is_whitespace()).filter(|f| !f.is_empty()).collect();
+    if fields.len() != N {
+        return Err(ParseError::FieldCount { expected: N, found: fields.len() });
+    }
+    let values = (0..N).map(|i| parse_field(&fields, i)).collect::<Result<Vec<T>, _>>()?;
+    Ok(values.try_into().unwrap_or_else(|_| unreachable!()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_typed_test() {
+        assert_eq!(
+            split_typed::<(f64, u64, String)>(b"1.5,42,BTC\n", ','),
+            Ok((1.5, 42, "BTC".into()))
+        );
+        assert_eq!(split_typed::<(i64, i64)>(b"-1 | 2", '|'), Ok((-1, 2)));
+        assert_eq!(split_typed::<(String,)>(b"", ','), Ok(("".into(),)));
+        assert_eq!(
+            split_typed::<(u8, u8, u8, u8, u8, u8, u8, u8)>(b"1,2,3,4,5,6,7,8", ','),
+            Ok((1, 2, 3, 4, 5, 6, 7, 8))
+        );
+    }
+
+    #[test]
+    fn split_typed_error_test() {
+        assert_eq!(
+            split_typed::<(f64, u64)>(b"1.5,42,BTC", ','),
+            Err(ParseError::FieldCount { expected: 2, found: 3 })
+        );
+        assert_eq!(
+            split_typed::<(f64, u64, String)>(b"1.5", ','),
+            Err(ParseError::FieldCount { expected: 3, found: 1 })
+        );
+        assert_eq!(
+            split_typed::<(f64, u64, String)>(b"1.5,-42,BTC", ','),
+            Err(ParseError::InvalidField(1))
+        );
+        assert_eq!(split_typed::<(f64,)>(b"\xff", ','), Err(ParseError::InvalidUtf8));
+    }
+
+    #[test]
+    fn extract_values_test() {
+        assert_eq!(
+            extract_values::<f64, 3>(b"64123.45, 1200.5 1718000000\n"),
+            Ok([64123.45, 1200.5, 1718000000.0])
+        );
+        assert_eq!(extract_values::<u64, 2>(b"1,2"), Ok([1, 2]));
+        assert_eq!(extract_values::<u64, 0>(b" \n"), Ok([]));
+        assert_eq!(
+            extract_values::<u64, 2>(b"1, 2, 3"),
+            Err(ParseError::FieldCount { expected: 2, found: 3 })
+        );
+        assert_eq!(extract_values::<u64, 2>(b"1 x"), Err(ParseError::InvalidField(1)));
+    }
+}
@@ -0,0 +1,421 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::ParseError;
+
+// Deeper documents are rejected rather than risk exhausting the wasm stack.
+const MAX_DEPTH: usize = 128;
+
+/// A parsed JSON value. Numbers keep their original text, so integers and decimals wider
+/// than an f64 can still be read exactly, e.g. with `Decimal::from_str`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    /// The members of an object, in document order.
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Parses a complete JSON document.
+    pub fn parse(bytes: &[u8]) -> Result<Self, ParseError> {
+        let text = core::str::from_utf8(bytes).map_err(|_| ParseError::InvalidUtf8)?;
+        let mut parser = Parser { text, pos: 0 };
+        let value = parser.value(0)?;
+        parser.skip_whitespace();
+        if parser.pos != text.len() {
+            return Err(ParseError::InvalidJson);
+        }
+        Ok(value)
+    }
+
+    /// Returns the member with the given key if this is an object. If the key appears more
+    /// than once, the last member wins.
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(members) => {
+                members.iter().rev().find(|(k, _)| k == key).map(|m| &m.1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the element at the given index if this is an array.
+    pub fn index(&self, index: usize) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Array(elems) => elems.get(index),
+            _ => None,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, JsonValue::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JsonValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[JsonValue]> {
+        match self {
+            JsonValue::Array(elems) => Some(elems),
+            _ => None,
+        }
+    }
+
+    /// Returns the original text of a number.
+    pub fn as_number(&self) -> Option<&str> {
+        match self {
+            JsonValue::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number()?.parse().ok()
+    }
+
+    /// Returns the number as an i64 if it is an integer in range.
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number()?.parse().ok()
+    }
+
+    /// Returns the number as a u64 if it is a non-negative integer in range.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number()?.parse().ok()
+    }
+}
+
+/// Parses a JSON document and returns the value at the given path, such as `"a.b[0].c"`.
+/// Keys are separated by `.` and array indices are written in brackets. An empty path
+/// returns the whole document.
+pub fn json_path(bytes: &[u8], path: &str) -> Result<JsonValue, ParseError> {
+    let doc = JsonValue::parse(bytes)?;
+    let mut value = &doc;
+    for segment in parse_path(path)? {
+        let next = match segment {
+            Segment::Key(key) => value.get(key),
+            Segment::Index(index) => value.index(index),
+        };
+        value = next.ok_or(ParseError::NotFound)?;
+    }
+    Ok(value.clone())
+}
+
+enum Segment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+fn parse_path(path: &str) -> Result<Vec<Segment<'_>>, ParseError> {
+    let mut segments = Vec::new();
+    if path.is_empty() {
+        return Ok(segments);
+    }
+    for part in path.split('.') {
+        let (key, mut rest) = match part.find('[') {
+            Some(idx) => (&part[..idx], &part[idx..]),
+            None => (part, ""),
+        };
+        // Only the very first part may start with an index, as in "[0].a".
+        if !key.is_empty() {
+            segments.push(Segment::Key(key));
+        } else if !segments.is_empty() || rest.is_empty() {
+            return Err(ParseError::InvalidPath);
+        }
+        while !rest.is_empty() {
+            let end = rest.find(']').ok_or(ParseError::InvalidPath)?;
+            let digits = &rest[1..end];
+            if !rest.starts_with('[')
+                || digits.is_empty()
+                || !digits.bytes().all(|b| b.is_ascii_digit())
+            {
+                return Err(ParseError::InvalidPath);
+            }
+            segments.push(Segment::Index(digits.parse().map_err(|_| ParseError::InvalidPath)?));
+            rest = &rest[end + 1..];
+        }
+    }
+    Ok(segments)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Result<u8, ParseError> {
+        let b = self.peek().ok_or(ParseError::InvalidJson)?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), ParseError> {
+        if self.next()? != b {
+            return Err(ParseError::InvalidJson);
+        }
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn literal(&mut self, lit: &str, value: JsonValue) -> Result<JsonValue, ParseError> {
+        if !self.text[self.pos..].starts_with(lit) {
+            return Err(ParseError::InvalidJson);
+        }
+        self.pos += lit.len();
+        Ok(value)
+    }
+
+    fn value(&mut self, depth: usize) -> Result<JsonValue, ParseError> {
+        if depth > MAX_DEPTH {
+            return Err(ParseError::InvalidJson);
+        }
+        self.skip_whitespace();
+        match self.peek().ok_or(ParseError::InvalidJson)? {
+            b'n' => self.literal("null", JsonValue::Null),
+            b't' => self.literal("true", JsonValue::Bool(true)),
+            b'f' => self.literal("false", JsonValue::Bool(false)),
+            b'"' => Ok(JsonValue::String(self.string()?)),
+            b'[' => {
+                self.pos += 1;
+                let mut elems = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                    return Ok(JsonValue::Array(elems));
+                }
+                loop {
+                    elems.push(self.value(depth + 1)?);
+                    self.skip_whitespace();
+                    match self.next()? {
+                        b',' => continue,
+                        b']' => return Ok(JsonValue::Array(elems)),
+                        _ => return Err(ParseError::InvalidJson),
+                    }
+                }
+            }
+            b'{' => {
+                self.pos += 1;
+                let mut members = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(b'}') {
+                    self.pos += 1;
+                    return Ok(JsonValue::Object(members));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let key = self.string()?;
+                    self.skip_whitespace();
+                    self.expect(b':')?;
+                    members.push((key, self.value(depth + 1)?));
+                    self.skip_whitespace();
+                    match self.next()? {
+                        b',' => continue,
+                        b'}' => return Ok(JsonValue::Object(members)),
+                        _ => return Err(ParseError::InvalidJson),
+                    }
+                }
+            }
+            _ => self.number(),
+        }
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    fn number(&mut self) -> Result<JsonValue, ParseError> {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        let int_start = self.pos;
+        let int_len = self.digits();
+        if int_len == 0 || (int_len > 1 && self.text.as_bytes()[int_start] == b'0') {
+            return Err(ParseError::InvalidJson);
+        }
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            if self.digits() == 0 {
+                return Err(ParseError::InvalidJson);
+            }
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.pos += 1;
+            }
+            if self.digits() == 0 {
+                return Err(ParseError::InvalidJson);
+            }
+        }
+        Ok(JsonValue::Number(self.text[start..self.pos].into()))
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        self.expect(b'"')?;
+        let mut out = String::new();
+        loop {
+            // Copy everything up to the next quote or escape in one go. Both are ASCII, so
+            // the slice boundaries fall on character boundaries.
+            let rest = &self.text[self.pos..];
+            let end = rest.find(['"', '\\']).ok_or(ParseError::InvalidJson)?;
+            if rest[..end].chars().any(|c| c < ' ') {
+                return Err(ParseError::InvalidJson);
+            }
+            out.push_str(&rest[..end]);
+            self.pos += end;
+            if self.next()? == b'"' {
+                return Ok(out);
+            }
+            let c = match self.next()? {
+                b'"' => '"',
+                b'\\' => '\\',
+                b'/' => '/',
+                b'b' => '\u{8}',
+                b'f' => '\u{c}',
+                b'n' => '\n',
+                b'r' => '\r',
+                b't' => '\t',
+                b'u' => self.unicode_escape()?,
+                _ => return Err(ParseError::InvalidJson),
+            };
+            out.push(c);
+        }
+    }
+
+    // Decodes the code point of a `\u` escape whose `\u` has been consumed, joining a
+    // surrogate pair if needed.
+    fn unicode_escape(&mut self) -> Result<char, ParseError> {
+        let high = self.hex4()?;
+        let code = match high {
+            0xD800..=0xDBFF => {
+                self.expect(b'\\')?;
+                self.expect(b'u')?;
+                let low = self.hex4()?;
+                if !(0xDC00..=0xDFFF).contains(&low) {
+                    return Err(ParseError::InvalidJson);
+                }
+                0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+            }
+            _ => high,
+        };
+        char::from_u32(code).ok_or(ParseError::InvalidJson)
+    }
+
+    fn hex4(&mut self) -> Result<u32, ParseError> {
+        let hex = self.text.get(self.pos..self.pos + 4).ok_or(ParseError::InvalidJson)?;
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseError::InvalidJson);
+        }
+        self.pos += 4;
+        u32::from_str_radix(hex, 16).map_err(|_| ParseError::InvalidJson)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRICES: &[u8] = br#"{
+        "status": "ok",
+        "data": {"quotes": [{"symbol": "BTC", "price": 64123.45}, {"symbol": "ETH", "price": 3210}]},
+        "volume": 123456789012345678901234567890,
+        "stale": false,
+        "note": null
+    }"#;
+
+    #[test]
+    fn json_path_test() {
+        assert_eq!(json_path(PRICES, "status"), Ok(JsonValue::String("ok".into())));
+        assert_eq!(json_path(PRICES, "data.quotes[0].price").unwrap().as_f64(), Some(64123.45));
+        assert_eq!(json_path(PRICES, "data.quotes[1].price").unwrap().as_u64(), Some(3210));
+        assert_eq!(json_path(PRICES, "data.quotes[1].symbol").unwrap().as_str(), Some("ETH"));
+        assert_eq!(
+            json_path(PRICES, "volume").unwrap().as_number(),
+            Some("123456789012345678901234567890")
+        );
+        assert_eq!(json_path(PRICES, "volume").unwrap().as_u64(), None);
+        assert_eq!(json_path(PRICES, "stale").unwrap().as_bool(), Some(false));
+        assert!(json_path(PRICES, "note").unwrap().is_null());
+        assert_eq!(json_path(PRICES, "data.quotes").unwrap().as_array().map(|a| a.len()), Some(2));
+        assert_eq!(json_path(b"[[1, 2], [3]]", "[0][1]").unwrap().as_i64(), Some(2));
+        assert_eq!(json_path(b" 7 ", ""), Ok(JsonValue::Number("7".into())));
+    }
+
+    #[test]
+    fn json_path_error_test() {
+        assert_eq!(json_path(PRICES, "data.quotes[2]"), Err(ParseError::NotFound));
+        assert_eq!(json_path(PRICES, "status.length"), Err(ParseError::NotFound));
+        assert_eq!(json_path(PRICES, "missing"), Err(ParseError::NotFound));
+        for path in
+            ["data..quotes", "data.quotes[", "data.quotes[x]", "data.quotes[]", "a.[0]", "."]
+        {
+            assert_eq!(json_path(PRICES, path), Err(ParseError::InvalidPath), "{}", path);
+        }
+        assert_eq!(json_path(b"\xff", ""), Err(ParseError::InvalidUtf8));
+    }
+
+    #[test]
+    fn parse_test() {
+        assert_eq!(
+            JsonValue::parse(br#""a\"\\\/\n\u00e9\ud83d\ude00""#),
+            Ok(JsonValue::String("a\"\\/\n\u{e9}\u{1f600}".into()))
+        );
+        assert_eq!(
+            JsonValue::parse(br#"{"a": 1, "a": 2}"#).unwrap().get("a").unwrap().as_i64(),
+            Some(2)
+        );
+        assert_eq!(JsonValue::parse(b"-0.5e-3").unwrap().as_f64(), Some(-0.0005));
+        assert_eq!(JsonValue::parse(b"{}"), Ok(JsonValue::Object(vec![])));
+        assert_eq!(JsonValue::parse(b"[ ]"), Ok(JsonValue::Array(vec![])));
+        let invalid: &[&[u8]] = &[
+            b"",
+            b"{",
+            b"[1,]",
+            b"{\"a\" 1}",
+            b"01",
+            b"1.",
+            b"-",
+            b"1e",
+            b"tru",
+            b"\"\\x\"",
+            b"\"\\ud800\"",
+            b"\"a\nb\"",
+            b"1 2",
+            b"{a: 1}",
+        ];
+        for doc in invalid {
+            assert_eq!(JsonValue::parse(doc), Err(ParseError::InvalidJson), "{:?}", doc);
+        }
+        let deep = "[".repeat(MAX_DEPTH + 2) + &"]".repeat(MAX_DEPTH + 2);
+        assert_eq!(JsonValue::parse(deep.as_bytes()), Err(ParseError::InvalidJson));
+    }
+}
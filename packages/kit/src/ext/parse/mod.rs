@@ -0,0 +1,45 @@
+//! Helpers for parsing the output of data sources.
+use core::fmt;
+
+mod delimited;
+mod json;
+mod oracle_parse;
+
+pub use delimited::{extract_values, split_typed, FromFields};
+pub use json::{json_path, JsonValue};
+pub use oracle_parse::{HexBytes, OracleParse};
+
+/// The error returned when data source output cannot be parsed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input is not valid UTF-8.
+    InvalidUtf8,
+    /// The input is not a valid JSON document.
+    InvalidJson,
+    /// The path is malformed, such as an unclosed `[`.
+    InvalidPath,
+    /// The path does not lead to a value in the document.
+    NotFound,
+    /// The input has a different number of delimited fields than expected.
+    FieldCount { expected: usize, found: usize },
+    /// The delimited field at the given index could not be parsed.
+    InvalidField(usize),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidUtf8 => write!(f, "input is not valid UTF-8"),
+            ParseError::InvalidJson => write!(f, "input is not valid JSON"),
+            ParseError::InvalidPath => write!(f, "invalid path"),
+            ParseError::NotFound => write!(f, "path not found"),
+            ParseError::FieldCount { expected, found } => {
+                write!(f, "expected {} fields, found {}", expected, found)
+            }
+            ParseError::InvalidField(index) => write!(f, "invalid field at index {}", index),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
@@ -0,0 +1,53 @@
+use alloc::vec::Vec;
+use core::str::FromStr;
+
+use crate::ext::encode;
+
+/// Decodes a single validator report. Every `FromStr` type is covered, parsed from the
+/// report text with the trailing whitespace and newline that data sources commonly emit
+/// removed.
+pub trait OracleParse: Sized {
+    fn parse(bytes: &[u8]) -> Option<Self>;
+}
+
+impl<T: FromStr> OracleParse for T {
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        core::str::from_utf8(bytes).ok()?.trim_end().parse().ok()
+    }
+}
+
+/// Bytes reported as hex text, with an optional `0x` prefix.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct HexBytes(pub Vec<u8>);
+
+impl FromStr for HexBytes {
+    type Err = encode::DecodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        encode::hex_decode(s).map(HexBytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ext::decimal::Decimal;
+    use alloc::string::String;
+
+    #[test]
+    fn oracle_parse_test() {
+        assert_eq!(i64::parse(b"-42\n"), Some(-42));
+        assert_eq!(i64::parse(b" -42"), None);
+        assert_eq!(u8::parse(b"256"), None);
+        assert_eq!(usize::parse(b"7\n"), Some(7));
+        assert_eq!(isize::parse(b"-7"), Some(-7));
+        assert_eq!(char::parse(b"a\r\n"), Some('a'));
+        assert_eq!(f64::parse(b"1.5\r\n"), Some(1.5));
+        assert_eq!(bool::parse(b"true\n"), Some(true));
+        assert_eq!(String::parse(b"  BTC \n"), Some(String::from("  BTC")));
+        assert_eq!(HexBytes::parse(b"0xdeadBEEF\n"), Some(HexBytes(vec![0xde, 0xad, 0xbe, 0xef])));
+        assert_eq!(Decimal::<2>::parse(b"1.25\n"), Some(Decimal::from_mantissa(125)));
+        assert_eq!(i64::parse(b"\xff"), None);
+        assert_eq!(i64::parse(b"1 2"), None);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::ext::decimal::Decimal;
+use crate::ext::math::mul_div_u128;
+
+const BPS: u128 = 10_000;
+
+/// Returns the A/B rate from the A/USD and B/USD prices, rounded down, or None if the
+/// B price is zero or the rate does not fit.
+pub fn cross_rate<const SCALE: u32>(
+    a_usd: Decimal<SCALE>,
+    b_usd: Decimal<SCALE>,
+) -> Option<Decimal<SCALE>> {
+    a_usd.checked_div(b_usd)
+}
+
+/// Returns the B/A rate from the A/B rate, rounded down, or None if the rate is zero.
+pub fn invert<const SCALE: u32>(rate: Decimal<SCALE>) -> Option<Decimal<SCALE>> {
+    Decimal::from_mantissa(Decimal::<SCALE>::ONE).checked_div(rate)
+}
+
+/// Returns the difference between two prices in basis points of the lower one, rounded
+/// down, or None if the lower price is zero. Measuring against the lower price makes the
+/// spread symmetric and never smaller than against the midpoint.
+pub fn spread_bps<const SCALE: u32>(a: Decimal<SCALE>, b: Decimal<SCALE>) -> Option<u128> {
+    let (lo, hi) = (a.min(b).mantissa(), a.max(b).mantissa());
+    if lo == 0 {
+        return None;
+    }
+    // The spread of a nonzero price can only overflow if it is astronomically wide.
+    Some(mul_div_u128(hi - lo, BPS, lo).unwrap_or(u128::MAX))
+}
+
+/// Returns whether two prices are within `max_bps` basis points of each other, as
+/// measured by `spread_bps`. The comparison is exact, without rounding the spread.
+/// A zero price is only within range of another zero price.
+pub fn within_spread_bps<const SCALE: u32>(
+    a: Decimal<SCALE>,
+    b: Decimal<SCALE>,
+    max_bps: u128,
+) -> bool {
+    let (lo, hi) = (a.min(b).mantissa(), a.max(b).mantissa());
+    // diff * BPS <= max_bps * lo holds exactly when diff <= floor(max_bps * lo / BPS).
+    hi - lo <= mul_div_u128(max_bps, lo, BPS).unwrap_or(u128::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type D = Decimal<18>;
+
+    fn d(s: &str) -> D {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn cross_rate_test() {
+        assert_eq!(cross_rate(d("64000"), d("3200")), Some(d("20")));
+        assert_eq!(cross_rate(d("1"), d("3")), Some(d("0.333333333333333333")));
+        assert_eq!(cross_rate(d("1"), D::from_mantissa(0)), None);
+        assert_eq!(invert(d("4")), Some(d("0.25")));
+        assert_eq!(invert(d("0.000000000000000001")), Some(d("1000000000000000000")));
+        assert_eq!(invert(D::from_mantissa(0)), None);
+    }
+
+    #[test]
+    fn spread_test() {
+        assert_eq!(spread_bps(d("100"), d("101")), Some(100));
+        assert_eq!(spread_bps(d("101"), d("100")), Some(100));
+        assert_eq!(spread_bps(d("100"), d("100.005")), Some(0));
+        assert_eq!(spread_bps(d("0"), d("1")), None);
+        assert_eq!(spread_bps(D::from_mantissa(1), D::from_mantissa(u128::MAX)), Some(u128::MAX));
+        assert!(within_spread_bps(d("100"), d("101"), 100));
+        assert!(!within_spread_bps(d("100"), d("101.000000000000000001"), 100));
+        assert!(!within_spread_bps(d("100"), d("100.005"), 0));
+        assert!(within_spread_bps(d("0"), d("0"), 0));
+        assert!(!within_spread_bps(d("0"), d("1"), 1_000_000));
+        assert!(within_spread_bps(
+            D::from_mantissa(u128::MAX / 2),
+            D::from_mantissa(u128::MAX),
+            u128::MAX
+        ));
+    }
+}
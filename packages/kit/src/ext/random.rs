@@ -0,0 +1,177 @@
+use core::convert::TryInto;
+use core::ops::Range;
+
+const CONSTANTS: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+/// A deterministic random number generator for expanding a random seed, such as a VRF
+/// output or entropy aggregated from reports, into many draws. It runs the ChaCha20 block
+/// function in counter mode, so every validator derives the same sequence from the same
+/// seed. The seed should already be uniformly random; this does not hide a guessable one.
+#[derive(Clone, Debug)]
+pub struct OracleRng {
+    state: [u32; 16],
+    block: [u32; 16],
+    index: usize,
+}
+
+impl OracleRng {
+    /// Creates a generator from a seed of any length. Seeds of up to 32 bytes are used as
+    /// the key directly; longer seeds are folded into the key one 32-byte chunk at a time.
+    pub fn from_seed(seed: &[u8]) -> Self {
+        let mut key = [0u32; 8];
+        for (i, chunk) in seed.chunks(32).enumerate() {
+            if i > 0 {
+                let block = chacha20_block(&init_state(&key, i as u64, seed.len() as u64));
+                key.copy_from_slice(&block[..8]);
+            }
+            let mut padded = [0u8; 32];
+            padded[..chunk.len()].copy_from_slice(chunk);
+            for (k, word) in key.iter_mut().zip(padded.chunks(4)) {
+                *k ^= u32::from_le_bytes(word.try_into().unwrap());
+            }
+        }
+        // The seed length goes into the nonce so that trailing zero bytes still matter.
+        let state = init_state(&key, 0, seed.len() as u64);
+        OracleRng { state, block: [0; 16], index: 16 }
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        if self.index == 16 {
+            self.block = chacha20_block(&self.state);
+            self.index = 0;
+            let counter = ((self.state[13] as u64) << 32 | self.state[12] as u64).wrapping_add(1);
+            self.state[12] = counter as u32;
+            self.state[13] = (counter >> 32) as u32;
+        }
+        self.index += 1;
+        self.block[self.index - 1]
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let lo = self.next_u32() as u64;
+        (self.next_u32() as u64) << 32 | lo
+    }
+
+    /// Returns a uniformly distributed value in the given range, without modulo bias.
+    /// Panics if the range is empty.
+    pub fn gen_range(&mut self, range: Range<u64>) -> u64 {
+        assert!(range.start < range.end, "empty range");
+        let span = range.end - range.start;
+        // Reject draws from the incomplete final copy of the span to keep the result uniform.
+        let zone = u64::MAX - (u64::MAX - span + 1) % span;
+        loop {
+            let value = self.next_u64();
+            if value <= zone {
+                return range.start + value % span;
+            }
+        }
+    }
+
+    /// Shuffles the slice in place with the Fisher-Yates algorithm.
+    pub fn shuffle<T>(&mut self, data: &mut [T]) {
+        for i in (1..data.len()).rev() {
+            let j = self.gen_range(0..i as u64 + 1) as usize;
+            data.swap(i, j);
+        }
+    }
+}
+
+fn init_state(key: &[u32; 8], counter: u64, nonce: u64) -> [u32; 16] {
+    let mut state = [0u32; 16];
+    state[..4].copy_from_slice(&CONSTANTS);
+    state[4..12].copy_from_slice(key);
+    state[12] = counter as u32;
+    state[13] = (counter >> 32) as u32;
+    state[14] = nonce as u32;
+    state[15] = (nonce >> 32) as u32;
+    state
+}
+
+fn quarter_round(s: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    s[a] = s[a].wrapping_add(s[b]);
+    s[d] = (s[d] ^ s[a]).rotate_left(16);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_left(12);
+    s[a] = s[a].wrapping_add(s[b]);
+    s[d] = (s[d] ^ s[a]).rotate_left(8);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_left(7);
+}
+
+// The ChaCha20 block function from RFC 8439.
+fn chacha20_block(state: &[u32; 16]) -> [u32; 16] {
+    let mut s = *state;
+    for _ in 0..10 {
+        quarter_round(&mut s, 0, 4, 8, 12);
+        quarter_round(&mut s, 1, 5, 9, 13);
+        quarter_round(&mut s, 2, 6, 10, 14);
+        quarter_round(&mut s, 3, 7, 11, 15);
+        quarter_round(&mut s, 0, 5, 10, 15);
+        quarter_round(&mut s, 1, 6, 11, 12);
+        quarter_round(&mut s, 2, 7, 8, 13);
+        quarter_round(&mut s, 3, 4, 9, 14);
+    }
+    for (out, input) in s.iter_mut().zip(state.iter()) {
+        *out = out.wrapping_add(*input);
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chacha20_block_test() {
+        // RFC 8439, section 2.3.2.
+        let mut state = [0u32; 16];
+        state[..4].copy_from_slice(&CONSTANTS);
+        for i in 0..8 {
+            let b = 4 * i as u32;
+            state[4 + i] = u32::from_le_bytes([b as u8, b as u8 + 1, b as u8 + 2, b as u8 + 3]);
+        }
+        state[12..].copy_from_slice(&[1, 0x09000000, 0x4a000000, 0]);
+        let block = chacha20_block(&state);
+        assert_eq!(block[..4], [0xe4e7f110, 0x15593bd1, 0x1fdd0f50, 0xc47120a3]);
+        assert_eq!(block[12..], [0xd19c12b5, 0xb94e16de, 0xe883d0cb, 0x4e3c50a2]);
+    }
+
+    #[test]
+    fn deterministic_test() {
+        let mut a = OracleRng::from_seed(b"beta");
+        let mut b = OracleRng::from_seed(b"beta");
+        let draws: Vec<u64> = (0..20).map(|_| a.next_u64()).collect();
+        assert!(draws.iter().all(|v| *v == b.next_u64()));
+        assert_ne!(draws[0], OracleRng::from_seed(b"beta\0").next_u64());
+        let long = [7u8; 100];
+        assert_ne!(
+            OracleRng::from_seed(&long).next_u64(),
+            OracleRng::from_seed(&long[..99]).next_u64()
+        );
+    }
+
+    #[test]
+    fn gen_range_test() {
+        let mut rng = OracleRng::from_seed(&[42; 32]);
+        let mut seen = [false; 6];
+        for _ in 0..200 {
+            let v = rng.gen_range(10..16);
+            assert!((10..16).contains(&v));
+            seen[(v - 10) as usize] = true;
+        }
+        assert!(seen.iter().all(|s| *s));
+        assert_eq!(rng.gen_range(5..6), 5);
+        let _ = rng.gen_range(0..u64::MAX);
+    }
+
+    #[test]
+    fn shuffle_test() {
+        let mut rng = OracleRng::from_seed(b"lottery");
+        let mut data: Vec<u32> = (0..50).collect();
+        rng.shuffle(&mut data);
+        assert_ne!(data, (0..50).collect::<Vec<_>>());
+        data.sort();
+        assert_eq!(data, (0..50).collect::<Vec<_>>());
+        rng.shuffle(&mut [] as &mut [u32]);
+    }
+}
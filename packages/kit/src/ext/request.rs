@@ -0,0 +1,90 @@
+use crate::oei::{self, OeiError};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use obi::OBIEncode;
+
+/// Declares consecutive external ID constants starting from 1, in the same order that
+/// `DataRequestBuilder` assigns them, so execute can refer to the data asked in prepare.
+#[macro_export]
+macro_rules! external_ids {
+    ($($name:ident),+ $(,)?) => {
+        $crate::external_ids!(@next 1i64; $($name),+);
+    };
+    (@next $id:expr; $name:ident $(, $rest:ident)*) => {
+        pub const $name: i64 = $id;
+        $crate::external_ids!(@next $id + 1; $($rest),*);
+    };
+    (@next $id:expr;) => {};
+}
+
+/// Collects raw requests during preparation, assigning external IDs from 1 in the order
+/// they are added. Nothing is asked from the host until `emit`, so a request that fails
+/// validation leaves no partial set of requests behind.
+#[derive(Debug, Default)]
+pub struct DataRequestBuilder {
+    asks: Vec<(i64, i64, Vec<u8>)>,
+}
+
+impl DataRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a raw request to the given data source and returns its external ID, or an
+    /// error if the calldata does not fit in a span.
+    pub fn ask(&mut self, did: i64, calldata: &[u8]) -> Result<i64, OeiError> {
+        if calldata.len() as i64 > oei::get_span_size() {
+            return Err(OeiError::SpanExceeded);
+        }
+        let eid = self.asks.len() as i64 + 1;
+        self.asks.push((eid, did, calldata.to_vec()));
+        Ok(eid)
+    }
+
+    /// Same as `ask`, but OBI-encodes the given calldata.
+    #[cfg(feature = "std")]
+    pub fn ask_obi<T: OBIEncode>(&mut self, did: i64, calldata: &T) -> Result<i64, OeiError> {
+        let calldata = calldata.try_to_vec().map_err(|_| OeiError::EncodingFailed)?;
+        self.ask(did, &calldata)
+    }
+
+    /// Issues all collected raw requests to the host. Must only be called during
+    /// preparation phase.
+    pub fn emit(self) {
+        for (eid, did, calldata) in self.asks {
+            oei::ask_external_data(eid, did, &calldata);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    external_ids!(FIRST, SECOND, THIRD);
+
+    #[test]
+    fn external_ids_test() {
+        assert_eq!((FIRST, SECOND, THIRD), (1, 2, 3));
+    }
+
+    #[test]
+    fn data_request_builder_test() {
+        let backend = Rc::new(oei::MemoryBackend::new(8));
+        oei::set_backend(backend.clone());
+
+        let mut builder = DataRequestBuilder::new();
+        assert_eq!(builder.ask(10, b"beeb"), Ok(FIRST));
+        assert_eq!(builder.ask(11, b"bandchain"), Err(OeiError::SpanExceeded));
+        assert_eq!(builder.ask_obi(12, &String::from("beeb")), Ok(SECOND));
+        assert!(backend.asked.borrow().is_empty());
+
+        builder.emit();
+        assert_eq!(
+            *backend.asked.borrow(),
+            vec![(FIRST, 10, b"beeb".to_vec()), (SECOND, 12, b"\0\0\0\x04beeb".to_vec())]
+        );
+        oei::clear_backend();
+    }
+}
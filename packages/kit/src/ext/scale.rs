@@ -0,0 +1,73 @@
+use num::{Float, NumCast};
+
+/// How to round a value that falls between two integers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Toward negative infinity.
+    Floor,
+    /// To the nearest integer, with halfway cases away from zero.
+    Round,
+    /// Toward positive infinity.
+    Ceil,
+}
+
+/// Returns `value * 10^decimals` rounded to an integer, the usual on-chain representation
+/// of a price, or None if the result is NaN, negative or does not fit in a u64. The
+/// multiplication is done in floating point, so a value such as 1.005 that has no exact
+/// binary representation may land just below its halfway point.
+pub fn to_scaled_u64<T>(value: T, decimals: u32, rounding: Rounding) -> Option<u64>
+where
+    T: Float,
+{
+    let scaled = value * pow10(decimals)?;
+    let rounded = match rounding {
+        Rounding::Floor => scaled.floor(),
+        Rounding::Round => scaled.round(),
+        Rounding::Ceil => scaled.ceil(),
+    };
+    rounded.to_u64()
+}
+
+/// Returns `scaled / 10^decimals`, the inverse of `to_scaled_u64`.
+pub fn from_scaled<T>(scaled: u64, decimals: u32) -> Option<T>
+where
+    T: Float,
+{
+    Some(<T as NumCast>::from(scaled)? / pow10(decimals)?)
+}
+
+fn pow10<T>(decimals: u32) -> Option<T>
+where
+    T: Float,
+{
+    let ten: T = NumCast::from(10)?;
+    Some(ten.powi(decimals as i32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_scaled_u64_test() {
+        assert_eq!(to_scaled_u64(1.23456789, 9, Rounding::Round), Some(1_234_567_890));
+        assert_eq!(to_scaled_u64(1.5, 0, Rounding::Floor), Some(1));
+        assert_eq!(to_scaled_u64(1.5, 0, Rounding::Round), Some(2));
+        assert_eq!(to_scaled_u64(1.2, 0, Rounding::Ceil), Some(2));
+        assert_eq!(to_scaled_u64(2.0, 0, Rounding::Ceil), Some(2));
+        assert_eq!(to_scaled_u64(0.0000000004, 9, Rounding::Round), Some(0));
+        assert_eq!(to_scaled_u64(0.0000000004, 9, Rounding::Ceil), Some(1));
+        assert_eq!(to_scaled_u64(-1.0, 9, Rounding::Round), None);
+        assert_eq!(to_scaled_u64(f64::NAN, 9, Rounding::Round), None);
+        assert_eq!(to_scaled_u64(1e11, 9, Rounding::Round), None);
+        assert_eq!(to_scaled_u64(1.5f32, 1, Rounding::Round), Some(15));
+    }
+
+    #[test]
+    fn from_scaled_test() {
+        assert_eq!(from_scaled::<f64>(1_234_567_890, 9), Some(1.23456789));
+        assert_eq!(from_scaled::<f64>(15, 0), Some(15.0));
+        let scaled = to_scaled_u64(42.125, 9, Rounding::Round).unwrap();
+        assert_eq!(from_scaled::<f64>(scaled, 9), Some(42.125));
+    }
+}
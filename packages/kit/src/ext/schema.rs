@@ -0,0 +1,74 @@
+use obi::schema::{Declaration, Definition};
+use obi::OBISchema;
+use std::collections::HashMap;
+
+/// Returns the OBI schema of an oracle script with the given input and output types, in
+/// the `input/output` form stored with the script on chain, such as
+/// `{symbol:string,multiplier:u64}/{px:u64}`.
+pub fn obi_schema<I, O>() -> String
+where
+    I: OBISchema,
+    O: OBISchema,
+{
+    format!("{}/{}", type_schema::<I>(), type_schema::<O>())
+}
+
+fn type_schema<T: OBISchema>() -> String {
+    let mut definitions = HashMap::new();
+    T::add_definitions_recursively(&mut definitions);
+    render(&T::declaration(), &definitions)
+}
+
+// Renders a declaration in the on-chain schema format. `obi::get_schema` is not used
+// because it prefixes sequences with their declaration, as in `[u64]:[u64]`.
+fn render(declaration: &Declaration, definitions: &HashMap<Declaration, Definition>) -> String {
+    match definitions.get(declaration) {
+        Some(Definition::Sequence { elements }) if elements == "u8" => String::from("bytes"),
+        Some(Definition::Sequence { elements }) => format!("[{}]", render(elements, definitions)),
+        Some(Definition::Struct { fields }) => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|(name, dec)| format!("{}:{}", name, render(dec, definitions)))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        None => declaration.clone(),
+    }
+}
+
+/// Leaks the schema and returns its wasm32 pointer in the high 32 bits and its length in
+/// the low 32 bits, as returned by the `get_schema` export of `schema_entry_point!`.
+pub fn export(schema: String) -> i64 {
+    let schema: &'static str = Box::leak(schema.into_boxed_str());
+    ((schema.as_ptr() as i64) << 32) | schema.len() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(OBISchema)]
+    #[allow(dead_code)]
+    struct Input {
+        symbols: Vec<String>,
+        multiplier: u64,
+    }
+
+    #[derive(OBISchema)]
+    #[allow(dead_code)]
+    struct Output {
+        rates: Vec<u64>,
+        proof: Vec<u8>,
+    }
+
+    #[test]
+    fn obi_schema_test() {
+        assert_eq!(
+            obi_schema::<Input, Output>(),
+            "{symbols:[string],multiplier:u64}/{rates:[u64],proof:bytes}"
+        );
+        assert_eq!(obi_schema::<String, u64>(), "string/u64");
+        assert_eq!(obi_schema::<Vec<String>, Vec<u8>>(), "[string]/bytes");
+        assert_eq!(obi_schema::<Vec<Vec<i32>>, bool>(), "[[i32]]/bool");
+    }
+}
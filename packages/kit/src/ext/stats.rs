@@ -1,13 +1,88 @@
 use core::cmp::{Ord, Ordering, PartialEq};
 
-use num::{Float, Num, NumCast};
+use alloc::vec::Vec;
+use num::{Float, Integer, Num, NumCast, PrimInt, ToPrimitive};
 
 use crate::ext::cmp;
 
+/// Several aggregates of one data set, as returned by `summarize`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Summary<T> {
+    pub min: T,
+    pub max: T,
+    pub mean: T,
+    pub median: T,
+    /// The population standard deviation.
+    pub std_dev: T,
+    pub count: usize,
+}
+
+/// Returns a `Summary` of the given data set, or None if data is empty. Everything but
+/// the median is computed in a single pass, with Welford's algorithm for the mean and
+/// standard deviation.
+pub fn summarize<T>(data: Vec<T>) -> Option<Summary<T>>
+where
+    T: Float,
+{
+    let first = *data.first()?;
+    let (mut min, mut max, mut mean, mut m2) = (first, first, T::zero(), T::zero());
+    let mut count = T::zero();
+    for &v in &data {
+        min = min.min(v);
+        max = max.max(v);
+        count = count + T::one();
+        let delta = v - mean;
+        mean = mean + delta / count;
+        m2 = m2 + delta * (v - mean);
+    }
+    Some(Summary {
+        min,
+        max,
+        mean,
+        std_dev: (m2 / count).sqrt(),
+        count: data.len(),
+        median: median_float(data)?,
+    })
+}
+
 /// Returns the average value of the given data set, or None if data is empty.
 pub fn average<T>(data: Vec<T>) -> Option<T>
 where
     T: Num,
+{
+    average_iter(data)
+}
+
+/// Same as `average`, but sums with Neumaier's compensated summation, so the rounding
+/// error does not grow with the number of values and the result barely depends on their
+/// order.
+pub fn average_accurate<T>(data: Vec<T>) -> Option<T>
+where
+    T: Float,
+{
+    if data.is_empty() {
+        return None;
+    }
+    let (mut sum, mut compensation) = (T::zero(), T::zero());
+    for &v in &data {
+        let t = sum + v;
+        // Recover the low-order bits lost when adding the smaller operand.
+        if sum.abs() >= v.abs() {
+            compensation = compensation + ((sum - t) + v);
+        } else {
+            compensation = compensation + ((v - t) + sum);
+        }
+        sum = t;
+    }
+    let count: T = NumCast::from(data.len())?;
+    Some((sum + compensation) / count)
+}
+
+/// Same as `average`, but consumes the values from an iterator without collecting them.
+pub fn average_iter<T, I>(data: I) -> Option<T>
+where
+    T: Num,
+    I: IntoIterator<Item = T>,
 {
     let mut sum = T::zero();
     let mut count = T::zero();
@@ -22,6 +97,178 @@ where
     }
 }
 
+/// Returns the average of the given values weighted by their paired weights, such as
+/// validator power or liquidity, or None if the total weight is zero or a weight is
+/// negative.
+pub fn weighted_average<T, W>(values: Vec<(T, W)>) -> Option<T>
+where
+    T: Float,
+    W: ToPrimitive,
+{
+    let (mut sum, mut total) = (T::zero(), T::zero());
+    for (v, w) in values {
+        let w: T = NumCast::from(w)?;
+        if w.is_nan() || w < T::zero() {
+            return None;
+        }
+        sum = sum + v * w;
+        total = total + w;
+    }
+    if total == T::zero() {
+        None
+    } else {
+        Some(sum / total)
+    }
+}
+
+/// Returns the volume-weighted average price of the given price and volume pairs, or None
+/// if the total volume is zero or a volume is negative.
+pub fn vwap<T, V>(prices_and_volumes: Vec<(T, V)>) -> Option<T>
+where
+    T: Float,
+    V: ToPrimitive,
+{
+    weighted_average(prices_and_volumes)
+}
+
+/// Returns the time-weighted average price of the given price and timestamp samples. Each
+/// price holds from its timestamp until the next sample, so the latest sample only marks
+/// the end of the window. Returns None if the samples span no time.
+pub fn twap<T>(mut prices_and_timestamps: Vec<(T, i64)>) -> Option<T>
+where
+    T: Float,
+{
+    prices_and_timestamps.sort_by_key(|&(_, t)| t);
+    weighted_average(
+        prices_and_timestamps
+            .windows(2)
+            .map(|w| (w[0].0, w[1].1 as i128 - w[0].1 as i128))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Returns the exponential moving average of the given values in time order, oldest
+/// first, with smoothing factor `alpha` in (0, 1]. The first value seeds the average and
+/// each later value moves it by `alpha` of the difference. Returns None if data is empty
+/// or `alpha` is out of range.
+pub fn ema<T>(values_in_time_order: Vec<T>, alpha: T) -> Option<T>
+where
+    T: Float,
+{
+    if !(alpha > T::zero() && alpha <= T::one()) {
+        return None;
+    }
+    let mut values = values_in_time_order.into_iter();
+    let first = values.next()?;
+    Some(values.fold(first, |avg, v| avg + alpha * (v - avg)))
+}
+
+/// Same as `weighted_average` for integers, rounded toward zero. Accumulates in i128 with
+/// checked arithmetic and returns None if that overflows.
+pub fn weighted_average_integer<T, W>(values: Vec<(T, W)>) -> Option<T>
+where
+    T: PrimInt,
+    W: PrimInt,
+{
+    let (mut sum, mut total) = (0i128, 0i128);
+    for (v, w) in values {
+        let w = w.to_i128()?;
+        if w < 0 {
+            return None;
+        }
+        sum = sum.checked_add(v.to_i128()?.checked_mul(w)?)?;
+        total = total.checked_add(w)?;
+    }
+    if total == 0 {
+        None
+    } else {
+        NumCast::from(sum / total)
+    }
+}
+
+/// Returns the geometric mean of the given data set, or None if data is empty or
+/// contains a negative value. Computed in log space so large products do not overflow.
+pub fn geometric_mean<T>(data: Vec<T>) -> Option<T>
+where
+    T: Float,
+{
+    if data.iter().any(|v| v.is_nan() || *v < T::zero()) {
+        return None;
+    }
+    average(data.into_iter().map(|v| v.ln()).collect()).map(|v| v.exp())
+}
+
+/// Returns the harmonic mean of the given data set, or None if data is empty or contains
+/// a value that is not positive.
+pub fn harmonic_mean<T>(data: Vec<T>) -> Option<T>
+where
+    T: Float,
+{
+    if data.iter().any(|v| v.is_nan() || *v <= T::zero()) {
+        return None;
+    }
+    average(data.into_iter().map(|v| v.recip()).collect()).map(|v| v.recip())
+}
+
+/// Returns the geometric mean of the given data set rounded down, or None if data is
+/// empty, contains a value that is not positive, or the product of all values overflows.
+pub fn geometric_mean_integer<T>(data: Vec<T>) -> Option<T>
+where
+    T: PrimInt,
+{
+    if data.is_empty() || data.iter().any(|v| *v <= T::zero()) {
+        return None;
+    }
+    let product = data.iter().try_fold(T::one(), |acc, v| acc.checked_mul(v))?;
+    // Binary search for the largest root whose n-th power does not exceed the product.
+    // The geometric mean is at most the largest value.
+    let n = data.len();
+    let pow_le = |root: T| {
+        let mut acc = T::one();
+        for _ in 0..n {
+            match acc.checked_mul(&root) {
+                Some(next) if next <= product => acc = next,
+                _ => return false,
+            }
+        }
+        true
+    };
+    let (mut lo, mut hi) = (T::one(), *data.iter().max().unwrap());
+    while lo < hi {
+        let mid = hi - (hi - lo) / (T::one() + T::one());
+        if pow_le(mid) {
+            lo = mid;
+        } else {
+            hi = mid - T::one();
+        }
+    }
+    Some(lo)
+}
+
+/// Returns the harmonic mean of the given data set rounded down, or None if data is
+/// empty, contains a value that is not positive, or an intermediate value overflows.
+pub fn harmonic_mean_integer<T>(data: Vec<T>) -> Option<T>
+where
+    T: PrimInt + Integer,
+{
+    if data.is_empty() || data.iter().any(|v| *v <= T::zero()) {
+        return None;
+    }
+    // Sum the reciprocals as an exact fraction num / den, reduced at each step.
+    let (mut num, mut den) = (T::zero(), T::one());
+    let mut count = T::zero();
+    for v in data {
+        let lcm = (den / den.gcd(&v)).checked_mul(&v)?;
+        num = num.checked_mul(&(lcm / den))?.checked_add(&(lcm / v))?;
+        den = lcm;
+        let gcd = num.gcd(&den);
+        num = num / gcd;
+        den = den / gcd;
+        count = count.checked_add(&T::one())?;
+    }
+    count.checked_mul(&den)?.checked_div(&num)
+}
+
 /// Returns the median value using the given compare function, or None if data is empty.
 pub fn median_by<T, F>(mut data: Vec<T>, compare: F) -> Option<T>
 where
@@ -34,6 +281,7 @@ where
 
     data.sort_by(compare);
     let mid = data.len() / 2;
+    #[allow(clippy::manual_is_multiple_of)]
     if data.len() % 2 == 0 {
         let rhs = data.swap_remove(mid);
         let lhs = data.swap_remove(mid - 1);
@@ -43,12 +291,62 @@ where
     }
 }
 
-/// Returns the median value of the given data set, or None if data is empty.
-pub fn median_integer<T>(data: Vec<T>) -> Option<T>
+/// Same as `median_by`, but reorders the given slice in place instead of taking a Vec.
+/// Only partially sorts the slice, so it does less work than a full sort.
+pub fn median_by_slice<T, F>(data: &mut [T], mut compare: F) -> Option<T>
 where
-    T: Ord + Num + NumCast,
+    T: Num + NumCast + Clone,
+    F: FnMut(&T, &T) -> Ordering,
 {
-    median_by(data, T::cmp)
+    if data.is_empty() {
+        return None;
+    }
+
+    let len = data.len();
+    let mid = len / 2;
+    let (lower, rhs, _) = data.select_nth_unstable_by(mid, &mut compare);
+    let rhs = rhs.clone();
+    if len % 2 == 1 {
+        return Some(rhs);
+    }
+    let lhs = lower.iter().max_by(|a, b| compare(a, b)).unwrap().clone();
+    Some((lhs + rhs) / NumCast::from(2).unwrap())
+}
+
+/// Returns the median value of the given data set, or None if data is empty. Works for
+/// any integer type, including u128, i128 and U256, without overflowing near its bounds.
+pub fn median_integer<T>(mut data: Vec<T>) -> Option<T>
+where
+    T: Ord + Num + Clone,
+{
+    if data.is_empty() {
+        return None;
+    }
+
+    data.sort();
+    let mid = data.len() / 2;
+    if data.len() % 2 == 1 {
+        return Some(data.swap_remove(mid));
+    }
+    let rhs = data.swap_remove(mid);
+    let lhs = data.swap_remove(mid - 1);
+    Some(midpoint(lhs, rhs))
+}
+
+// Returns (lhs + rhs) / 2 rounded toward zero for lhs <= rhs, without computing the sum
+// unless the signs differ, when it cannot overflow.
+fn midpoint<T>(lhs: T, rhs: T) -> T
+where
+    T: Ord + Num + Clone,
+{
+    let two = T::one() + T::one();
+    if lhs < T::zero() && rhs >= T::zero() {
+        (lhs + rhs) / two
+    } else if lhs >= T::zero() {
+        lhs.clone() + (rhs - lhs) / two
+    } else {
+        rhs.clone() - (rhs - lhs) / two
+    }
 }
 
 /// Returns the median value of the given data set, or None if data is empty.
@@ -59,6 +357,42 @@ where
     median_by(data, cmp::fcmp)
 }
 
+/// Returns the `p`-th percentile of the given data set for `p` in 0 to 100, or None if
+/// data is empty or `p` is out of range. Values between two data points are linearly
+/// interpolated at rank `p / 100 * (n - 1)`, the same rule as NumPy's default.
+pub fn percentile<T>(mut data: Vec<T>, p: T) -> Option<T>
+where
+    T: Float,
+{
+    let hundred: T = NumCast::from(100).unwrap();
+    if data.is_empty() || !(p >= T::zero() && p <= hundred) {
+        return None;
+    }
+
+    data.sort_by(cmp::fcmp);
+    let rank = p / hundred * NumCast::from(data.len() - 1).unwrap();
+    let lo = rank.floor();
+    let (lhs, rhs) = (data[lo.to_usize().unwrap()], data[rank.ceil().to_usize().unwrap()]);
+    Some(lhs + (rhs - lhs) * (rank - lo))
+}
+
+/// Returns the `p`-th percentile of the given data set for `p` in 0 to 100, or None if
+/// data is empty or `p` is out of range. Uses the nearest-rank rule: the smallest value
+/// that at least `p` percent of the data set is less than or equal to. Unlike
+/// `percentile`, the result is always one of the data points.
+pub fn percentile_integer<T>(mut data: Vec<T>, p: u32) -> Option<T>
+where
+    T: Ord,
+{
+    if data.is_empty() || p > 100 {
+        return None;
+    }
+
+    data.sort();
+    let rank = (p as usize * data.len()).div_ceil(100).max(1);
+    Some(data.swap_remove(rank - 1))
+}
+
 /// Returns the majority value of the given data set, or None if there is no majority.
 pub fn majority<T>(mut data: Vec<T>) -> Option<T>
 where
@@ -96,6 +430,248 @@ where
     }
 }
 
+/// Returns the first quartile, the third quartile and the interquartile range between
+/// them of the given data set, or None if data is empty. Quartiles are computed with
+/// `percentile`.
+pub fn iqr<T>(mut data: Vec<T>) -> Option<(T, T, T)>
+where
+    T: Float,
+{
+    // Presorting makes the sorts inside `percentile` run in linear time.
+    data.sort_by(cmp::fcmp);
+    let q1 = percentile(data.clone(), NumCast::from(25).unwrap())?;
+    let q3 = percentile(data, NumCast::from(75).unwrap())?;
+    Some((q1, q3, q3 - q1))
+}
+
+/// Returns the values of the given data set within `k` interquartile ranges below the
+/// first quartile or above the third quartile, in their original order. Quartiles are
+/// computed with `percentile`; `k` is conventionally 1.5.
+pub fn filter_outliers_iqr<T>(data: Vec<T>, k: T) -> Vec<T>
+where
+    T: Float,
+{
+    let (q1, q3, iqr) = match iqr(data.clone()) {
+        Some(quartiles) => quartiles,
+        None => return data,
+    };
+    data.into_iter().filter(|&v| v >= q1 - k * iqr && v <= q3 + k * iqr).collect()
+}
+
+/// Returns the values of the given data set whose distance from the mean is at most
+/// `threshold` population standard deviations, in their original order.
+pub fn filter_outliers_zscore<T>(data: Vec<T>, threshold: T) -> Vec<T>
+where
+    T: Float,
+{
+    let mean = match average(data.clone()) {
+        Some(mean) => mean,
+        None => return data,
+    };
+    let variance = average(data.iter().map(|&v| (v - mean) * (v - mean)).collect()).unwrap();
+    let limit = threshold * variance.sqrt();
+    data.into_iter().filter(|&v| (v - mean).abs() <= limit).collect()
+}
+
+/// Returns the values of the given data set whose distance from the median is at most
+/// `threshold` median absolute deviations, in their original order. If more than half
+/// of the values are equal, the deviation is zero and only those values are kept.
+pub fn filter_outliers_mad<T>(data: Vec<T>, threshold: T) -> Vec<T>
+where
+    T: Float + NumCast,
+{
+    let (median, mad) = match median_and_mad(&data) {
+        Some(stats) => stats,
+        None => return data,
+    };
+    data.into_iter().filter(|&v| (v - median).abs() <= threshold * mad).collect()
+}
+
+/// Returns the median absolute deviation of the given data set, the median distance of
+/// the values from their median, or None if data is empty.
+pub fn median_absolute_deviation<T>(data: Vec<T>) -> Option<T>
+where
+    T: Float,
+{
+    median_and_mad(&data).map(|(_, mad)| mad)
+}
+
+/// Same as `median_absolute_deviation` for integers, where each median is computed as in
+/// `median_integer`.
+pub fn median_absolute_deviation_integer<T>(data: Vec<T>) -> Option<T>
+where
+    T: Ord + Num + Clone,
+{
+    let median = median_integer(data.clone())?;
+    median_integer(
+        data.into_iter()
+            .map(|v| if v >= median { v - median.clone() } else { median.clone() - v })
+            .collect(),
+    )
+}
+
+fn median_and_mad<T>(data: &[T]) -> Option<(T, T)>
+where
+    T: Float,
+{
+    let median = median_float(data.to_vec())?;
+    let mad = median_float(data.iter().map(|&v| (v - median).abs()).collect())?;
+    Some((median, mad))
+}
+
+/// Returns the given data set, or None if it is empty or any value deviates from the
+/// median by more than `max_ratio` of the median, e.g. 0.05 for 5%. Lets a script refuse
+/// to report when its sources disagree.
+pub fn within_deviation<T>(data: Vec<T>, max_ratio: T) -> Option<Vec<T>>
+where
+    T: Float,
+{
+    within_deviation_tolerant(data, max_ratio, 0)
+}
+
+/// Same as `within_deviation`, but tolerates up to `max_deviating` deviating values and
+/// returns only the values within the deviation, in their original order.
+pub fn within_deviation_tolerant<T>(
+    data: Vec<T>,
+    max_ratio: T,
+    max_deviating: usize,
+) -> Option<Vec<T>>
+where
+    T: Float,
+{
+    let median = median_float(data.clone())?;
+    let limit = max_ratio * median.abs();
+    let len = data.len();
+    let within: Vec<T> = data.into_iter().filter(|&v| (v - median).abs() <= limit).collect();
+    if len - within.len() > max_deviating {
+        None
+    } else {
+        Some(within)
+    }
+}
+
+/// Returns the population covariance of two paired data sets, or None if they are empty
+/// or have different lengths.
+pub fn covariance<T>(a: Vec<T>, b: Vec<T>) -> Option<T>
+where
+    T: Float,
+{
+    if a.len() != b.len() {
+        return None;
+    }
+    let (mean_a, mean_b) = (average(a.clone())?, average(b.clone())?);
+    average(a.into_iter().zip(b).map(|(x, y)| (x - mean_a) * (y - mean_b)).collect())
+}
+
+/// Returns the Pearson correlation coefficient of two paired data sets, from -1 to 1, or
+/// None if they are empty, have different lengths, or either one is constant.
+pub fn pearson_correlation<T>(a: Vec<T>, b: Vec<T>) -> Option<T>
+where
+    T: Float,
+{
+    let cov = covariance(a.clone(), b.clone())?;
+    let std_dev_a = covariance(a.clone(), a)?.sqrt();
+    let std_dev_b = covariance(b.clone(), b)?.sqrt();
+    if std_dev_a == T::zero() || std_dev_b == T::zero() {
+        return None;
+    }
+    Some(cov / (std_dev_a * std_dev_b))
+}
+
+/// Same as `average`, but skips missing values. Also returns the number of values that
+/// contributed.
+pub fn average_some<T>(data: Vec<Option<T>>) -> (Option<T>, usize)
+where
+    T: Num,
+{
+    let data: Vec<T> = data.into_iter().flatten().collect();
+    let count = data.len();
+    (average(data), count)
+}
+
+/// Same as `median_by`, but returns None unless the data set has at least `min_count`
+/// values, e.g. to require a number of independent sources before reporting.
+pub fn median_with_min_count<T, F>(data: Vec<T>, min_count: usize, compare: F) -> Option<T>
+where
+    T: Num + NumCast,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if data.len() < min_count {
+        return None;
+    }
+    median_by(data, compare)
+}
+
+/// Same as `median_by`, but skips missing values. Also returns the number of values that
+/// contributed.
+pub fn median_some<T, F>(data: Vec<Option<T>>, compare: F) -> (Option<T>, usize)
+where
+    T: Num + NumCast,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let data: Vec<T> = data.into_iter().flatten().collect();
+    let count = data.len();
+    (median_by(data, compare), count)
+}
+
+/// Same as `majority`, but skips missing values, which do not count toward the total.
+/// Also returns the number of values that contributed.
+pub fn majority_some<T>(data: Vec<Option<T>>) -> (Option<T>, usize)
+where
+    T: PartialEq,
+{
+    let data: Vec<T> = data.into_iter().flatten().collect();
+    let count = data.len();
+    (majority(data), count)
+}
+
+// Returns each distinct value of the data set with its number of occurrences, in
+// ascending order of value.
+fn frequencies<T>(mut data: Vec<T>) -> Vec<(T, usize)>
+where
+    T: Ord,
+{
+    data.sort();
+    let mut freqs: Vec<(T, usize)> = Vec::new();
+    for v in data {
+        match freqs.last_mut() {
+            Some((last, count)) if *last == v => *count += 1,
+            _ => freqs.push((v, 1)),
+        }
+    }
+    freqs
+}
+
+/// Returns the most frequent value of the given data set, or None if data is empty. Ties
+/// are broken in favor of the smallest value. Unlike `majority`, the value does not need
+/// to appear in more than half of the data set.
+pub fn mode<T>(data: Vec<T>) -> Option<T>
+where
+    T: Ord,
+{
+    let mut best: Option<(T, usize)> = None;
+    for (v, count) in frequencies(data) {
+        match best {
+            Some((_, best_count)) if best_count >= count => {}
+            _ => best = Some((v, count)),
+        }
+    }
+    best.map(|(v, _)| v)
+}
+
+/// Returns up to `k` most frequent values of the given data set with their number of
+/// occurrences, from most to least frequent. Ties are ordered by ascending value.
+pub fn top_k_frequent<T>(data: Vec<T>, k: usize) -> Vec<(T, usize)>
+where
+    T: Ord,
+{
+    let mut freqs = frequencies(data);
+    // The sort is stable, so values with equal counts stay in ascending order.
+    freqs.sort_by(|(_, lhs), (_, rhs)| rhs.cmp(lhs));
+    freqs.truncate(k);
+    freqs
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,6 +756,344 @@ mod tests {
         assert_eq!(median_float(vals), Some(24.6));
     }
 
+    #[test]
+    fn test_percentile() {
+        let vals: Vec<f64> = vec![];
+        assert_eq!(percentile(vals, 50.0), None);
+        let vals = vec![15.0, 20.0, 35.0, 40.0, 50.0];
+        assert_eq!(percentile(vals.clone(), 0.0), Some(15.0));
+        assert_eq!(percentile(vals.clone(), 25.0), Some(20.0));
+        assert_eq!(percentile(vals.clone(), 40.0), Some(29.0));
+        assert_eq!(percentile(vals.clone(), 50.0), Some(35.0));
+        assert_eq!(percentile(vals.clone(), 100.0), Some(50.0));
+        assert_eq!(percentile(vals.clone(), 100.5), None);
+        assert_eq!(percentile(vals, f64::NAN), None);
+        assert_eq!(percentile(vec![3.0], 95.0), Some(3.0));
+    }
+
+    #[test]
+    fn test_percentile_integer() {
+        let vals: Vec<i64> = vec![];
+        assert_eq!(percentile_integer(vals, 50), None);
+        let vals = vec![50, 15, 35, 20, 40];
+        assert_eq!(percentile_integer(vals.clone(), 0), Some(15));
+        assert_eq!(percentile_integer(vals.clone(), 5), Some(15));
+        assert_eq!(percentile_integer(vals.clone(), 30), Some(20));
+        assert_eq!(percentile_integer(vals.clone(), 40), Some(20));
+        assert_eq!(percentile_integer(vals.clone(), 50), Some(35));
+        assert_eq!(percentile_integer(vals.clone(), 100), Some(50));
+        assert_eq!(percentile_integer(vals, 101), None);
+    }
+
+    #[test]
+    fn test_iqr() {
+        let vals: Vec<f64> = vec![];
+        assert_eq!(iqr(vals), None);
+        assert_eq!(iqr(vec![7.0, 1.0, 5.0, 3.0, 9.0]), Some((3.0, 7.0, 4.0)));
+        assert_eq!(iqr(vec![2.0]), Some((2.0, 2.0, 0.0)));
+    }
+
+    #[test]
+    fn test_filter_outliers_iqr() {
+        let vals: Vec<f64> = vec![];
+        assert_eq!(filter_outliers_iqr(vals, 1.5), Vec::<f64>::new());
+        let vals = vec![10.0, 11.0, 9.5, 10.5, 100.0, 10.2, -50.0];
+        assert_eq!(filter_outliers_iqr(vals, 1.5), vec![10.0, 11.0, 9.5, 10.5, 10.2]);
+        let vals = vec![3.0, 3.0, 3.0];
+        assert_eq!(filter_outliers_iqr(vals, 1.5), vec![3.0, 3.0, 3.0]);
+    }
+
+    #[test]
+    fn test_filter_outliers_zscore() {
+        let vals: Vec<f64> = vec![];
+        assert_eq!(filter_outliers_zscore(vals, 2.0), Vec::<f64>::new());
+        let vals = vec![10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 100.0];
+        assert_eq!(filter_outliers_zscore(vals, 2.0), vec![10.0; 9]);
+        let vals = vec![1.0, 2.0, 3.0];
+        assert_eq!(filter_outliers_zscore(vals, 2.0), vec![1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_filter_outliers_mad() {
+        let vals: Vec<f64> = vec![];
+        assert_eq!(filter_outliers_mad(vals, 3.0), Vec::<f64>::new());
+        let vals = vec![10.0, 11.0, 9.0, 10.5, 9.5, 1000.0];
+        assert_eq!(filter_outliers_mad(vals, 3.0), vec![10.0, 11.0, 9.0, 10.5, 9.5]);
+        let vals = vec![5.0, 5.0, 5.0, 6.0];
+        assert_eq!(filter_outliers_mad(vals, 3.0), vec![5.0, 5.0, 5.0]);
+    }
+
+    #[test]
+    fn test_median_integer_bounds() {
+        assert_eq!(median_integer(vec![u128::MAX, u128::MAX - 2]), Some(u128::MAX - 1));
+        assert_eq!(median_integer(vec![i128::MAX, i128::MIN]), Some(0));
+        assert_eq!(median_integer(vec![i128::MIN, i128::MIN + 2]), Some(i128::MIN + 1));
+        assert_eq!(median_integer(vec![-2, -1]), Some(-1));
+        assert_eq!(median_integer(vec![-1, 2]), Some(0));
+        assert_eq!(median_integer(vec![1, 2]), Some(1));
+    }
+
+    #[cfg(feature = "u256")]
+    #[test]
+    fn test_u256() {
+        use ruint::aliases::U256;
+
+        let vals = vec![U256::MAX, U256::MAX - U256::from(2), U256::from(1)];
+        assert_eq!(median_integer(vals), Some(U256::MAX - U256::from(2)));
+        let vals = vec![U256::MAX, U256::MAX - U256::from(2)];
+        assert_eq!(median_integer(vals), Some(U256::MAX - U256::from(1)));
+        let vals = vec![
+            U256::from(10).pow(U256::from(18)),
+            U256::from(3) * U256::from(10).pow(U256::from(18)),
+        ];
+        assert_eq!(average(vals), Some(U256::from(2) * U256::from(10).pow(U256::from(18))));
+    }
+
+    #[test]
+    fn test_summarize() {
+        let vals: Vec<f64> = vec![];
+        assert_eq!(summarize(vals), None);
+        let summary = summarize(vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]).unwrap();
+        assert_eq!(
+            summary,
+            Summary { min: 2.0, max: 9.0, mean: 5.0, median: 4.5, std_dev: 2.0, count: 8 }
+        );
+        let summary = summarize(vec![3.0]).unwrap();
+        assert_eq!(
+            summary,
+            Summary { min: 3.0, max: 3.0, mean: 3.0, median: 3.0, std_dev: 0.0, count: 1 }
+        );
+    }
+
+    #[test]
+    fn test_average_accurate() {
+        let vals: Vec<f64> = vec![];
+        assert_eq!(average_accurate(vals), None);
+        assert_eq!(average_accurate(vec![1.0, 2.0, 3.0]), Some(2.0));
+        // The naive sum loses both ones to rounding.
+        let vals = vec![1.0, 1e100, 1.0, -1e100];
+        assert_eq!(average(vals.clone()), Some(0.0));
+        assert_eq!(average_accurate(vals), Some(0.5));
+        let vals = vec![0.1; 1000];
+        assert_eq!(average_accurate(vals), Some(0.1));
+    }
+
+    #[test]
+    fn test_average_iter() {
+        assert_eq!(average_iter(core::iter::empty::<i64>()), None);
+        assert_eq!(average_iter([3, 2, 5, 7, 2, 9, 1].iter().copied()), Some(4));
+        assert_eq!(average_iter((1..=4).map(|v| v as f64)), Some(2.5));
+    }
+
+    #[test]
+    fn test_median_by_slice() {
+        let mut vals: [i64; 0] = [];
+        assert_eq!(median_by_slice(&mut vals, i64::cmp), None);
+        let mut vals = [3, 2, 5, 7, 2, 9, 1];
+        assert_eq!(median_by_slice(&mut vals, i64::cmp), Some(3));
+        let mut vals = [3, 2, 5, 7, 2, 10, 32, 1];
+        assert_eq!(median_by_slice(&mut vals, i64::cmp), Some(4));
+        let mut vals = [13.0, 36.2];
+        assert_eq!(median_by_slice(&mut vals, cmp::fcmp), Some(24.6));
+    }
+
+    #[test]
+    fn test_weighted_average() {
+        let vals: Vec<(f64, u64)> = vec![];
+        assert_eq!(weighted_average(vals), None);
+        assert_eq!(weighted_average(vec![(10.0, 1u64), (20.0, 3)]), Some(17.5));
+        assert_eq!(weighted_average(vec![(10.0, 0u64), (20.0, 0)]), None);
+        assert_eq!(weighted_average(vec![(10.0, -1i64), (20.0, 3)]), None);
+    }
+
+    #[test]
+    fn test_vwap() {
+        assert_eq!(vwap(vec![(100.0, 2.0), (110.0, 8.0)]), Some(108.0));
+        assert_eq!(vwap(vec![(100.0, 0u64)]), None);
+    }
+
+    #[test]
+    fn test_twap() {
+        let vals: Vec<(f64, i64)> = vec![];
+        assert_eq!(twap(vals), None);
+        assert_eq!(twap(vec![(100.0, 1000)]), None);
+        assert_eq!(twap(vec![(100.0, 1000), (100.0, 1000)]), None);
+        // 100 for 30s, then 130 for 10s.
+        assert_eq!(twap(vec![(130.0, 1030), (100.0, 1000), (999.0, 1040)]), Some(107.5));
+        assert_eq!(twap(vec![(1.0, i64::MIN), (3.0, 0), (5.0, i64::MAX)]), Some(2.0));
+    }
+
+    #[test]
+    fn test_ema() {
+        let vals: Vec<f64> = vec![];
+        assert_eq!(ema(vals, 0.5), None);
+        assert_eq!(ema(vec![10.0], 0.5), Some(10.0));
+        assert_eq!(ema(vec![10.0, 20.0, 30.0], 0.5), Some(22.5));
+        assert_eq!(ema(vec![10.0, 20.0, 30.0], 1.0), Some(30.0));
+        assert_eq!(ema(vec![10.0, 20.0], 0.0), None);
+        assert_eq!(ema(vec![10.0, 20.0], 1.5), None);
+        assert_eq!(ema(vec![10.0, 20.0], f64::NAN), None);
+    }
+
+    #[test]
+    fn test_weighted_average_integer() {
+        let vals: Vec<(i64, u64)> = vec![];
+        assert_eq!(weighted_average_integer(vals), None);
+        assert_eq!(weighted_average_integer(vec![(10i64, 1u64), (20, 3)]), Some(17));
+        assert_eq!(weighted_average_integer(vec![(-10i64, 1u64), (-20, 3)]), Some(-17));
+        assert_eq!(
+            weighted_average_integer(vec![(u64::MAX, 1u64 << 32), (u64::MAX, 1)]),
+            Some(u64::MAX)
+        );
+        assert_eq!(weighted_average_integer(vec![(u64::MAX, u64::MAX)]), None);
+        assert_eq!(weighted_average_integer(vec![(10i64, -1i64)]), None);
+        assert_eq!(weighted_average_integer(vec![(i128::MAX, 2i64)]), None);
+    }
+
+    #[test]
+    fn test_geometric_mean() {
+        let vals: Vec<f64> = vec![];
+        assert_eq!(geometric_mean(vals), None);
+        assert!((geometric_mean(vec![2.0, 8.0]).unwrap() - 4.0).abs() < 1e-12);
+        assert!((geometric_mean(vec![1e300, 1e300]).unwrap() - 1e300).abs() < 1e288);
+        assert_eq!(geometric_mean(vec![0.0, 8.0]), Some(0.0));
+        assert_eq!(geometric_mean(vec![-2.0, 8.0]), None);
+    }
+
+    #[test]
+    fn test_harmonic_mean() {
+        let vals: Vec<f64> = vec![];
+        assert_eq!(harmonic_mean(vals), None);
+        assert!((harmonic_mean(vec![1.0, 4.0, 4.0]).unwrap() - 2.0).abs() < 1e-12);
+        assert_eq!(harmonic_mean(vec![0.0, 4.0]), None);
+    }
+
+    #[test]
+    fn test_geometric_mean_integer() {
+        let vals: Vec<u64> = vec![];
+        assert_eq!(geometric_mean_integer(vals), None);
+        assert_eq!(geometric_mean_integer(vec![2u64, 8]), Some(4));
+        assert_eq!(geometric_mean_integer(vec![2u64, 9]), Some(4));
+        assert_eq!(geometric_mean_integer(vec![7i64]), Some(7));
+        assert_eq!(geometric_mean_integer(vec![0i64, 8]), None);
+        assert_eq!(geometric_mean_integer(vec![u64::MAX, 2]), None);
+        assert_eq!(geometric_mean_integer(vec![1u8, 255]), Some(15));
+    }
+
+    #[test]
+    fn test_harmonic_mean_integer() {
+        let vals: Vec<u64> = vec![];
+        assert_eq!(harmonic_mean_integer(vals), None);
+        assert_eq!(harmonic_mean_integer(vec![1u64, 4, 4]), Some(2));
+        assert_eq!(harmonic_mean_integer(vec![2u64, 3]), Some(2));
+        assert_eq!(harmonic_mean_integer(vec![0i64, 4]), None);
+        assert_eq!(harmonic_mean_integer(vec![u64::MAX, u64::MAX - 1]), None);
+    }
+
+    #[test]
+    fn test_median_absolute_deviation() {
+        let vals: Vec<f64> = vec![];
+        assert_eq!(median_absolute_deviation(vals), None);
+        assert_eq!(median_absolute_deviation(vec![1.0, 1.0, 2.0, 2.0, 4.0, 6.0, 9.0]), Some(1.0));
+        assert_eq!(median_absolute_deviation(vec![3.0, 8.0]), Some(2.5));
+    }
+
+    #[test]
+    fn test_median_absolute_deviation_integer() {
+        let vals: Vec<i64> = vec![];
+        assert_eq!(median_absolute_deviation_integer(vals), None);
+        assert_eq!(median_absolute_deviation_integer(vec![1, 1, 2, 2, 4, 6, 9]), Some(1));
+        assert_eq!(median_absolute_deviation_integer(vec![-5, 0, 5]), Some(5));
+        assert_eq!(median_absolute_deviation_integer(vec![10u64, 2, 7]), Some(3));
+    }
+
+    #[test]
+    fn test_within_deviation() {
+        let vals: Vec<f64> = vec![];
+        assert_eq!(within_deviation(vals, 0.05), None);
+        let vals = vec![100.0, 102.0, 98.0, 104.0];
+        assert_eq!(within_deviation(vals.clone(), 0.05), Some(vals.clone()));
+        assert_eq!(within_deviation(vals, 0.01), None);
+        assert_eq!(within_deviation(vec![0.0, 0.0], 0.05), Some(vec![0.0, 0.0]));
+    }
+
+    #[test]
+    fn test_within_deviation_tolerant() {
+        let vals = vec![100.0, 101.0, 99.0, 150.0, 50.0];
+        assert_eq!(within_deviation_tolerant(vals.clone(), 0.05, 1), None);
+        assert_eq!(within_deviation_tolerant(vals, 0.05, 2), Some(vec![100.0, 101.0, 99.0]));
+    }
+
+    #[test]
+    fn test_covariance() {
+        assert_eq!(covariance::<f64>(vec![], vec![]), None);
+        assert_eq!(covariance(vec![1.0, 2.0], vec![1.0]), None);
+        assert_eq!(covariance(vec![1.0, 2.0, 3.0], vec![2.0, 4.0, 6.0]), Some(4.0 / 3.0));
+        assert_eq!(covariance(vec![1.0, 2.0, 3.0], vec![5.0, 5.0, 5.0]), Some(0.0));
+    }
+
+    #[test]
+    fn test_pearson_correlation() {
+        let r = pearson_correlation(vec![1.0, 2.0, 3.0, 4.0], vec![10.0, 20.0, 30.0, 40.0]);
+        assert!((r.unwrap() - 1.0).abs() < 1e-12);
+        let r = pearson_correlation(vec![1.0, 2.0, 3.0, 4.0], vec![4.0, 3.0, 2.0, 1.0]);
+        assert!((r.unwrap() + 1.0).abs() < 1e-12);
+        let r = pearson_correlation(vec![1.0, 2.0, 3.0, 4.0], vec![1.0, 3.0, 2.0, 4.0]);
+        assert!((r.unwrap() - 0.8).abs() < 1e-12);
+        assert_eq!(pearson_correlation(vec![1.0, 2.0], vec![5.0, 5.0]), None);
+        assert_eq!(pearson_correlation(vec![1.0, 2.0], vec![5.0]), None);
+    }
+
+    #[test]
+    fn test_average_some() {
+        assert_eq!(average_some::<i64>(vec![None, None]), (None, 0));
+        assert_eq!(average_some(vec![Some(3), None, Some(5)]), (Some(4), 2));
+    }
+
+    #[test]
+    fn test_median_with_min_count() {
+        assert_eq!(median_with_min_count(vec![3, 1, 2], 3, i64::cmp), Some(2));
+        assert_eq!(median_with_min_count(vec![3, 1, 2], 4, i64::cmp), None);
+        assert_eq!(median_with_min_count::<i64, _>(vec![], 0, i64::cmp), None);
+        assert_eq!(median_with_min_count(vec![1.0, 2.0], 2, cmp::fcmp), Some(1.5));
+    }
+
+    #[test]
+    fn test_median_some() {
+        assert_eq!(median_some::<i64, _>(vec![], i64::cmp), (None, 0));
+        assert_eq!(median_some(vec![Some(3), None, Some(5), Some(1)], i64::cmp), (Some(3), 3));
+        assert_eq!(median_some(vec![Some(1.0), None, Some(2.0)], cmp::fcmp), (Some(1.5), 2));
+    }
+
+    #[test]
+    fn test_majority_some() {
+        assert_eq!(
+            majority_some(vec![Some("a"), None, None, Some("a"), Some("b")]),
+            (Some("a"), 3)
+        );
+        assert_eq!(majority_some(vec![Some("a"), None, Some("b")]), (None, 2));
+    }
+
+    #[test]
+    fn test_mode() {
+        let vals: Vec<i64> = vec![];
+        assert_eq!(mode(vals), None);
+        assert_eq!(mode(vec![3, 1, 2, 3, 1, 3]), Some(3));
+        assert_eq!(mode(vec!["home", "away", "draw", "away"]), Some("away"));
+        assert_eq!(mode(vec![2, 1, 2, 1]), Some(1));
+    }
+
+    #[test]
+    fn test_top_k_frequent() {
+        let vals = vec!["a", "b", "c", "b", "a", "b", "d"];
+        assert_eq!(top_k_frequent(vals.clone(), 2), vec![("b", 3), ("a", 2)]);
+        assert_eq!(top_k_frequent(vals.clone(), 3), vec![("b", 3), ("a", 2), ("c", 1)]);
+        assert_eq!(top_k_frequent(vals.clone(), 0), vec![]);
+        assert_eq!(top_k_frequent(vals, 10).len(), 4);
+        let vals: Vec<i64> = vec![];
+        assert_eq!(top_k_frequent(vals, 1), vec![]);
+    }
+
     #[test]
     fn test_majority_int() {
         let vals = vec![1, 2, 3, 1, 3, 1, 1];
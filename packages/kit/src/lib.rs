@@ -1,5 +1,35 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 #[macro_use]
 mod macros;
 
+mod script;
+
 pub mod ext;
 pub mod oei;
+#[cfg(all(feature = "testing", not(target_arch = "wasm32")))]
+pub mod testing;
+pub mod version;
+
+pub use script::OracleScript;
+
+// Items named by the exported and attribute macros, which expand in script crates that may
+// not depend on them directly.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::oei;
+    pub use alloc::vec::Vec;
+    #[cfg(feature = "borsh")]
+    pub use borsh;
+    #[cfg(feature = "std")]
+    pub use obi;
+    #[cfg(feature = "protobuf")]
+    pub use prost;
+    #[cfg(any(feature = "json", feature = "cbor"))]
+    pub use serde;
+}
+
+#[cfg(feature = "macros")]
+pub use owasm_kit_macros::{oracle_execute, oracle_prepare};
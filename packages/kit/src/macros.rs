@@ -1,9 +1,74 @@
+/// Asserts at compile time that the given function takes an `OBIDecode` input and, for
+/// execute, returns an `OBIEncode` output, so a mismatched signature is reported at the
+/// entry-point macro rather than inside the generated code. Entry points for other
+/// encodings give the input and output bounds in brackets, and raw entry points take the
+/// calldata bytes and return any `AsRef<[u8]>`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __check_entry_point {
+    (prepare, $name:ident, raw) => {
+        const _: () = {
+            const fn check(_: fn($crate::__private::Vec<u8>)) {}
+            check($name)
+        };
+    };
+    (execute, $name:ident, raw) => {
+        const _: () = {
+            const fn check<O: AsRef<[u8]>>(_: fn($crate::__private::Vec<u8>) -> O) {}
+            check($name)
+        };
+    };
+    (prepare, $name:ident: [$($input:tt)+]) => {
+        const _: () = {
+            const fn check<I: $($input)+>(_: fn(I)) {}
+            check($name)
+        };
+    };
+    (execute, $name:ident: [$($input:tt)+] => [$($output:tt)+]) => {
+        const _: () = {
+            const fn check<I: $($input)+, O: $($output)+>(_: fn(I) -> O) {}
+            check($name)
+        };
+    };
+    (prepare, $name:ident) => {
+        const _: () = {
+            const fn check<I: OBIDecode>(_: fn(I)) {}
+            check($name)
+        };
+    };
+    (prepare, $name:ident, Result) => {
+        const _: () = {
+            const fn check<I: OBIDecode, E: Into<oei::ReturnError>>(_: fn(I) -> Result<(), E>) {}
+            check($name)
+        };
+    };
+    (execute, $name:ident) => {
+        const _: () = {
+            const fn check<I: OBIDecode, O: OBIEncode>(_: fn(I) -> O) {}
+            check($name)
+        };
+    };
+    (execute, $name:ident, Result) => {
+        const _: () = {
+            const fn check<I: OBIDecode, O: OBIEncode, E: Into<oei::ReturnError>>(
+                _: fn(I) -> Result<O, E>,
+            ) {
+            }
+            check($name)
+        };
+    };
+}
+
 #[macro_export]
 macro_rules! prepare_entry_point {
     ($name:ident) => {
+        $crate::__check_entry_point!(prepare, $name);
+
         #[no_mangle]
         pub fn prepare() {
-            $name(OBIDecode::try_from_slice(&oei::get_calldata()).unwrap());
+            $name(
+                OBIDecode::try_from_slice(&oei::get_calldata()).expect("failed to decode calldata"),
+            );
         }
     };
 }
@@ -11,13 +76,598 @@ macro_rules! prepare_entry_point {
 #[macro_export]
 macro_rules! execute_entry_point {
     ($name:ident) => {
+        $crate::__check_entry_point!(execute, $name);
+
         #[no_mangle]
         pub fn execute() {
             oei::save_return_data(
-                &$name(OBIDecode::try_from_slice(&oei::get_calldata()).unwrap())
-                    .try_to_vec()
-                    .unwrap(),
+                &$name(
+                    OBIDecode::try_from_slice(&oei::get_calldata())
+                        .expect("failed to decode calldata"),
+                )
+                .try_to_vec()
+                .expect("failed to encode return data"),
             );
         }
     };
+    // The function returns a Result whose error converts into `oei::ReturnError`, which
+    // is saved with `oei::save_return_error` instead of trapping.
+    ($name:ident, Result) => {
+        $crate::__check_entry_point!(execute, $name, Result);
+
+        #[no_mangle]
+        pub fn execute() {
+            match $name(
+                OBIDecode::try_from_slice(&oei::get_calldata()).expect("failed to decode calldata"),
+            ) {
+                Ok(result) => oei::save_return_data(
+                    &result.try_to_vec().expect("failed to encode return data"),
+                ),
+                Err(err) => {
+                    let err: oei::ReturnError = err.into();
+                    oei::save_return_error(err.code, &err.message)
+                }
+            }
+        }
+    };
+}
+
+/// Same as `prepare_entry_point!`, for calldata encoded as JSON.
+#[macro_export]
+macro_rules! prepare_json_entry_point {
+    ($name:ident) => {
+        $crate::__check_entry_point!(
+            prepare,
+            $name: [$crate::__private::serde::de::DeserializeOwned]
+        );
+
+        #[no_mangle]
+        pub fn prepare() {
+            $name(oei::get_calldata_json().expect("failed to decode calldata"));
+        }
+    };
+}
+
+/// Same as `execute_entry_point!`, for calldata and return data encoded as JSON.
+#[macro_export]
+macro_rules! execute_json_entry_point {
+    ($name:ident) => {
+        $crate::__check_entry_point!(
+            execute,
+            $name: [$crate::__private::serde::de::DeserializeOwned]
+                => [$crate::__private::serde::Serialize]
+        );
+
+        #[no_mangle]
+        pub fn execute() {
+            oei::save_return_data_json(&$name(
+                oei::get_calldata_json().expect("failed to decode calldata"),
+            ))
+            .expect("failed to encode return data");
+        }
+    };
+}
+
+/// Same as `prepare_entry_point!`, but passes the raw calldata bytes without decoding, for
+/// scripts with their own wire format.
+#[macro_export]
+macro_rules! prepare_raw_entry_point {
+    ($name:ident) => {
+        $crate::__check_entry_point!(prepare, $name, raw);
+
+        #[no_mangle]
+        pub fn prepare() {
+            $name(oei::get_calldata());
+        }
+    };
+}
+
+/// Same as `execute_entry_point!`, but passes the raw calldata bytes without decoding and
+/// saves the returned bytes, of any `AsRef<[u8]>` type, as they are.
+#[macro_export]
+macro_rules! execute_raw_entry_point {
+    ($name:ident) => {
+        $crate::__check_entry_point!(execute, $name, raw);
+
+        #[no_mangle]
+        pub fn execute() {
+            oei::save_return_data(AsRef::<[u8]>::as_ref(&$name(oei::get_calldata())));
+        }
+    };
+}
+
+/// Same as `prepare_entry_point!`, for calldata encoded as CBOR.
+#[macro_export]
+macro_rules! prepare_cbor_entry_point {
+    ($name:ident) => {
+        $crate::__check_entry_point!(
+            prepare,
+            $name: [$crate::__private::serde::de::DeserializeOwned]
+        );
+
+        #[no_mangle]
+        pub fn prepare() {
+            $name(oei::get_calldata_cbor().expect("failed to decode calldata"));
+        }
+    };
+}
+
+/// Same as `execute_entry_point!`, for calldata and return data encoded as CBOR.
+#[macro_export]
+macro_rules! execute_cbor_entry_point {
+    ($name:ident) => {
+        $crate::__check_entry_point!(
+            execute,
+            $name: [$crate::__private::serde::de::DeserializeOwned]
+                => [$crate::__private::serde::Serialize]
+        );
+
+        #[no_mangle]
+        pub fn execute() {
+            oei::save_return_data_cbor(&$name(
+                oei::get_calldata_cbor().expect("failed to decode calldata"),
+            ))
+            .expect("failed to encode return data");
+        }
+    };
+}
+
+/// Same as `prepare_entry_point!`, for calldata encoded as Borsh.
+#[macro_export]
+macro_rules! prepare_borsh_entry_point {
+    ($name:ident) => {
+        $crate::__check_entry_point!(prepare, $name: [$crate::__private::borsh::BorshDeserialize]);
+
+        #[no_mangle]
+        pub fn prepare() {
+            $name(oei::get_calldata_borsh().expect("failed to decode calldata"));
+        }
+    };
+}
+
+/// Same as `execute_entry_point!`, for calldata and return data encoded as Borsh.
+#[macro_export]
+macro_rules! execute_borsh_entry_point {
+    ($name:ident) => {
+        $crate::__check_entry_point!(
+            execute,
+            $name: [$crate::__private::borsh::BorshDeserialize]
+                => [$crate::__private::borsh::BorshSerialize]
+        );
+
+        #[no_mangle]
+        pub fn execute() {
+            oei::save_return_data_borsh(&$name(
+                oei::get_calldata_borsh().expect("failed to decode calldata"),
+            ))
+            .expect("failed to encode return data");
+        }
+    };
+}
+
+/// Same as `prepare_entry_point!`, for calldata encoded as a protobuf message.
+#[macro_export]
+macro_rules! prepare_protobuf_entry_point {
+    ($name:ident) => {
+        $crate::__check_entry_point!(
+            prepare,
+            $name: [$crate::__private::prost::Message + Default]
+        );
+
+        #[no_mangle]
+        pub fn prepare() {
+            $name(oei::get_calldata_protobuf().expect("failed to decode calldata"));
+        }
+    };
+}
+
+/// Same as `execute_entry_point!`, for calldata and return data encoded as protobuf
+/// messages.
+#[macro_export]
+macro_rules! execute_protobuf_entry_point {
+    ($name:ident) => {
+        $crate::__check_entry_point!(
+            execute,
+            $name: [$crate::__private::prost::Message + Default]
+                => [$crate::__private::prost::Message]
+        );
+
+        #[no_mangle]
+        pub fn execute() {
+            oei::save_return_data_protobuf(&$name(
+                oei::get_calldata_protobuf().expect("failed to decode calldata"),
+            ));
+        }
+    };
+}
+
+/// Same as `prepare_entry_point!`, for calldata encoded with the Ethereum ABI. The input
+/// can be any `ext::abi::AbiType`, with a tuple read as a list of parameters.
+#[macro_export]
+macro_rules! prepare_abi_entry_point {
+    ($name:ident) => {
+        $crate::__check_entry_point!(prepare, $name: [$crate::ext::abi::AbiType]);
+
+        #[no_mangle]
+        pub fn prepare() {
+            $name(oei::get_calldata_abi().expect("failed to decode calldata"));
+        }
+    };
+}
+
+/// Same as `execute_entry_point!`, for calldata and return data encoded with the Ethereum
+/// ABI, so EVM contracts can decode the result with `abi.decode`.
+#[macro_export]
+macro_rules! execute_abi_entry_point {
+    ($name:ident) => {
+        $crate::__check_entry_point!(
+            execute,
+            $name: [$crate::ext::abi::AbiType] => [$crate::ext::abi::AbiType]
+        );
+
+        #[no_mangle]
+        pub fn execute() {
+            oei::save_return_data_abi($name(
+                oei::get_calldata_abi().expect("failed to decode calldata"),
+            ));
+        }
+    };
+}
+
+/// Exports both `prepare` and `execute` for a type implementing `OracleScript`, decoding
+/// the same OBI input in both phases.
+#[macro_export]
+macro_rules! oracle_script_entry_point {
+    ($script:ty) => {
+        #[no_mangle]
+        pub fn prepare() {
+            <$script as $crate::OracleScript>::prepare(
+                OBIDecode::try_from_slice(&oei::get_calldata()).expect("failed to decode calldata"),
+            );
+        }
+
+        #[no_mangle]
+        pub fn execute() {
+            oei::save_return_data(
+                &<$script as $crate::OracleScript>::execute(
+                    OBIDecode::try_from_slice(&oei::get_calldata())
+                        .expect("failed to decode calldata"),
+                )
+                .try_to_vec()
+                .expect("failed to encode return data"),
+            );
+        }
+    };
+}
+
+/// Same as `prepare_entry_point!`, for a function that returns a Result whose error
+/// converts into `oei::ReturnError`. Return data cannot be saved during preparation, so
+/// an error aborts the script with its code and message.
+#[macro_export]
+macro_rules! prepare_entry_point_result {
+    ($name:ident) => {
+        $crate::__check_entry_point!(prepare, $name, Result);
+
+        #[no_mangle]
+        pub fn prepare() {
+            if let Err(err) = $name(
+                OBIDecode::try_from_slice(&oei::get_calldata()).expect("failed to decode calldata"),
+            ) {
+                let err: oei::ReturnError = err.into();
+                panic!("prepare failed with code {}: {}", err.code, err.message);
+            }
+        }
+    };
+}
+
+/// Same as `execute_entry_point!`, for a function that returns a Result whose error
+/// converts into `oei::ReturnError`. The error is saved with `oei::save_return_error`.
+#[macro_export]
+macro_rules! execute_entry_point_result {
+    ($name:ident) => {
+        $crate::execute_entry_point!($name, Result);
+    };
+}
+
+/// Exports a `get_schema` function that returns the OBI schema of the given input and
+/// output types, so the interface of a deployed script can be read from its wasm. See
+/// `ext::schema::export` for how the string is returned.
+#[macro_export]
+macro_rules! schema_entry_point {
+    ($input:ty, $output:ty) => {
+        #[no_mangle]
+        pub fn get_schema() -> i64 {
+            $crate::ext::schema::export($crate::ext::schema::obi_schema::<$input, $output>())
+        }
+    };
+}
+
+/// Same as `schema_entry_point!`, for scripts using the ABI entry points. The exported
+/// `get_abi_schema` returns the canonical Solidity types of the input and output, such as
+/// `(string,uint64)/(uint64[],bytes)`, from which EVM consumers can derive decoders.
+#[macro_export]
+macro_rules! abi_schema_entry_point {
+    ($input:ty, $output:ty) => {
+        #[no_mangle]
+        pub fn get_abi_schema() -> i64 {
+            $crate::ext::schema::export($crate::ext::abi::abi_schema::<$input, $output>())
+        }
+    };
+}
+
+/// Embeds the kit version and the given script version in the `owasm_version` custom
+/// section of the wasm, where the VM can read them at upload time.
+#[macro_export]
+macro_rules! script_version {
+    ($version:expr) => {
+        #[cfg(target_arch = "wasm32")]
+        #[link_section = "owasm_version"]
+        #[used]
+        static OWASM_VERSION: [u8; $crate::version::metadata_len($version)] =
+            $crate::version::metadata($version);
+    };
+}
+
+/// Sets a panic hook that saves the panic message with the error-return convention before
+/// the script traps. See `oei::set_panic_hook`. Call it at the start of the execute entry
+/// point.
+#[macro_export]
+macro_rules! install_oracle_panic_hook {
+    () => {
+        $crate::oei::set_panic_hook()
+    };
+}
+
+/// Generates an `oracle_test_harness` module for native tests that runs the given prepare
+/// and execute functions against an `oei::MemoryBackend`, decoding the backend's calldata
+/// and encoding the result the same way as `prepare_entry_point!` and
+/// `execute_entry_point!`. The backend is cleared even if the script panics.
+///
+/// Each case given after the functions becomes a module with a `prepare` test, which
+/// checks that preparation asks for external data, and an `execute` test, which checks
+/// that execution saves a result rather than an error from the given successful reports
+/// of each external ID:
+///
+/// ```ignore
+/// oracle_test_harness!(prepare_impl, execute_impl, {
+///     btc: Input { symbol: "BTC".into() } => { 1: ["64000", "64100", "63900"] },
+/// });
+/// ```
+///
+/// The `run_prepare` and `run_execute` helpers are public for tests that need to inspect
+/// the backend:
+///
+/// ```ignore
+/// #[test]
+/// fn prepare_asks_coingecko() {
+///     let mut backend = oei::MemoryBackend::new(1024);
+///     backend.calldata = Input { symbol: "BTC".into() }.try_to_vec().unwrap();
+///     let backend = oracle_test_harness::run_prepare(backend);
+///     assert_eq!(backend.asked.borrow().len(), 1);
+/// }
+/// ```
+#[macro_export]
+macro_rules! oracle_test_harness {
+    ($prepare:ident, $execute:ident) => {
+        $crate::oracle_test_harness!($prepare, $execute, {});
+    };
+    (
+        $prepare:ident, $execute:ident, {
+            $($case:ident: $input:expr => {
+                $($eid:literal: [$($report:expr),* $(,)?]),* $(,)?
+            }),* $(,)?
+        }
+    ) => {
+        #[cfg(test)]
+        mod oracle_test_harness {
+            use super::*;
+            use std::rc::Rc;
+
+            struct ClearBackend;
+
+            impl Drop for ClearBackend {
+                fn drop(&mut self) {
+                    oei::clear_backend();
+                }
+            }
+
+            /// Runs prepare against the given backend.
+            pub fn run_prepare(backend: oei::MemoryBackend) -> Rc<oei::MemoryBackend> {
+                let backend = Rc::new(backend);
+                oei::set_backend(backend.clone());
+                let _guard = ClearBackend;
+                $prepare(
+                    OBIDecode::try_from_slice(&oei::get_calldata())
+                        .expect("failed to decode calldata"),
+                );
+                backend
+            }
+
+            /// Runs execute against the given backend and saves its encoded result.
+            pub fn run_execute(backend: oei::MemoryBackend) -> Rc<oei::MemoryBackend> {
+                let backend = Rc::new(backend);
+                oei::set_backend(backend.clone());
+                let _guard = ClearBackend;
+                oei::save_return_data(
+                    &$execute(
+                        OBIDecode::try_from_slice(&oei::get_calldata())
+                            .expect("failed to decode calldata"),
+                    )
+                    .try_to_vec()
+                    .expect("failed to encode return data"),
+                );
+                backend
+            }
+
+            $(
+                mod $case {
+                    use super::*;
+
+                    fn backend() -> oei::MemoryBackend {
+                        let mut backend = oei::MemoryBackend::new(1 << 20);
+                        backend.calldata = ($input).try_to_vec().expect("failed to encode input");
+                        backend
+                    }
+
+                    #[test]
+                    fn prepare() {
+                        let backend = run_prepare(backend());
+                        let asked = backend.asked.borrow();
+                        assert!(!asked.is_empty(), "prepare asked no external data");
+                    }
+
+                    #[test]
+                    fn execute() {
+                        let mut backend = backend();
+                        $(
+                            let reports = [$(AsRef::<[u8]>::as_ref(&$report)),*];
+                            for (vid, report) in reports.iter().enumerate() {
+                                backend.reports.insert(($eid, vid as i64), (0, report.to_vec()));
+                                backend.ask_count = backend.ask_count.max(vid as i64 + 1);
+                            }
+                        )*
+                        backend.min_count = backend.ask_count;
+                        backend.ans_count = backend.ask_count;
+                        let backend = run_execute(backend);
+                        let data = backend.return_data.borrow().clone();
+                        let data = data.expect("execute saved no return data");
+                        if let Some(err) = oei::ReturnError::decode(&data) {
+                            panic!("execute failed with code {}: {}", err.code, err.message);
+                        }
+                    }
+                }
+            )*
+        }
+    };
+}
+
+/// Declares the data sources of an oracle script as a struct with one field per source.
+/// Each field gives the data source ID, a function from the input to the calldata, and a
+/// function from the external ID to the aggregated value, such as `ext::load_median_float`.
+/// External IDs are assigned from 1 in field order, so `ask` and `load` always agree.
+///
+/// ```ignore
+/// decl_data! {
+///     pub struct Prices for Input {
+///         pub coingecko: f64 = (1, |i: &Input| i.symbol.clone().into_bytes(), ext::load_median_float),
+///         pub binance: f64 = (2, |i: &Input| i.symbol.clone().into_bytes(), ext::load_median_float),
+///     }
+/// }
+/// ```
+///
+/// `Prices::ask(&input)` issues the raw requests during preparation, and `Prices::load()`
+/// aggregates the reports during execution, with None for sources that fail to aggregate.
+#[macro_export]
+macro_rules! decl_data {
+    (
+        $vis:vis struct $name:ident for $input:ty {
+            $($fvis:vis $field:ident : $ty:ty = ($did:expr, $calldata:expr, $aggregate:expr)),* $(,)?
+        }
+    ) => {
+        $vis struct $name {
+            $($fvis $field: Option<$ty>,)*
+        }
+
+        impl $name {
+            /// Asks every data source with the calldata for the given input.
+            pub fn ask(input: &$input) {
+                let mut eid: i64 = 0;
+                $(
+                    eid += 1;
+                    $crate::oei::ask_external_data(eid, $did, &($calldata)(input));
+                )*
+            }
+
+            /// Aggregates the reports of every data source.
+            pub fn load() -> Self {
+                let mut eid: i64 = 0;
+                $name {
+                    $($field: {
+                        eid += 1;
+                        ($aggregate)(eid)
+                    },)*
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ext, oei};
+    use std::rc::Rc;
+
+    struct Input {
+        symbol: String,
+    }
+
+    decl_data! {
+        struct Prices for Input {
+            coingecko: f64 = (3, |i: &Input| i.symbol.clone().into_bytes(), ext::load_median_float),
+            binance: u64 = (7, |i: &Input| format!("{}USDT", i.symbol).into_bytes(), ext::load_median_integer),
+            kraken: f64 = (9, |_: &Input| vec![], ext::load_average),
+        }
+    }
+
+    #[test]
+    fn decl_data_test() {
+        let backend = Rc::new(oei::MemoryBackend::new(64));
+        oei::set_backend(backend.clone());
+        Prices::ask(&Input { symbol: String::from("BTC") });
+        assert_eq!(
+            *backend.asked.borrow(),
+            vec![(1, 3, b"BTC".to_vec()), (2, 7, b"BTCUSDT".to_vec()), (3, 9, vec![])]
+        );
+
+        let mut backend = oei::MemoryBackend::new(64);
+        backend.ask_count = 2;
+        for vid in 0..2 {
+            backend.reports.insert((1, vid), (0, format!("{}.5", 100 + vid).into_bytes()));
+            backend.reports.insert((2, vid), (0, format!("{}", 200 + vid * 2).into_bytes()));
+        }
+        oei::set_backend(Rc::new(backend));
+        let prices = Prices::load();
+        assert_eq!(prices.coingecko, Some(101.0));
+        assert_eq!(prices.binance, Some(201));
+        assert_eq!(prices.kraken, None);
+        oei::clear_backend();
+    }
+
+    mod harness {
+        use crate::{ext, oei};
+        use obi::{OBIDecode, OBIEncode};
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        fn prepare_impl(symbol: String) {
+            oei::ask_external_data(1, 1, symbol.as_bytes());
+        }
+
+        fn execute_impl(_: String) -> u64 {
+            ext::load_median_integer(1).unwrap()
+        }
+
+        oracle_test_harness!(prepare_impl, execute_impl, {
+            btc: String::from("BTC") => { 1: ["64000", "64100", "63900"] },
+        });
+
+        #[test]
+        fn run_execute_test() {
+            let mut backend = oei::MemoryBackend::new(64);
+            backend.calldata = String::from("BTC").try_to_vec().unwrap();
+            backend.ask_count = 1;
+            backend.reports.insert((1, 0), (0, b"64000".to_vec()));
+            let backend = oracle_test_harness::run_execute(backend);
+            let data = backend.return_data.borrow().clone().unwrap();
+            assert_eq!(u64::try_from_slice(&data).unwrap(), 64000);
+        }
+
+        #[test]
+        fn run_prepare_panic_test() {
+            // Empty calldata fails to decode, so prepare panics after the backend is set.
+            let backend = oei::MemoryBackend::new(64);
+            assert!(catch_unwind(AssertUnwindSafe(|| oracle_test_harness::run_prepare(backend)))
+                .is_err());
+            assert!(catch_unwind(oei::get_ask_count).is_err());
+        }
+    }
 }
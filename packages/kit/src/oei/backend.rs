@@ -0,0 +1,324 @@
+#[cfg(target_arch = "wasm32")]
+use super::raw;
+use super::OeiError;
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::{cell::RefCell, collections::HashMap};
+
+/// The host functions behind `oei`. Oracle scripts run against the wasm imports provided
+/// by the VM, while native tests install another backend with `set_backend`.
+pub trait OeiBackend {
+    fn get_span_size(&self) -> i64;
+    fn get_ask_count(&self) -> i64;
+    fn get_min_count(&self) -> i64;
+    fn get_prepare_time(&self) -> i64;
+    fn get_execute_time(&self) -> i64;
+    fn get_ans_count(&self) -> i64;
+    fn read_calldata(&self) -> Result<Vec<u8>, OeiError>;
+    fn set_return_data(&self, data: &[u8]);
+    fn ask_external_data(&self, eid: i64, did: i64, calldata: &[u8]);
+    fn get_external_data_status(&self, eid: i64, vid: i64) -> i64;
+    fn read_external_data(&self, eid: i64, vid: i64) -> Result<Vec<u8>, OeiError>;
+
+    fn ecvrf_verify(&self, _y: &[u8], _pi: &[u8], _alpha: &[u8]) -> u32 {
+        panic!("ecvrf_verify is not supported by this backend")
+    }
+
+    fn groth16_verify(&self, _vk: &[u8], _proof: &[u8], _public_inputs: &[u8]) -> u32 {
+        panic!("groth16_verify is not supported by this backend")
+    }
+
+    fn poseidon_hash(&self, _inputs: &[[u8; 32]], _output: &mut [u8; 32]) -> u32 {
+        panic!("poseidon_hash is not supported by this backend")
+    }
+}
+
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+thread_local! {
+    static BACKEND: RefCell<Option<Rc<dyn OeiBackend>>> = RefCell::new(None);
+}
+
+/// Routes all `oei` calls on the current thread to the given backend.
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+pub fn set_backend(backend: Rc<dyn OeiBackend>) {
+    BACKEND.with(|b| *b.borrow_mut() = Some(backend));
+}
+
+/// Removes the backend installed on the current thread with `set_backend`.
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+pub fn clear_backend() {
+    BACKEND.with(|b| *b.borrow_mut() = None);
+}
+
+// Oracle scripts always call the wasm imports. Dispatching on the concrete type keeps
+// each import out of the module unless the script uses it, as `owasm_vm::compile`
+// rejects imports the VM does not provide.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn with_backend<R>(f: impl FnOnce(&WasmBackend) -> R) -> R {
+    f(&WasmBackend)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn with_backend<R>(f: impl FnOnce(&dyn OeiBackend) -> R) -> R {
+    #[cfg(feature = "std")]
+    if let Some(backend) = BACKEND.with(|b| b.borrow().clone()) {
+        return f(&*backend);
+    }
+    panic!("no oei backend is set; call oei::set_backend first")
+}
+
+// Returns whether `with_backend` has a backend to call on the current thread.
+#[cfg(feature = "std")]
+pub(crate) fn has_backend() -> bool {
+    #[cfg(target_arch = "wasm32")]
+    return true;
+    #[cfg(not(target_arch = "wasm32"))]
+    BACKEND.with(|b| b.borrow().is_some())
+}
+
+// Reads a host value of at most `span_size` bytes. `read` writes into the given pointer
+// and returns the number of bytes written.
+#[cfg(any(target_arch = "wasm32", test))]
+pub(crate) fn read_span(
+    span_size: i64,
+    read: impl FnOnce(i64) -> i64,
+) -> Result<Vec<u8>, OeiError> {
+    let mut data = Vec::with_capacity(span_size.max(0) as usize);
+    let len = read(data.as_mut_ptr() as i64);
+    if len < 0 || len as usize > data.capacity() {
+        return Err(OeiError::SpanExceeded);
+    }
+    unsafe { data.set_len(len as usize) };
+    Ok(data)
+}
+
+/// The wasm imports provided by the owasm VM.
+#[cfg(target_arch = "wasm32")]
+pub struct WasmBackend;
+
+#[cfg(target_arch = "wasm32")]
+impl OeiBackend for WasmBackend {
+    fn get_span_size(&self) -> i64 {
+        use core::sync::atomic::{AtomicI64, Ordering};
+        // The span size cannot change during a single run, so the host is only queried once.
+        static SPAN_SIZE: AtomicI64 = AtomicI64::new(-1);
+        let span_size = SPAN_SIZE.load(Ordering::Relaxed);
+        if span_size >= 0 {
+            return span_size;
+        }
+        let span_size = unsafe { raw::get_span_size() };
+        SPAN_SIZE.store(span_size, Ordering::Relaxed);
+        span_size
+    }
+
+    fn get_ask_count(&self) -> i64 {
+        unsafe { raw::get_ask_count() }
+    }
+
+    fn get_min_count(&self) -> i64 {
+        unsafe { raw::get_min_count() }
+    }
+
+    fn get_prepare_time(&self) -> i64 {
+        unsafe { raw::get_prepare_time() }
+    }
+
+    fn get_execute_time(&self) -> i64 {
+        unsafe { raw::get_execute_time() }
+    }
+
+    fn get_ans_count(&self) -> i64 {
+        unsafe { raw::get_ans_count() }
+    }
+
+    fn read_calldata(&self) -> Result<Vec<u8>, OeiError> {
+        read_span(self.get_span_size(), |ptr| unsafe { raw::read_calldata(ptr) })
+    }
+
+    fn set_return_data(&self, data: &[u8]) {
+        unsafe { raw::set_return_data(data.as_ptr() as i64, data.len() as i64) }
+    }
+
+    fn ask_external_data(&self, eid: i64, did: i64, calldata: &[u8]) {
+        unsafe { raw::ask_external_data(eid, did, calldata.as_ptr() as i64, calldata.len() as i64) }
+    }
+
+    fn get_external_data_status(&self, eid: i64, vid: i64) -> i64 {
+        unsafe { raw::get_external_data_status(eid, vid) }
+    }
+
+    fn read_external_data(&self, eid: i64, vid: i64) -> Result<Vec<u8>, OeiError> {
+        read_span(self.get_span_size(), |ptr| unsafe { raw::read_external_data(eid, vid, ptr) })
+    }
+
+    fn ecvrf_verify(&self, y: &[u8], pi: &[u8], alpha: &[u8]) -> u32 {
+        unsafe {
+            raw::ecvrf_verify(
+                y.as_ptr() as i64,
+                y.len() as i64,
+                pi.as_ptr() as i64,
+                pi.len() as i64,
+                alpha.as_ptr() as i64,
+                alpha.len() as i64,
+            )
+        }
+    }
+
+    fn groth16_verify(&self, vk: &[u8], proof: &[u8], public_inputs: &[u8]) -> u32 {
+        unsafe {
+            raw::groth16_verify(
+                vk.as_ptr() as i64,
+                vk.len() as i64,
+                proof.as_ptr() as i64,
+                proof.len() as i64,
+                public_inputs.as_ptr() as i64,
+                public_inputs.len() as i64,
+            )
+        }
+    }
+
+    fn poseidon_hash(&self, inputs: &[[u8; 32]], output: &mut [u8; 32]) -> u32 {
+        unsafe {
+            raw::poseidon_hash(
+                inputs.as_ptr() as i64,
+                (inputs.len() * 32) as i64,
+                output.as_mut_ptr() as i64,
+            )
+        }
+    }
+}
+
+/// An in-memory backend for running prepare and execute logic in native tests. Requests
+/// and return data are recorded for inspection.
+#[cfg(feature = "std")]
+#[derive(Default)]
+pub struct MemoryBackend {
+    pub span_size: i64,
+    pub ask_count: i64,
+    pub min_count: i64,
+    pub prepare_time: i64,
+    pub execute_time: i64,
+    pub ans_count: i64,
+    pub calldata: Vec<u8>,
+    /// The status and data of each report, keyed by external ID and validator index.
+    /// Validators without an entry have not reported. Reading a validator index outside
+    /// `0..ask_count` panics, as the host rejects it.
+    pub reports: HashMap<(i64, i64), (i64, Vec<u8>)>,
+    /// The external ID, data source ID and calldata of every raw request asked.
+    pub asked: RefCell<Vec<(i64, i64, Vec<u8>)>>,
+    pub return_data: RefCell<Option<Vec<u8>>>,
+}
+
+#[cfg(feature = "std")]
+impl MemoryBackend {
+    pub fn new(span_size: i64) -> Self {
+        MemoryBackend { span_size, ..Default::default() }
+    }
+
+    // Panics on a validator index the host would reject with `BadValidatorIndexError`.
+    fn check_validator_index(&self, vid: i64) {
+        if vid < 0 || vid >= self.ask_count {
+            panic!("validator index {} out of range for ask count {}", vid, self.ask_count);
+        }
+    }
+
+    fn check_span(&self, data: Vec<u8>) -> Result<Vec<u8>, OeiError> {
+        if data.len() as i64 > self.span_size {
+            return Err(OeiError::SpanExceeded);
+        }
+        Ok(data)
+    }
+}
+
+#[cfg(feature = "std")]
+impl OeiBackend for MemoryBackend {
+    fn get_span_size(&self) -> i64 {
+        self.span_size
+    }
+
+    fn get_ask_count(&self) -> i64 {
+        self.ask_count
+    }
+
+    fn get_min_count(&self) -> i64 {
+        self.min_count
+    }
+
+    fn get_prepare_time(&self) -> i64 {
+        self.prepare_time
+    }
+
+    fn get_execute_time(&self) -> i64 {
+        self.execute_time
+    }
+
+    fn get_ans_count(&self) -> i64 {
+        self.ans_count
+    }
+
+    fn read_calldata(&self) -> Result<Vec<u8>, OeiError> {
+        self.check_span(self.calldata.clone())
+    }
+
+    fn set_return_data(&self, data: &[u8]) {
+        *self.return_data.borrow_mut() = Some(data.to_vec());
+    }
+
+    fn ask_external_data(&self, eid: i64, did: i64, calldata: &[u8]) {
+        self.asked.borrow_mut().push((eid, did, calldata.to_vec()));
+    }
+
+    fn get_external_data_status(&self, eid: i64, vid: i64) -> i64 {
+        self.check_validator_index(vid);
+        self.reports.get(&(eid, vid)).map_or(-1, |(status, _)| *status)
+    }
+
+    fn read_external_data(&self, eid: i64, vid: i64) -> Result<Vec<u8>, OeiError> {
+        self.check_validator_index(vid);
+        let data = self.reports.get(&(eid, vid)).map(|(_, data)| data.clone());
+        self.check_span(data.unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_span_test() {
+        let data = read_span(4, |ptr| {
+            unsafe { core::ptr::copy_nonoverlapping(b"beeb".as_ptr(), ptr as *mut u8, 4) };
+            4
+        });
+        assert_eq!(data, Ok(b"beeb".to_vec()));
+        assert_eq!(read_span(4, |_| 0), Ok(vec![]));
+        assert_eq!(read_span(4, |_| 5), Err(OeiError::SpanExceeded));
+        assert_eq!(read_span(4, |_| -1), Err(OeiError::SpanExceeded));
+    }
+
+    #[test]
+    fn memory_backend_test() {
+        let mut backend = MemoryBackend::new(4);
+        backend.ask_count = 3;
+        backend.calldata = b"beeb".to_vec();
+        backend.reports.insert((1, 0), (0, b"band".to_vec()));
+        backend.reports.insert((1, 1), (0, b"bandchain".to_vec()));
+        assert_eq!(backend.read_calldata(), Ok(b"beeb".to_vec()));
+        assert_eq!(backend.get_external_data_status(1, 0), 0);
+        assert_eq!(backend.get_external_data_status(1, 2), -1);
+        assert_eq!(backend.read_external_data(1, 0), Ok(b"band".to_vec()));
+        assert_eq!(backend.read_external_data(1, 1), Err(OeiError::SpanExceeded));
+        backend.ask_external_data(1, 2, b"c");
+        assert_eq!(*backend.asked.borrow(), vec![(1, 2, b"c".to_vec())]);
+    }
+
+    #[test]
+    #[should_panic(expected = "validator index 3 out of range")]
+    fn memory_backend_bad_validator_index_test() {
+        let mut backend = MemoryBackend::new(4);
+        backend.ask_count = 3;
+        backend.get_external_data_status(1, 3);
+    }
+}
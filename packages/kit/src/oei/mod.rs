@@ -1,87 +1,625 @@
+mod backend;
+#[cfg(target_arch = "wasm32")]
 mod raw;
+mod timestamp;
+
+#[cfg(feature = "std")]
+pub use backend::MemoryBackend;
+pub use backend::OeiBackend;
+#[cfg(target_arch = "wasm32")]
+pub use backend::WasmBackend;
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+pub use backend::{clear_backend, set_backend};
+pub use timestamp::Timestamp;
+
+use backend::with_backend;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use obi::OBIEncode;
+
+/// Errors returned by the `try_` variants of the oei functions. Host errors, such as
+/// calling an execution-only function during preparation, still abort the script.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OeiError {
+    /// The host reported more bytes than fit in a span.
+    SpanExceeded,
+    /// The validator's report is not a successful one.
+    ExternalDataStatus(ExternalDataStatus),
+    /// The reported data is not valid UTF-8.
+    InvalidUtf8,
+    /// The data could not be encoded.
+    EncodingFailed,
+    /// The data could not be decoded.
+    DecodingFailed,
+}
+
+impl fmt::Display for OeiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OeiError::SpanExceeded => write!(f, "data exceeds the span size"),
+            OeiError::ExternalDataStatus(status) => {
+                write!(f, "external data has status {:?}", status)
+            }
+            OeiError::InvalidUtf8 => write!(f, "external data is not valid UTF-8"),
+            OeiError::EncodingFailed => write!(f, "failed to encode data"),
+            OeiError::DecodingFailed => write!(f, "failed to decode data"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OeiError {}
+
+/// The status of a validator's report for an external data ID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExternalDataStatus {
+    /// The validator has not reported, so there is no data for it (raw status -1).
+    Pending,
+    /// The data source exited with status 0.
+    Success,
+    /// The data source exited with the given nonzero status.
+    Failure(i64),
+    /// The validator index is not one of the validators asked for this request.
+    NotExist,
+}
+
+impl ExternalDataStatus {
+    /// Interprets a raw status as returned by the host. Negative statuses other than -1
+    /// are outside the range a report can have, so they mean the report does not exist.
+    pub fn from_raw(status: i64) -> Self {
+        match status {
+            0 => ExternalDataStatus::Success,
+            -1 => ExternalDataStatus::Pending,
+            status if status < -1 => ExternalDataStatus::NotExist,
+            status => ExternalDataStatus::Failure(status),
+        }
+    }
+}
+
+/// Marks return data saved by `save_return_error`. Ordinary return data starting with
+/// these bytes would be read back as an error.
+pub const RETURN_ERROR_PREFIX: &[u8] = b"\0owasm-error\0";
+
+/// A structured failure returned from execution in place of the result.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReturnError {
+    pub code: u32,
+    pub message: String,
+}
+
+impl ReturnError {
+    pub fn new(code: u32, message: impl Into<String>) -> Self {
+        ReturnError { code, message: message.into() }
+    }
+
+    /// Encodes the error as `RETURN_ERROR_PREFIX`, the big-endian code, and the message.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(RETURN_ERROR_PREFIX.len() + 4 + self.message.len());
+        data.extend_from_slice(RETURN_ERROR_PREFIX);
+        data.extend_from_slice(&self.code.to_be_bytes());
+        data.extend_from_slice(self.message.as_bytes());
+        data
+    }
+
+    /// Decodes return data saved by `save_return_error`, or returns None if the data is
+    /// an ordinary result.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let data = data.strip_prefix(RETURN_ERROR_PREFIX)?;
+        if data.len() < 4 {
+            return None;
+        }
+        let (code, message) = data.split_at(4);
+        let code = u32::from_be_bytes([code[0], code[1], code[2], code[3]]);
+        Some(ReturnError { code, message: String::from_utf8(message.to_vec()).ok()? })
+    }
+}
+
+// Checks that `data` fits in a span of `span_size` bytes.
+#[cfg(any(feature = "std", feature = "abi"))]
+fn check_span(data: &[u8], span_size: i64) -> Result<(), OeiError> {
+    if data.len() as i64 > span_size {
+        return Err(OeiError::SpanExceeded);
+    }
+    Ok(())
+}
+
+/// Returns the maximum size in bytes of calldata, external data and return data. The
+/// host is only queried once, as the span size cannot change during a single run.
+pub fn get_span_size() -> i64 {
+    with_backend(|b| b.get_span_size())
+}
 
 /// Returns the number of validators to asked to report data from raw requests.
 pub fn get_ask_count() -> i64 {
-    unsafe { raw::get_ask_count() }
+    with_backend(|b| b.get_ask_count())
 }
 
 /// Returns the minimum number of data reports as specified by the oracle request.
 pub fn get_min_count() -> i64 {
-    unsafe { raw::get_min_count() }
+    with_backend(|b| b.get_min_count())
 }
 
 /// Return the prepare block time as specified by the oracle request.
 pub fn get_prepare_time() -> i64 {
-    unsafe { raw::get_prepare_time() }
+    with_backend(|b| b.get_prepare_time())
 }
 
 /// Return the execute block time during the call of execution phase. Must only
 /// be called during execution phase.
 pub fn get_execute_time() -> i64 {
-    unsafe { raw::get_execute_time() }
+    with_backend(|b| b.get_execute_time())
+}
+
+/// Same as `get_prepare_time`, as a `Timestamp`.
+pub fn get_prepare_timestamp() -> Timestamp {
+    Timestamp::from_seconds(get_prepare_time())
+}
+
+/// Same as `get_execute_time`, as a `Timestamp`. Must only be called during execution
+/// phase.
+pub fn get_execute_timestamp() -> Timestamp {
+    Timestamp::from_seconds(get_execute_time())
 }
 
 /// Returns the number of validators that report data to this oracle request. Must
 /// only be called during execution phase.
 pub fn get_ans_count() -> i64 {
-    unsafe { raw::get_ans_count() }
+    with_backend(|b| b.get_ans_count())
 }
 
 /// Returns the raw calldata as specified when the oracle request is submitted.
 pub fn get_calldata() -> Vec<u8> {
-    unsafe {
-        let mut data = Vec::with_capacity(raw::get_span_size() as usize);
-        let len = raw::read_calldata(data.as_mut_ptr() as i64);
-        data.set_len(len as usize);
-        data
-    }
+    try_get_calldata().expect("failed to read calldata")
+}
+
+/// Returns the raw calldata, or an error if the host reports more data than a span holds.
+pub fn try_get_calldata() -> Result<Vec<u8>, OeiError> {
+    with_backend(|b| b.read_calldata())
+}
+
+/// Returns the calldata decoded from JSON.
+#[cfg(feature = "json")]
+pub fn get_calldata_json<T: serde::de::DeserializeOwned>() -> Result<T, serde_json::Error> {
+    serde_json::from_slice(&get_calldata())
+}
+
+/// Saves the given value encoded as JSON as the result of the oracle execution. The same
+/// rules as `save_return_data` apply.
+#[cfg(feature = "json")]
+pub fn save_return_data_json<T: serde::Serialize>(value: &T) -> Result<(), serde_json::Error> {
+    save_return_data(&serde_json::to_vec(value)?);
+    Ok(())
+}
+
+/// Returns the calldata decoded from CBOR.
+#[cfg(feature = "cbor")]
+pub fn get_calldata_cbor<T: serde::de::DeserializeOwned>() -> Result<T, OeiError> {
+    ciborium::de::from_reader(get_calldata().as_slice()).map_err(|_| OeiError::DecodingFailed)
+}
+
+/// Saves the given value encoded as CBOR as the result of the oracle execution. The same
+/// rules as `save_return_data` apply.
+#[cfg(feature = "cbor")]
+pub fn save_return_data_cbor<T: serde::Serialize>(value: &T) -> Result<(), OeiError> {
+    let mut data = Vec::new();
+    ciborium::ser::into_writer(value, &mut data).map_err(|_| OeiError::EncodingFailed)?;
+    save_return_data(&data);
+    Ok(())
+}
+
+/// Returns the calldata decoded from Borsh.
+#[cfg(feature = "borsh")]
+pub fn get_calldata_borsh<T: borsh::BorshDeserialize>() -> Result<T, borsh::io::Error> {
+    borsh::from_slice(&get_calldata())
+}
+
+/// Saves the given value encoded as Borsh as the result of the oracle execution. The same
+/// rules as `save_return_data` apply.
+#[cfg(feature = "borsh")]
+pub fn save_return_data_borsh<T: borsh::BorshSerialize>(value: &T) -> Result<(), borsh::io::Error> {
+    save_return_data(&borsh::to_vec(value)?);
+    Ok(())
+}
+
+/// Returns the calldata decoded as a protobuf message.
+#[cfg(feature = "protobuf")]
+pub fn get_calldata_protobuf<T: prost::Message + Default>() -> Result<T, prost::DecodeError> {
+    T::decode(get_calldata().as_slice())
+}
+
+/// Saves the given protobuf message as the result of the oracle execution. The same rules
+/// as `save_return_data` apply.
+#[cfg(feature = "protobuf")]
+pub fn save_return_data_protobuf<T: prost::Message>(message: &T) {
+    save_return_data(&message.encode_to_vec())
+}
+
+/// Returns the calldata decoded from the Ethereum ABI, with a tuple read as a list of
+/// parameters. See `ext::abi`.
+#[cfg(feature = "abi")]
+pub fn get_calldata_abi<T: crate::ext::abi::AbiType>() -> Result<T, OeiError> {
+    crate::ext::abi::decode(&get_calldata()).ok_or(OeiError::DecodingFailed)
+}
+
+/// Saves the given value encoded with the Ethereum ABI as the result of the oracle
+/// execution. The same rules as `save_return_data` apply.
+#[cfg(feature = "abi")]
+pub fn save_return_data_abi<T: crate::ext::abi::AbiType>(value: T) {
+    save_return_data(&crate::ext::abi::encode(value))
 }
 
 /// Saves the given data as the result of the oracle execution. Must only be called
 /// during execution phase and must be called exactly once.
 pub fn save_return_data(data: &[u8]) {
-    unsafe { raw::set_return_data(data.as_ptr() as i64, data.len() as i64) }
+    with_backend(|b| b.set_return_data(data));
+    #[cfg(feature = "std")]
+    RETURN_DATA_SAVED.with(|saved| saved.set(true));
+}
+
+// Whether the return data has been saved since `set_panic_hook`, which the host allows
+// only once.
+#[cfg(feature = "std")]
+std::thread_local! {
+    static RETURN_DATA_SAVED: core::cell::Cell<bool> = const { core::cell::Cell::new(false) };
+}
+
+/// Saves a structured failure as the result of the oracle execution, so requesters can
+/// tell it apart from a script that traps. The same rules as `save_return_data` apply.
+pub fn save_return_error(code: u32, msg: &str) {
+    save_return_data(&ReturnError::new(code, msg).encode())
+}
+
+/// The `ReturnError` code saved by the panic hook set with `set_panic_hook`.
+pub const PANIC_ERROR_CODE: u32 = u32::MAX;
+
+/// Sets a panic hook that saves the panic message as a `ReturnError` with code
+/// `PANIC_ERROR_CODE` before the script traps, so the failure reason can be recovered
+/// wherever the host keeps the return data of a failed execution. The message is cut to
+/// fit in a span, and nothing is saved if the script has already saved its return data.
+/// Must only be called during execution phase, as return data cannot be saved during
+/// preparation.
+#[cfg(feature = "std")]
+pub fn set_panic_hook() {
+    RETURN_DATA_SAVED.with(|saved| saved.set(false));
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(std::boxed::Box::new(move |info| {
+        previous(info);
+        if !backend::has_backend() || RETURN_DATA_SAVED.with(|saved| saved.get()) {
+            return;
+        }
+        let max_len = get_span_size() - RETURN_ERROR_PREFIX.len() as i64 - 4;
+        if max_len < 0 {
+            return;
+        }
+        let mut message = info.to_string();
+        if message.len() as i64 > max_len {
+            let mut len = max_len as usize;
+            while !message.is_char_boundary(len) {
+                len -= 1;
+            }
+            message.truncate(len);
+        }
+        save_return_error(PANIC_ERROR_CODE, &message);
+    }));
 }
 
 /// Issues a new raw request to the host environement using the specified data
 /// source ID and calldata, and assigns it to the given external ID. Must only be
 /// called during preparation phase.
 pub fn ask_external_data(eid: i64, did: i64, calldata: &[u8]) {
-    unsafe { raw::ask_external_data(eid, did, calldata.as_ptr() as i64, calldata.len() as i64) }
+    with_backend(|b| b.ask_external_data(eid, did, calldata))
+}
+
+/// Same as `ask_external_data`, but OBI-encodes the given calldata and returns an error
+/// instead of issuing the request if it does not fit in a span.
+#[cfg(feature = "std")]
+pub fn ask_external_data_obi<T: OBIEncode>(
+    eid: i64,
+    did: i64,
+    calldata: &T,
+) -> Result<(), OeiError> {
+    let calldata = calldata.try_to_vec().map_err(|_| OeiError::EncodingFailed)?;
+    try_ask_external_data(eid, did, &calldata)
+}
+
+/// Same as `ask_external_data`, but ABI-encodes the given tokens and returns an error
+/// instead of issuing the request if they do not fit in a span.
+#[cfg(feature = "abi")]
+pub fn ask_external_data_abi(eid: i64, did: i64, tokens: &[ethabi::Token]) -> Result<(), OeiError> {
+    try_ask_external_data(eid, did, &ethabi::encode(tokens))
+}
+
+#[cfg(any(feature = "std", feature = "abi"))]
+fn try_ask_external_data(eid: i64, did: i64, calldata: &[u8]) -> Result<(), OeiError> {
+    check_span(calldata, get_span_size())?;
+    ask_external_data(eid, did, calldata);
+    Ok(())
+}
+
+/// Returns the status of the report from the given validator index for the given
+/// external data ID. Must only be called during execution phase.
+pub fn get_external_data_status(eid: i64, vid: i64) -> ExternalDataStatus {
+    // The host aborts the script on an out-of-range validator index, so check it here.
+    if vid < 0 || vid >= get_ask_count() {
+        return ExternalDataStatus::NotExist;
+    }
+    ExternalDataStatus::from_raw(get_external_data_status_raw(eid, vid))
+}
+
+/// Returns the status exactly as reported by the host, for scripts that need to tell
+/// apart statuses that `ExternalDataStatus` does not distinguish.
+pub fn get_external_data_status_raw(eid: i64, vid: i64) -> i64 {
+    with_backend(|b| b.get_external_data_status(eid, vid))
 }
 
 /// Returns the data reported from the given validator index for the given external
 /// data ID. Result is OK if the validator reports data with zero return status, and
 /// Err otherwise. Must only be called during execution phase.
 pub fn get_external_data(eid: i64, vid: i64) -> Result<String, i64> {
-    unsafe {
-        let status = raw::get_external_data_status(eid, vid);
-        if status != 0 {
-            Err(status)
-        } else {
-            let mut data = Vec::with_capacity(raw::get_span_size() as usize);
-            let len = raw::read_external_data(eid, vid, data.as_mut_ptr() as i64);
-            data.set_len(len as usize);
-            Ok(String::from_utf8_unchecked(data))
-        }
+    let status = get_external_data_status_raw(eid, vid);
+    if status != 0 {
+        return Err(status);
+    }
+    let data = read_reported_data(eid, vid).expect("external data exceeds the span size");
+    Ok(unsafe { String::from_utf8_unchecked(data) })
+}
+
+/// Returns the data reported from the given validator index for the given external
+/// data ID. Unlike `get_external_data`, the data is checked to be valid UTF-8. Must only
+/// be called during execution phase.
+pub fn try_get_external_data(eid: i64, vid: i64) -> Result<String, OeiError> {
+    String::from_utf8(read_external_data(eid, vid)?).map_err(|_| OeiError::InvalidUtf8)
+}
+
+/// Returns the raw bytes reported from the given validator index for the given external
+/// data ID, or None if the report is pending, failed or does not exist. Must only be
+/// called during execution phase.
+pub fn get_external_data_opt(eid: i64, vid: i64) -> Option<Vec<u8>> {
+    get_external_data_with_status(eid, vid).1
+}
+
+/// Returns the status of the report from the given validator index for the given
+/// external data ID, together with the reported data if the report is successful. Must
+/// only be called during execution phase.
+pub fn get_external_data_with_status(eid: i64, vid: i64) -> (ExternalDataStatus, Option<Vec<u8>>) {
+    if vid < 0 || vid >= get_ask_count() {
+        return (ExternalDataStatus::NotExist, None);
+    }
+    get_asked_external_data_with_status(eid, vid)
+}
+
+// Same as `get_external_data_with_status`, for validator indices already known to be
+// in range. A status outside the range a report can have reads as `NotExist`.
+pub(crate) fn get_asked_external_data_with_status(
+    eid: i64,
+    vid: i64,
+) -> (ExternalDataStatus, Option<Vec<u8>>) {
+    let status = ExternalDataStatus::from_raw(get_external_data_status_raw(eid, vid));
+    (status, read_successful_report(eid, vid, status))
+}
+
+fn read_successful_report(eid: i64, vid: i64, status: ExternalDataStatus) -> Option<Vec<u8>> {
+    match status {
+        ExternalDataStatus::Success => read_reported_data(eid, vid).ok(),
+        _ => None,
+    }
+}
+
+/// Returns the data reported by every asked validator for the given external data ID,
+/// indexed by validator index, with None for reports that are not successful. Must only
+/// be called during execution phase.
+pub fn get_all_external_data(eid: i64) -> Vec<Option<Vec<u8>>> {
+    (0..get_ask_count()).map(|vid| get_asked_external_data_with_status(eid, vid).1).collect()
+}
+
+fn read_external_data(eid: i64, vid: i64) -> Result<Vec<u8>, OeiError> {
+    match get_external_data_status(eid, vid) {
+        ExternalDataStatus::Success => {}
+        status => return Err(OeiError::ExternalDataStatus(status)),
     }
+    read_reported_data(eid, vid)
+}
+
+// Reads the data of a report already known to be successful.
+fn read_reported_data(eid: i64, vid: i64) -> Result<Vec<u8>, OeiError> {
+    with_backend(|b| b.read_external_data(eid, vid))
 }
 
 /// Return the verification result of ecvrf given a pubkey, a vrf proof, and the
-/// corresponding result.
+/// corresponding result. On failure, the error is the status reported by the host:
+/// 2 if a point is not on the curve, 4 if the proof is not 80 bytes, 5 if the pubkey is
+/// not 32 bytes, 11 or 12 if a point or scalar in the proof is not canonically encoded, or 10
+/// for any other failure.
 pub fn ecvrf_verify(y: &[u8], pi: &[u8], alpha: &[u8]) -> Result<bool, u32> {
-    unsafe {
-        match raw::ecvrf_verify(
-            y.as_ptr() as i64,
-            y.len() as i64,
-            pi.as_ptr() as i64,
-            pi.len() as i64,
-            alpha.as_ptr() as i64,
-            alpha.len() as i64,
-        ) {
-            0 => Ok(true),
-            1 => Ok(false),
-            x => Err(x),
-        }
+    match with_backend(|b| b.ecvrf_verify(y, pi, alpha)) {
+        0 => Ok(true),
+        1 => Ok(false),
+        x => Err(x),
+    }
+}
+
+/// Return the verification result of a BN254 Groth16 proof given an EIP-197 encoded
+/// verifying key, proof, and concatenated 32-byte public inputs. On failure, the error is
+/// the crypto error code reported by the host.
+pub fn groth16_verify(vk: &[u8], proof: &[u8], public_inputs: &[u8]) -> Result<bool, u32> {
+    match with_backend(|b| b.groth16_verify(vk, proof, public_inputs)) {
+        0 => Ok(true),
+        1 => Ok(false),
+        x => Err(x),
+    }
+}
+
+/// Return the circomlib-compatible Poseidon hash over BN254 of 1 to 12 big-endian field
+/// elements. On failure, the error is the crypto error code reported by the host.
+pub fn poseidon_hash(inputs: &[[u8; 32]]) -> Result<[u8; 32], u32> {
+    let mut output = [0u8; 32];
+    match with_backend(|b| b.poseidon_hash(inputs, &mut output)) {
+        0 => Ok(output),
+        x => Err(x),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::rc::Rc;
+
+    #[test]
+    fn memory_backend_oei_test() {
+        let mut backend = MemoryBackend::new(8);
+        backend.ask_count = 3;
+        backend.calldata = b"beeb".to_vec();
+        backend.reports.insert((1, 0), (0, b"band".to_vec()));
+        backend.reports.insert((1, 1), (126, vec![]));
+        let backend = Rc::new(backend);
+        set_backend(backend.clone());
+
+        assert_eq!(get_calldata(), b"beeb".to_vec());
+        assert_eq!(
+            get_external_data_with_status(1, 0),
+            (ExternalDataStatus::Success, Some(b"band".to_vec()))
+        );
+        assert_eq!(get_external_data_with_status(1, 1), (ExternalDataStatus::Failure(126), None));
+        assert_eq!(get_external_data_with_status(1, 2), (ExternalDataStatus::Pending, None));
+        assert_eq!(get_external_data_status(1, 3), ExternalDataStatus::NotExist);
+        assert_eq!(get_external_data_with_status(1, -1), (ExternalDataStatus::NotExist, None));
+        assert_eq!(get_external_data_with_status(1, 3), (ExternalDataStatus::NotExist, None));
+        assert_eq!(get_external_data_opt(1, 3), None);
+        assert_eq!(get_all_external_data(1), vec![Some(b"band".to_vec()), None, None]);
+        assert_eq!(try_get_external_data(1, 0), Ok(String::from("band")));
+
+        ask_external_data_obi(1, 2, &String::from("beeb")).unwrap();
+        assert_eq!(
+            ask_external_data_obi(1, 2, &String::from("bandchain")),
+            Err(OeiError::SpanExceeded)
+        );
+        assert_eq!(backend.asked.borrow().len(), 1);
+        save_return_data(b"beeb");
+        assert_eq!(*backend.return_data.borrow(), Some(b"beeb".to_vec()));
+        clear_backend();
+    }
+
+    #[test]
+    fn check_span_test() {
+        assert_eq!(check_span(b"", 0), Ok(()));
+        assert_eq!(check_span(b"beeb", 4), Ok(()));
+        assert_eq!(check_span(b"beeb", 3), Err(OeiError::SpanExceeded));
+    }
+
+    #[test]
+    fn return_error_test() {
+        let err = ReturnError::new(3, "no consensus");
+        let data = err.encode();
+        assert!(data.starts_with(RETURN_ERROR_PREFIX));
+        assert_eq!(ReturnError::decode(&data), Some(err));
+        assert_eq!(
+            ReturnError::decode(&ReturnError::new(0, "").encode()),
+            Some(ReturnError::new(0, ""))
+        );
+        assert_eq!(ReturnError::decode(b"beeb"), None);
+        assert_eq!(ReturnError::decode(&data[..RETURN_ERROR_PREFIX.len() + 3]), None);
+
+        let backend = Rc::new(MemoryBackend::new(64));
+        set_backend(backend.clone());
+        save_return_error(3, "no consensus");
+        assert_eq!(*backend.return_data.borrow(), Some(data));
+        clear_backend();
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_calldata_test() {
+        let mut backend = MemoryBackend::new(64);
+        backend.calldata = br#"{"symbols": ["BTC", "ETH"]}"#.to_vec();
+        let backend = Rc::new(backend);
+        set_backend(backend.clone());
+
+        let input: alloc::collections::BTreeMap<String, Vec<String>> = get_calldata_json().unwrap();
+        assert_eq!(input["symbols"], vec!["BTC", "ETH"]);
+        assert!(get_calldata_json::<Vec<u64>>().is_err());
+        save_return_data_json(&vec![64000, 3200]).unwrap();
+        assert_eq!(*backend.return_data.borrow(), Some(b"[64000,3200]".to_vec()));
+        clear_backend();
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn cbor_calldata_test() {
+        let mut backend = MemoryBackend::new(64);
+        // A map of "symbol" to "BTC".
+        backend.calldata = b"\xa1\x66symbol\x63BTC".to_vec();
+        let backend = Rc::new(backend);
+        set_backend(backend.clone());
+
+        let input: alloc::collections::BTreeMap<String, String> = get_calldata_cbor().unwrap();
+        assert_eq!(input["symbol"], "BTC");
+        assert_eq!(get_calldata_cbor::<u64>(), Err(OeiError::DecodingFailed));
+        save_return_data_cbor(&vec![64000u32]).unwrap();
+        assert_eq!(*backend.return_data.borrow(), Some(b"\x81\x19\xfa\x00".to_vec()));
+        clear_backend();
+    }
+
+    #[cfg(feature = "borsh")]
+    #[test]
+    fn borsh_calldata_test() {
+        let mut backend = MemoryBackend::new(64);
+        backend.calldata = b"\x03\x00\x00\x00BTC\x00\xfa\x00\x00\x00\x00\x00\x00".to_vec();
+        let backend = Rc::new(backend);
+        set_backend(backend.clone());
+
+        assert_eq!(get_calldata_borsh::<(String, u64)>().unwrap(), (String::from("BTC"), 64000));
+        assert!(get_calldata_borsh::<String>().is_err());
+        save_return_data_borsh(&vec![1u32]).unwrap();
+        assert_eq!(*backend.return_data.borrow(), Some(vec![1, 0, 0, 0, 1, 0, 0, 0]));
+        clear_backend();
+    }
+
+    #[cfg(feature = "protobuf")]
+    #[test]
+    fn protobuf_calldata_test() {
+        let mut backend = MemoryBackend::new(64);
+        // Field 1 of a string message, set to "BTC".
+        backend.calldata = b"\x0a\x03BTC".to_vec();
+        let backend = Rc::new(backend);
+        set_backend(backend.clone());
+
+        assert_eq!(get_calldata_protobuf::<String>(), Ok(String::from("BTC")));
+        assert!(get_calldata_protobuf::<u64>().is_err());
+        save_return_data_protobuf(&64000u64);
+        assert_eq!(*backend.return_data.borrow(), Some(b"\x08\x80\xf4\x03".to_vec()));
+        clear_backend();
+    }
+
+    #[cfg(feature = "abi")]
+    #[test]
+    fn abi_calldata_test() {
+        use crate::ext::abi::{self, Bytes};
+
+        let mut backend = MemoryBackend::new(256);
+        backend.calldata = abi::encode((String::from("BTC"), vec![1u64, 2]));
+        let backend = Rc::new(backend);
+        set_backend(backend.clone());
+
+        assert_eq!(get_calldata_abi::<(String, Vec<u64>)>(), Ok((String::from("BTC"), vec![1, 2])));
+        assert_eq!(get_calldata_abi::<(String, Vec<u64>, String)>(), Err(OeiError::DecodingFailed));
+        save_return_data_abi((64000u64, Bytes(vec![0xbe, 0xeb])));
+        assert_eq!(
+            abi::decode(backend.return_data.borrow().as_ref().unwrap()),
+            Some((64000u64, Bytes(vec![0xbe, 0xeb])))
+        );
+        clear_backend();
+    }
+
+    #[test]
+    fn external_data_status_from_raw_test() {
+        assert_eq!(ExternalDataStatus::from_raw(0), ExternalDataStatus::Success);
+        assert_eq!(ExternalDataStatus::from_raw(-1), ExternalDataStatus::Pending);
+        assert_eq!(ExternalDataStatus::from_raw(1), ExternalDataStatus::Failure(1));
+        assert_eq!(ExternalDataStatus::from_raw(126), ExternalDataStatus::Failure(126));
+        assert_eq!(ExternalDataStatus::from_raw(-2), ExternalDataStatus::NotExist);
     }
 }
@@ -18,4 +18,13 @@ extern "C" {
         alpha_offset: i64,
         alpha_len: i64,
     ) -> u32;
+    pub fn groth16_verify(
+        vk_offset: i64,
+        vk_len: i64,
+        proof_offset: i64,
+        proof_len: i64,
+        inputs_offset: i64,
+        inputs_len: i64,
+    ) -> u32;
+    pub fn poseidon_hash(inputs_offset: i64, inputs_len: i64, output_offset: i64) -> u32;
 }
@@ -0,0 +1,52 @@
+/// A block time in seconds since the Unix epoch, as reported by the host.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    pub fn from_seconds(seconds: i64) -> Self {
+        Timestamp(seconds)
+    }
+
+    pub fn seconds(self) -> i64 {
+        self.0
+    }
+
+    /// Returns the number of seconds from `earlier` to this timestamp, saturating at the
+    /// bounds of i64. The result is negative if `earlier` is after this timestamp.
+    pub fn saturating_diff(self, earlier: Timestamp) -> i64 {
+        self.0.saturating_sub(earlier.0)
+    }
+
+    /// Returns whether more than `max_age` seconds have passed from this timestamp to `now`.
+    pub fn is_stale(self, now: Timestamp, max_age: i64) -> bool {
+        now.saturating_diff(self) > max_age
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saturating_diff_test() {
+        let t = Timestamp::from_seconds(1_600_000_000);
+        assert_eq!(Timestamp::from_seconds(1_600_000_060).saturating_diff(t), 60);
+        assert_eq!(t.saturating_diff(Timestamp::from_seconds(1_600_000_060)), -60);
+        assert_eq!(
+            Timestamp::from_seconds(i64::MAX).saturating_diff(Timestamp::from_seconds(-1)),
+            i64::MAX
+        );
+        assert_eq!(
+            Timestamp::from_seconds(i64::MIN).saturating_diff(Timestamp::from_seconds(1)),
+            i64::MIN
+        );
+    }
+
+    #[test]
+    fn is_stale_test() {
+        let t = Timestamp::from_seconds(1_600_000_000);
+        assert!(!t.is_stale(Timestamp::from_seconds(1_600_000_060), 60));
+        assert!(t.is_stale(Timestamp::from_seconds(1_600_000_061), 60));
+        assert!(!t.is_stale(Timestamp::from_seconds(1_599_999_000), 60));
+    }
+}
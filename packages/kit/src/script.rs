@@ -0,0 +1,12 @@
+/// An oracle script whose preparation and execution share one input type. Export both
+/// phases with `oracle_script_entry_point!`.
+pub trait OracleScript {
+    type Input;
+    type Output;
+
+    /// Asks the external data needed for the given input.
+    fn prepare(input: Self::Input);
+
+    /// Aggregates the reports into the result for the given input.
+    fn execute(input: Self::Input) -> Self::Output;
+}
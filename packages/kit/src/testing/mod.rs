@@ -0,0 +1,189 @@
+//! # Native Oracle Script Testing
+//!
+//! `MockOei` simulates the host of an oracle script, so prepare and execute can run as
+//! ordinary Rust in `cargo test`, and `snapshot` compares its decoded output with
+//! snapshots stored in the script crate.
+use crate::oei::{self, MemoryBackend, OeiBackend, OeiError, ReturnError};
+use std::rc::Rc;
+
+pub mod snapshot;
+
+pub use owasm_scenario::Scenario;
+
+/// The span size of a new `MockOei`, large enough for any calldata or report in tests.
+pub const DEFAULT_SPAN_SIZE: i64 = 1 << 20;
+
+/// A simulated host that serves the calldata and reports of a `Scenario`, and records the raw
+/// requests asked and the return data saved by the script.
+pub struct MockOei {
+    backend: MemoryBackend,
+}
+
+impl Default for MockOei {
+    fn default() -> Self {
+        MockOei::new()
+    }
+}
+
+impl MockOei {
+    /// Returns a mock with no calldata and no validators. Use `MockOei::from` with a
+    /// `Scenario` to describe the request and reports.
+    pub fn new() -> Self {
+        MockOei::from(Scenario::new())
+    }
+
+    pub fn span_size(mut self, span_size: i64) -> Self {
+        self.backend.span_size = span_size;
+        self
+    }
+
+    /// Returns the external ID, data source ID and calldata of every raw request asked.
+    pub fn asked(&self) -> Vec<(i64, i64, Vec<u8>)> {
+        self.backend.asked.borrow().clone()
+    }
+
+    /// Returns the data saved with `oei::save_return_data`, if any.
+    pub fn return_data(&self) -> Option<Vec<u8>> {
+        self.backend.return_data.borrow().clone()
+    }
+
+    /// Returns the error saved with `oei::save_return_error`, if any.
+    pub fn return_error(&self) -> Option<ReturnError> {
+        ReturnError::decode(self.backend.return_data.borrow().as_ref()?)
+    }
+
+    /// Decodes the return data as OBI.
+    pub fn output<T: obi::OBIDecode>(&self) -> Option<T> {
+        T::try_from_slice(&self.return_data()?).ok()
+    }
+
+    /// Runs `f` with this mock as the oei backend of the current thread, and returns its
+    /// result along with the mock for inspection. The backend is cleared even if `f` panics.
+    pub fn run<R>(self, f: impl FnOnce() -> R) -> (R, Rc<MockOei>) {
+        struct Guard;
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                oei::clear_backend();
+            }
+        }
+
+        let mock = Rc::new(self);
+        oei::set_backend(mock.clone());
+        let _guard = Guard;
+        (f(), mock)
+    }
+}
+
+impl From<Scenario> for MockOei {
+    fn from(scenario: Scenario) -> Self {
+        let ans_count = scenario.ans_count();
+        MockOei {
+            backend: MemoryBackend {
+                span_size: DEFAULT_SPAN_SIZE,
+                ask_count: scenario.ask_count,
+                min_count: scenario.min_count,
+                prepare_time: scenario.prepare_time,
+                execute_time: scenario.execute_time,
+                ans_count,
+                calldata: scenario.calldata,
+                reports: scenario.reports.into_iter().collect(),
+                ..MemoryBackend::default()
+            },
+        }
+    }
+}
+
+impl OeiBackend for MockOei {
+    fn get_span_size(&self) -> i64 {
+        self.backend.get_span_size()
+    }
+
+    fn get_ask_count(&self) -> i64 {
+        self.backend.get_ask_count()
+    }
+
+    fn get_min_count(&self) -> i64 {
+        self.backend.get_min_count()
+    }
+
+    fn get_prepare_time(&self) -> i64 {
+        self.backend.get_prepare_time()
+    }
+
+    fn get_execute_time(&self) -> i64 {
+        self.backend.get_execute_time()
+    }
+
+    fn get_ans_count(&self) -> i64 {
+        self.backend.get_ans_count()
+    }
+
+    fn read_calldata(&self) -> Result<Vec<u8>, OeiError> {
+        self.backend.read_calldata()
+    }
+
+    fn set_return_data(&self, data: &[u8]) {
+        self.backend.set_return_data(data)
+    }
+
+    fn ask_external_data(&self, eid: i64, did: i64, calldata: &[u8]) {
+        self.backend.ask_external_data(eid, did, calldata)
+    }
+
+    fn get_external_data_status(&self, eid: i64, vid: i64) -> i64 {
+        self.backend.get_external_data_status(eid, vid)
+    }
+
+    fn read_external_data(&self, eid: i64, vid: i64) -> Result<Vec<u8>, OeiError> {
+        self.backend.read_external_data(eid, vid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ext;
+
+    #[test]
+    fn mock_oei_test() {
+        let scenario = Scenario::new().calldata(obi::OBIEncode::try_to_vec(&vec![3i64]).unwrap());
+        let (_, mock) = MockOei::from(scenario).run(|| {
+            let input: Vec<i64> = obi::OBIDecode::try_from_slice(&oei::get_calldata()).unwrap();
+            oei::ask_external_data(1, input[0], b"BTC");
+        });
+        assert_eq!(mock.asked(), vec![(1, 3, b"BTC".to_vec())]);
+
+        let scenario = Scenario::new().reports(1, vec!["10", "20", "30"]).report(1, 4, 1, "error");
+        let (median, mock) = MockOei::from(scenario).run(|| {
+            assert_eq!((oei::get_ask_count(), oei::get_ans_count()), (5, 4));
+            let median = ext::load_median_integer::<u64>(1);
+            oei::save_return_data(&obi::OBIEncode::try_to_vec(&median.unwrap()).unwrap());
+            median
+        });
+        assert_eq!(median, Some(20));
+        assert_eq!(mock.output::<u64>(), Some(20));
+        assert_eq!(mock.return_error(), None);
+
+        let (_, mock) = MockOei::new().run(|| oei::save_return_error(2, "no data"));
+        assert_eq!(mock.return_error(), Some(ReturnError::new(2, "no data")));
+    }
+
+    #[test]
+    fn scenario_test() {
+        let scenario = Scenario::new()
+            .ask_count(16)
+            .min_count(10)
+            .reports(1, vec!["100", "101", "102"])
+            .report(1, 3, 1, "timeout");
+        let (_, mock) = MockOei::from(scenario).run(|| {
+            assert_eq!(
+                (oei::get_ask_count(), oei::get_min_count(), oei::get_ans_count()),
+                (16, 10, 4)
+            );
+            assert_eq!(oei::get_external_data_status(1, 3), oei::ExternalDataStatus::Failure(1));
+            assert_eq!(oei::get_external_data_status(1, 4), oei::ExternalDataStatus::Pending);
+            oei::save_return_data(&[ext::load_median_integer::<u8>(1).unwrap()]);
+        });
+        assert_eq!(mock.return_data(), Some(vec![101]));
+    }
+}
@@ -0,0 +1,142 @@
+//! Snapshot testing of return data. The decoded output is rendered in a canonical,
+//! human-readable form and compared with `snapshots/<name>.snap` in the script crate, so
+//! changes in encoding or aggregation show up as a diff in review.
+use core::fmt;
+use std::fs;
+use std::path::Path;
+
+/// When set, `assert_snapshot` rewrites the stored snapshots with the current output.
+pub const UPDATE_ENV: &str = "OWASM_UPDATE_SNAPSHOTS";
+
+/// Renders OBI-encoded data as the pretty-printed debug form of the decoded value, or
+/// None if it does not decode.
+pub fn render_obi<T: obi::OBIDecode + fmt::Debug>(data: &[u8]) -> Option<String> {
+    Some(format!("{:#?}\n", T::try_from_slice(data).ok()?))
+}
+
+/// Renders ABI-encoded data as one `type: value` line per parameter, with integers in
+/// decimal and bytes and addresses in hex, or None if it does not decode.
+#[cfg(feature = "abi")]
+pub fn render_abi(types: &[ethabi::ParamType], data: &[u8]) -> Option<String> {
+    let tokens = ethabi::decode(types, data).ok()?;
+    Some(
+        types
+            .iter()
+            .zip(&tokens)
+            .map(|(ty, token)| format!("{}: {}\n", ty, render_token(token)))
+            .collect(),
+    )
+}
+
+#[cfg(feature = "abi")]
+fn render_token(token: &ethabi::Token) -> String {
+    use ethabi::Token;
+
+    let render_all =
+        |tokens: &[Token]| tokens.iter().map(render_token).collect::<Vec<_>>().join(", ");
+    match token {
+        Token::Address(address) => format!("{:#x}", address),
+        Token::Bytes(bytes) | Token::FixedBytes(bytes) => {
+            format!("0x{}", crate::ext::encode::hex_encode(bytes))
+        }
+        Token::Int(value) if value.bit(255) => {
+            format!("-{}", (!*value).overflowing_add(1.into()).0)
+        }
+        Token::Int(value) | Token::Uint(value) => value.to_string(),
+        Token::Bool(value) => value.to_string(),
+        Token::String(value) => format!("{:?}", value),
+        Token::Array(tokens) | Token::FixedArray(tokens) => format!("[{}]", render_all(tokens)),
+        Token::Tuple(tokens) => format!("({})", render_all(tokens)),
+    }
+}
+
+/// Compares the rendered output with the snapshot of the given name under `dir`. A
+/// missing snapshot fails the comparison, so a snapshot that was never committed cannot
+/// pass silently. Snapshots are written instead when `UPDATE_ENV` is set.
+pub fn assert_snapshot(dir: impl AsRef<Path>, name: &str, actual: &str) {
+    check_snapshot(dir.as_ref(), name, actual, std::env::var_os(UPDATE_ENV).is_some())
+}
+
+fn check_snapshot(dir: &Path, name: &str, actual: &str, update: bool) {
+    let path = dir.join("snapshots").join(format!("{}.snap", name));
+    if update {
+        fs::create_dir_all(path.parent().unwrap()).expect("failed to create snapshot directory");
+        fs::write(&path, actual).expect("failed to write snapshot");
+        return;
+    }
+    if !path.exists() {
+        panic!("snapshot {} does not exist; set {}=1 to write it", path.display(), UPDATE_ENV);
+    }
+    let expected = fs::read_to_string(&path).expect("failed to read snapshot");
+    if expected != actual {
+        panic!(
+            "output does not match snapshot {}; set {}=1 to update it\n--- snapshot\n{}--- output\n{}",
+            path.display(),
+            UPDATE_ENV,
+            expected,
+            actual
+        );
+    }
+}
+
+/// Same as `assert_snapshot`, with snapshots stored in the crate that calls the macro.
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $actual:expr $(,)?) => {
+        $crate::testing::snapshot::assert_snapshot(env!("CARGO_MANIFEST_DIR"), $name, &$actual)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use obi::OBIEncode;
+
+    #[test]
+    fn render_obi_test() {
+        let data = vec![1u64, 2].try_to_vec().unwrap();
+        assert_eq!(render_obi::<Vec<u64>>(&data), Some(String::from("[\n    1,\n    2,\n]\n")));
+        assert_eq!(render_obi::<Vec<u64>>(&[0, 0]), None);
+    }
+
+    #[cfg(feature = "abi")]
+    #[test]
+    fn render_abi_test() {
+        use ethabi::{ParamType, Token};
+
+        let tokens = [
+            Token::Uint(1000.into()),
+            Token::Int(!ethabi::Uint::from(41)),
+            Token::Array(vec![Token::Bool(true), Token::Bool(false)]),
+            Token::Tuple(vec![Token::Bytes(vec![0xbe, 0xeb]), Token::String(String::from("BTC"))]),
+        ];
+        let types = [
+            ParamType::Uint(64),
+            ParamType::Int(64),
+            ParamType::Array(Box::new(ParamType::Bool)),
+            ParamType::Tuple(vec![ParamType::Bytes, ParamType::String]),
+        ];
+        assert_eq!(
+            render_abi(&types, &ethabi::encode(&tokens)),
+            Some(String::from(
+                "uint64: 1000\nint64: -42\nbool[]: [true, false]\n(bytes,string): (0xbeeb, \"BTC\")\n"
+            ))
+        );
+        assert_eq!(render_abi(&types, &[0]), None);
+    }
+
+    #[test]
+    fn assert_snapshot_test() {
+        let dir = std::env::temp_dir().join(format!("owasm-snapshot-{}", std::process::id()));
+        let missing = std::panic::catch_unwind(|| check_snapshot(&dir, "output", "1\n", false));
+        assert!(missing.is_err());
+        assert!(!dir.join("snapshots/output.snap").exists());
+
+        check_snapshot(&dir, "output", "1\n", true);
+        assert_eq!(fs::read_to_string(dir.join("snapshots/output.snap")).unwrap(), "1\n");
+        check_snapshot(&dir, "output", "1\n", false);
+        let result = std::panic::catch_unwind(|| check_snapshot(&dir, "output", "2\n", false));
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(result.is_err());
+    }
+}
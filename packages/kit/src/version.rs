@@ -0,0 +1,50 @@
+//! Version metadata embedded in oracle scripts by `script_version!`.
+
+/// The version of owasm-kit the script is built with.
+pub const KIT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Returns the length of the metadata for the given script version.
+pub const fn metadata_len(script_version: &str) -> usize {
+    let mut len = 0;
+    let parts = parts(script_version);
+    let mut i = 0;
+    while i < parts.len() {
+        len += parts[i].len();
+        i += 1;
+    }
+    len
+}
+
+/// Returns the metadata for the given script version, `kit=<kit version>\nscript=<script
+/// version>`. `N` must be `metadata_len(script_version)`.
+pub const fn metadata<const N: usize>(script_version: &str) -> [u8; N] {
+    let mut out = [0u8; N];
+    let parts = parts(script_version);
+    let (mut i, mut pos) = (0, 0);
+    while i < parts.len() {
+        let mut j = 0;
+        while j < parts[i].len() {
+            out[pos] = parts[i][j];
+            pos += 1;
+            j += 1;
+        }
+        i += 1;
+    }
+    out
+}
+
+const fn parts(script_version: &str) -> [&[u8]; 4] {
+    [b"kit=", KIT_VERSION.as_bytes(), b"\nscript=", script_version.as_bytes()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metadata_test() {
+        const LEN: usize = metadata_len("1.2.0");
+        let metadata: [u8; LEN] = metadata("1.2.0");
+        assert_eq!(&metadata[..], format!("kit={}\nscript=1.2.0", KIT_VERSION).as_bytes());
+    }
+}
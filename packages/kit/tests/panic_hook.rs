@@ -0,0 +1,47 @@
+//! `oei::set_panic_hook` replaces the process-wide panic hook, so it is tested in its own
+//! binary where no other test can panic while the hook is set.
+#![cfg(feature = "std")]
+
+use owasm_kit::oei::{self, MemoryBackend, ReturnError, PANIC_ERROR_CODE};
+use std::rc::Rc;
+
+// Restores the default panic hook and clears the backend, even if the test fails.
+struct Restore;
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        drop(std::panic::take_hook());
+        oei::clear_backend();
+    }
+}
+
+#[test]
+fn set_panic_hook_test() {
+    let backend = Rc::new(MemoryBackend::new(256));
+    oei::set_backend(backend.clone());
+    let restore = Restore;
+    oei::set_panic_hook();
+    assert!(std::panic::catch_unwind(|| panic!("no reports for {}", "BTC")).is_err());
+
+    let err = ReturnError::decode(backend.return_data.borrow().as_ref().unwrap()).unwrap();
+    assert_eq!(err.code, PANIC_ERROR_CODE);
+    assert!(err.message.contains("no reports for BTC"), "{}", err.message);
+
+    // The message is cut to fit in a span.
+    let backend = Rc::new(MemoryBackend::new(24));
+    oei::set_backend(backend.clone());
+    oei::set_panic_hook();
+    assert!(std::panic::catch_unwind(|| panic!("{}", "é".repeat(64))).is_err());
+    let data = backend.return_data.borrow().clone().unwrap();
+    assert!(data.len() <= 24);
+    assert_eq!(ReturnError::decode(&data).unwrap().code, PANIC_ERROR_CODE);
+
+    // Return data saved by the script is kept, as the host allows saving it only once.
+    let backend = Rc::new(MemoryBackend::new(256));
+    oei::set_backend(backend.clone());
+    oei::set_panic_hook();
+    oei::save_return_data(b"beeb");
+    assert!(std::panic::catch_unwind(|| panic!("after saving")).is_err());
+    assert_eq!(*backend.return_data.borrow(), Some(b"beeb".to_vec()));
+    drop(restore);
+}
@@ -0,0 +1,106 @@
+//! # Owasm Scenario
+//!
+//! A `Scenario` describes the request an oracle script runs for: its calldata, the
+//! validator counts and times, and the report of each validator for each external ID.
+use std::collections::BTreeMap;
+
+/// The raw status of a successful report.
+pub const STATUS_SUCCESS: i64 = 0;
+
+/// The request and reports seen by an oracle script. Validators without an entry in
+/// `reports` have not reported.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Scenario {
+    pub calldata: Vec<u8>,
+    pub ask_count: i64,
+    pub min_count: i64,
+    pub prepare_time: i64,
+    pub execute_time: i64,
+    /// The raw status and data of each report, keyed by external ID and validator index.
+    pub reports: BTreeMap<(i64, i64), (i64, Vec<u8>)>,
+}
+
+impl Scenario {
+    pub fn new() -> Self {
+        Scenario::default()
+    }
+
+    pub fn calldata(mut self, calldata: impl Into<Vec<u8>>) -> Self {
+        self.calldata = calldata.into();
+        self
+    }
+
+    pub fn ask_count(mut self, ask_count: i64) -> Self {
+        self.ask_count = ask_count;
+        self
+    }
+
+    pub fn min_count(mut self, min_count: i64) -> Self {
+        self.min_count = min_count;
+        self
+    }
+
+    pub fn prepare_time(mut self, prepare_time: i64) -> Self {
+        self.prepare_time = prepare_time;
+        self
+    }
+
+    pub fn execute_time(mut self, execute_time: i64) -> Self {
+        self.execute_time = execute_time;
+        self
+    }
+
+    /// Adds the report of a validator with the given raw status, where nonzero is a failed
+    /// data source run. The ask count grows to include the validator if needed.
+    pub fn report(mut self, eid: i64, vid: i64, status: i64, data: impl Into<Vec<u8>>) -> Self {
+        self.reports.insert((eid, vid), (status, data.into()));
+        self.ask_count = self.ask_count.max(vid + 1);
+        self
+    }
+
+    /// Adds a successful report for each of the given data, from validator 0 onwards.
+    pub fn reports<D: Into<Vec<u8>>>(self, eid: i64, data: impl IntoIterator<Item = D>) -> Self {
+        data.into_iter().enumerate().fold(self, |scenario, (vid, data)| {
+            scenario.report(eid, vid as i64, STATUS_SUCCESS, data)
+        })
+    }
+
+    /// Removes the report of a validator, as if it never reported.
+    pub fn missing(mut self, eid: i64, vid: i64) -> Self {
+        self.reports.remove(&(eid, vid));
+        self
+    }
+
+    /// Returns the number of validators that reported for any external ID.
+    pub fn ans_count(&self) -> i64 {
+        let mut vids: Vec<i64> = self.reports.keys().map(|(_, vid)| *vid).collect();
+        vids.sort_unstable();
+        vids.dedup();
+        vids.len() as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scenario_test() {
+        let scenario = Scenario::new()
+            .calldata("BTC")
+            .ask_count(16)
+            .min_count(10)
+            .reports(1, vec!["100"; 12])
+            .report(1, 3, 1, "timeout")
+            .missing(1, 5)
+            .report(2, 13, STATUS_SUCCESS, "101");
+        assert_eq!(scenario.calldata, b"BTC".to_vec());
+        assert_eq!((scenario.ask_count, scenario.min_count), (16, 10));
+        assert_eq!(scenario.reports.get(&(1, 3)), Some(&(1, b"timeout".to_vec())));
+        assert_eq!(scenario.reports.get(&(1, 5)), None);
+        assert_eq!(scenario.reports.get(&(1, 11)), Some(&(0, b"100".to_vec())));
+        assert_eq!(scenario.ans_count(), 12);
+
+        assert_eq!(Scenario::new().report(1, 19, 0, "").ask_count, 20);
+    }
+}
@@ -1,5 +1,5 @@
 use std::{
-    borrow::BorrowMut,
+    borrow::{Borrow, BorrowMut},
     sync::{Arc, RwLock},
 };
 
@@ -9,23 +9,61 @@ use crate::error::Error;
 use clru::CLruCache;
 use wasmer::{Instance, Module, Store};
 
+/// A cached module along with its estimated heap footprint in bytes
+struct CachedModule {
+    module: Module,
+    size: usize,
+}
+
 /// An in-memory module cache
 pub struct InMemoryCache {
-    modules: CLruCache<Checksum, Module>,
+    modules: CLruCache<Checksum, CachedModule>,
+    size: usize,
 }
 
 impl InMemoryCache {
     pub fn new(max_entries: u32) -> Self {
-        InMemoryCache { modules: CLruCache::new(max_entries as usize) }
+        InMemoryCache { modules: CLruCache::new(max_entries as usize), size: 0 }
     }
 
     pub fn store(&mut self, checksum: &Checksum, module: Module) -> Option<Module> {
-        self.modules.put(*checksum, module)
+        if self.modules.capacity() == 0 {
+            return None;
+        }
+
+        // Evict the least recently used entry ourselves so that its size can be released.
+        if self.modules.peek(checksum).is_none() && self.modules.is_full() {
+            if let Some((_, evicted)) = self.modules.pop_back() {
+                self.size -= evicted.size;
+            }
+        }
+
+        let size = loupe::size_of_val(&module);
+        self.size += size;
+        self.modules.put(*checksum, CachedModule { module, size }).map(|replaced| {
+            self.size -= replaced.size;
+            replaced.module
+        })
     }
 
     /// Looks up a module in the cache and creates a new module
     pub fn load(&mut self, checksum: &Checksum) -> Option<Module> {
-        self.modules.get(checksum).cloned()
+        self.modules.get(checksum).map(|cached| cached.module.clone())
+    }
+
+    /// Returns the number of modules in the cache.
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    /// Returns true if the cache holds no modules.
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    /// Returns the estimated memory usage of all cached modules in bytes.
+    pub fn size(&self) -> usize {
+        self.size
     }
 }
 
@@ -34,15 +72,30 @@ pub struct CacheOptions {
     pub cache_size: u32,
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: u32,
+    pub misses: u32,
+    /// Number of modules currently held in the in-memory cache.
+    pub elements: usize,
+    /// Estimated memory usage of the in-memory cache in bytes, measured with `loupe`.
+    pub size: usize,
+}
+
 pub struct Cache {
     memory_cache: Arc<RwLock<InMemoryCache>>,
+    stats: Stats,
 }
 
 impl Cache {
     pub fn new(options: CacheOptions) -> Self {
         let CacheOptions { cache_size } = options;
 
-        Self { memory_cache: Arc::new(RwLock::new(InMemoryCache::new(cache_size))) }
+        #[allow(clippy::arc_with_non_send_sync)]
+        Self {
+            memory_cache: Arc::new(RwLock::new(InMemoryCache::new(cache_size))),
+            stats: Stats::default(),
+        }
     }
 
     fn with_in_memory_cache<C, R>(&mut self, callback: C) -> R
@@ -54,6 +107,13 @@ impl Cache {
         callback(in_memory_cache)
     }
 
+    /// Returns a snapshot of the cache statistics, including the current memory usage.
+    pub fn stats(&self) -> Stats {
+        let guard = self.memory_cache.as_ref().read().unwrap();
+        let in_memory_cache: &InMemoryCache = guard.borrow();
+        Stats { elements: in_memory_cache.len(), size: in_memory_cache.size(), ..self.stats }
+    }
+
     pub fn get_instance(
         &mut self,
         wasm: &[u8],
@@ -61,21 +121,30 @@ impl Cache {
         import_object: &wasmer::ImportObject,
     ) -> Result<(wasmer::Instance, bool), Error> {
         let checksum = Checksum::generate(wasm);
-        self.with_in_memory_cache(|in_memory_cache| {
+        let result = self.with_in_memory_cache(|in_memory_cache| {
             // lookup cache
             if let Some(module) = in_memory_cache.load(&checksum) {
-                return Ok((Instance::new(&module, &import_object).unwrap(), true));
+                return Ok((Instance::new(&module, import_object).unwrap(), true));
             }
 
             // recompile
-            let module = Module::new(store, &wasm).map_err(|_| Error::InstantiationError)?;
+            let module = Module::new(store, wasm).map_err(|_| Error::InstantiationError)?;
             let instance =
-                Instance::new(&module, &import_object).map_err(|_| Error::InstantiationError)?;
+                Instance::new(&module, import_object).map_err(|_| Error::InstantiationError)?;
 
             in_memory_cache.store(&checksum, module);
 
             Ok((instance, false))
-        })
+        });
+
+        if let Ok((_, is_hit)) = result {
+            if is_hit {
+                self.stats.hits = self.stats.hits.saturating_add(1);
+            } else {
+                self.stats.misses = self.stats.misses.saturating_add(1);
+            }
+        }
+        result
     }
 }
 
@@ -92,11 +161,7 @@ mod tests {
         let mut output_file = NamedTempFile::new().unwrap();
         input_file.write_all(wat.as_ref()).unwrap();
         Command::new("wat2wasm")
-            .args(&[
-                input_file.path().to_str().unwrap(),
-                "-o",
-                output_file.path().to_str().unwrap(),
-            ])
+            .args([input_file.path().to_str().unwrap(), "-o", output_file.path().to_str().unwrap()])
             .output()
             .unwrap();
         let mut wasm = Vec::new();
@@ -109,7 +174,7 @@ mod tests {
         let store = Store::new(&Universal::new(compiler).engine());
         let import_object = imports! {};
 
-        match cache.get_instance(&wasm, &store, &import_object) {
+        match cache.get_instance(wasm, &store, &import_object) {
             Ok((instance, is_hit)) => (instance, is_hit),
             Err(_) => panic!("Fail to get instance"),
         }
@@ -134,19 +199,19 @@ mod tests {
         );
 
         let (instance1, is_hit) = get_instance_without_err(&mut cache, &wasm);
-        assert_eq!(false, is_hit);
+        assert!(!is_hit);
 
         let (instance2, is_hit) = get_instance_without_err(&mut cache, &wasm);
-        assert_eq!(true, is_hit);
+        assert!(is_hit);
 
         let (_, is_hit) = get_instance_without_err(&mut cache, &wasm2);
-        assert_eq!(false, is_hit);
+        assert!(!is_hit);
 
         let (_, is_hit) = get_instance_without_err(&mut cache, &wasm);
-        assert_eq!(true, is_hit);
+        assert!(is_hit);
 
         let (_, is_hit) = get_instance_without_err(&mut cache, &wasm2);
-        assert_eq!(true, is_hit);
+        assert!(is_hit);
 
         let ser1 = match instance1.module().serialize() {
             Ok(r) => r,
@@ -190,38 +255,96 @@ mod tests {
 
         // miss [_ _] => [1 _]
         let (_, is_hit) = get_instance_without_err(&mut cache, &wasm1);
-        assert_eq!(false, is_hit);
+        assert!(!is_hit);
 
         // miss [1 _] => [2 1]
         let (_, is_hit) = get_instance_without_err(&mut cache, &wasm2);
-        assert_eq!(false, is_hit);
+        assert!(!is_hit);
 
         // miss [2 1] => [3 2]
         let (_, is_hit) = get_instance_without_err(&mut cache, &wasm3);
-        assert_eq!(false, is_hit);
+        assert!(!is_hit);
 
         // hit [3 2] => [2 3]
         let (_, is_hit) = get_instance_without_err(&mut cache, &wasm2);
-        assert_eq!(true, is_hit);
+        assert!(is_hit);
 
         // miss [2 3] => [1 2]
         let (_, is_hit) = get_instance_without_err(&mut cache, &wasm1);
-        assert_eq!(false, is_hit);
+        assert!(!is_hit);
 
         // hit [1 2] => [2 1]
         let (_, is_hit) = get_instance_without_err(&mut cache, &wasm2);
-        assert_eq!(true, is_hit);
+        assert!(is_hit);
 
         // miss [2 1] => [3 2]
         let (_, is_hit) = get_instance_without_err(&mut cache, &wasm3);
-        assert_eq!(false, is_hit);
+        assert!(!is_hit);
 
         cache = Cache::new(CacheOptions { cache_size: 0 });
 
         let (_, is_hit) = get_instance_without_err(&mut cache, &wasm1);
-        assert_eq!(false, is_hit);
+        assert!(!is_hit);
 
         let (_, is_hit) = get_instance_without_err(&mut cache, &wasm1);
-        assert_eq!(false, is_hit);
+        assert!(!is_hit);
+    }
+
+    #[test]
+    fn test_cache_stats() {
+        let mut cache = Cache::new(CacheOptions { cache_size: 2 });
+        let wasm1 = wat2wasm(
+            r#"(module
+                (func $execute (export "execute"))
+                (func $prepare (export "prepare"))
+                (func $foo (export "foo"))
+              )"#,
+        );
+
+        let wasm2 = wat2wasm(
+            r#"(module
+                (func $execute (export "execute"))
+                (func $prepare (export "prepare"))
+                (func $foo2 (export "foo2"))
+              )"#,
+        );
+
+        let wasm3 = wat2wasm(
+            r#"(module
+                (func $execute (export "execute"))
+                (func $prepare (export "prepare"))
+                (func $foo3 (export "foo3"))
+              )"#,
+        );
+
+        assert_eq!(cache.stats(), Stats::default());
+
+        get_instance_without_err(&mut cache, &wasm1);
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.elements), (0, 1, 1));
+        assert!(stats.size > 0);
+        let size_one = stats.size;
+
+        get_instance_without_err(&mut cache, &wasm1);
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.elements), (1, 1, 1));
+        assert_eq!(stats.size, size_one);
+
+        get_instance_without_err(&mut cache, &wasm2);
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.elements), (1, 2, 2));
+        assert!(stats.size > size_one);
+        let size_two = stats.size;
+
+        // wasm1 gets evicted, so its size is released
+        get_instance_without_err(&mut cache, &wasm3);
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.elements), (1, 3, 2));
+        assert!(stats.size < size_two + size_one);
+
+        cache = Cache::new(CacheOptions { cache_size: 0 });
+        get_instance_without_err(&mut cache, &wasm1);
+        let stats = cache.stats();
+        assert_eq!((stats.misses, stats.elements, stats.size), (1, 0, 0));
     }
 }
@@ -53,6 +53,7 @@ where
 }
 
 #[cfg(test)]
+#[allow(clippy::needless_borrows_for_generic_args, clippy::unnecessary_cast)]
 mod tests {
     use crate::cache::CacheOptions;
 
@@ -19,6 +19,7 @@ impl Checksum {
     }
 
     /// Creates a lowercase hex encoded copy of this checksum
+    #[allow(clippy::wrong_self_convention)]
     pub fn to_hex(&self) -> String {
         hex::encode(self.0)
     }
@@ -43,6 +44,7 @@ impl TryFrom<&[u8]> for Checksum {
     }
 }
 
+#[allow(clippy::from_over_into)]
 impl Into<Vec<u8>> for Checksum {
     fn into(self) -> Vec<u8> {
         // Rust 1.43+ also supports self.0.into()
@@ -51,6 +53,7 @@ impl Into<Vec<u8>> for Checksum {
 }
 
 #[cfg(test)]
+#[allow(clippy::useless_vec)]
 mod tests {
     use super::*;
 
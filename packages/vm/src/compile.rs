@@ -25,8 +25,38 @@ static SUPPORTED_IMPORTS: &[&str] = &[
     "env.get_external_data_status",
     "env.read_external_data",
     "env.ecvrf_verify",
+    "env.groth16_verify",
+    "env.poseidon_hash",
 ];
 
+// The custom section written by owasm-kit's `script_version!`.
+static VERSION_SECTION: &str = "owasm_version";
+
+/// The versions embedded in a script by owasm-kit's `script_version!`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct VersionMetadata {
+    pub kit: String,
+    pub script: String,
+}
+
+/// Reads the version metadata of the given Wasm code, so that incompatible scripts can be
+/// rejected at upload time. Returns None if the code has no well-formed metadata.
+pub fn read_version_metadata(code: &[u8]) -> Result<Option<VersionMetadata>, Error> {
+    let module: Module = deserialize_buffer(code).map_err(|_| Error::DeserializationError)?;
+    let payload = match module.custom_sections().find(|s| s.name() == VERSION_SECTION) {
+        Some(section) => section.payload(),
+        None => return Ok(None),
+    };
+    let metadata = std::str::from_utf8(payload).ok().and_then(|text| {
+        let (kit, script) = text.split_once('\n')?;
+        Some(VersionMetadata {
+            kit: kit.strip_prefix("kit=")?.to_string(),
+            script: script.strip_prefix("script=")?.to_string(),
+        })
+    });
+    Ok(metadata)
+}
+
 pub fn compile(code: &[u8]) -> Result<Vec<u8>, Error> {
     // Check that the given Wasm code is indeed a valid Wasm.
     wasmparser::validate(code).map_err(|_| Error::ValidationError)?;
@@ -91,7 +121,7 @@ fn inject_memory(module: Module) -> Result<Module, Error> {
         return Err(Error::BadMemorySectionError);
     }
 
-    if limits.maximum() != None {
+    if limits.maximum().is_some() {
         return Err(Error::BadMemorySectionError);
     }
 
@@ -112,8 +142,10 @@ fn inject_stack_height(module: Module) -> Result<Module, Error> {
 }
 
 #[cfg(test)]
+#[allow(clippy::needless_borrows_for_generic_args)]
 mod tests {
     use super::*;
+    use crate::checksum::Checksum;
 
     use assert_matches::assert_matches;
     use std::io::{Read, Write};
@@ -144,6 +176,26 @@ mod tests {
         }
     }
 
+    fn with_custom_section(wasm: &[u8], name: &str, payload: &[u8]) -> Vec<u8> {
+        let mut module = get_module_from_wasm(wasm);
+        module.set_custom_section(name, payload.to_vec());
+        serialize(module).unwrap()
+    }
+
+    #[test]
+    fn test_read_version_metadata() {
+        let wasm = wat2wasm(r#"(module (memory 1))"#);
+        assert_eq!(read_version_metadata(&wasm), Ok(None));
+        let versioned = with_custom_section(&wasm, "owasm_version", b"kit=0.3.1\nscript=1.2.0");
+        assert_eq!(
+            read_version_metadata(&versioned),
+            Ok(Some(VersionMetadata { kit: "0.3.1".into(), script: "1.2.0".into() }))
+        );
+        let malformed = with_custom_section(&wasm, "owasm_version", b"0.3.1");
+        assert_eq!(read_version_metadata(&malformed), Ok(None));
+        assert_eq!(read_version_metadata(b"beeb"), Err(Error::DeserializationError));
+    }
+
     #[test]
     fn test_inject_memory_ok() {
         let wasm = wat2wasm(r#"(module (memory 1))"#);
@@ -320,6 +372,74 @@ mod tests {
         assert_eq!(check_wasm_exports(&module), Ok(()));
     }
 
+    // Representative oracle scripts and the checksums of their compiled output. The injected
+    // memory limit and stack height metering are part of consensus, so any change to the
+    // compiled bytes must be deliberate. Sources live next to the binaries in testdata/golden.
+    // (name, wasm, input checksum, compiled checksum)
+    static GOLDEN_FIXTURES: &[(&str, &[u8], &str, &str)] = &[
+        (
+            "minimal",
+            include_bytes!("../testdata/golden/minimal.wasm"),
+            "5bbf3150d75937a12e47fb23c586a0b8b66447a8e6440665ca07f9bcd1341f04",
+            "dbb05319d3f3e4915e3f2d4df44b69b37ae2b289c6f1e88b5bc64190401fe5c1",
+        ),
+        (
+            "loop",
+            include_bytes!("../testdata/golden/loop.wasm"),
+            "9dcece73ce3ab4334b63af0d13cfdcf20524de7b412916f7cbe3ec344f7491b2",
+            "f7dea09e60d0869c30cf64a192c9a940be1afa89c4243245980d6a82eeec65c8",
+        ),
+        (
+            "oei_calls",
+            include_bytes!("../testdata/golden/oei_calls.wasm"),
+            "f78b2d2da6be982f8c238b06449c936c41c4ddc7cc336e4f09123d38cd5a6427",
+            "ea9feba6862e9f837d89ae4fbf95babc68d4c9e6afb30da41d6151b97593cf5a",
+        ),
+        (
+            "recursion",
+            include_bytes!("../testdata/golden/recursion.wasm"),
+            "ad9f06b143d540a39d9e20fc820c9368965343fb7f223968ea94e382c248bba9",
+            "ff5aaf192b2655d286349ac5c38b64ceb96ef0ebc71124f50b762fd7c46832da",
+        ),
+        (
+            "ecvrf",
+            include_bytes!("../testdata/golden/ecvrf.wasm"),
+            "c6899ff1b8b2bcb80d3b609e5d5b77c57198a91411dcb2f887ff28e48ffce65d",
+            "de41fab7f55f184cd72be7d20b3d1fcb563fd4ef27f31a5d5fdea561a59a479c",
+        ),
+        (
+            "rust_like",
+            include_bytes!("../testdata/golden/rust_like.wasm"),
+            "69294d1e6f9a1957ccf8a058352f76454bc4edfc599af405931839ad870b49a4",
+            "305e2531544694bbceaf4de4d32a0e2727fc0bcff63180d56529963c934ee509",
+        ),
+    ];
+
+    #[test]
+    fn test_compile_golden_fixtures() {
+        for (name, wasm, input_checksum, compiled_checksum) in GOLDEN_FIXTURES {
+            assert_eq!(
+                Checksum::generate(wasm).to_hex(),
+                *input_checksum,
+                "fixture {} has been modified",
+                name
+            );
+            let code = compile(wasm).unwrap();
+            assert_eq!(
+                Checksum::generate(&code).to_hex(),
+                *compiled_checksum,
+                "compiled output of fixture {} has changed",
+                name
+            );
+            assert_eq!(
+                compile(wasm).unwrap(),
+                code,
+                "compiling fixture {} is not deterministic",
+                name
+            );
+        }
+    }
+
     #[test]
     fn test_compile() {
         let wasm = wat2wasm(
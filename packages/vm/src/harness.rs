@@ -0,0 +1,380 @@
+//! Runs an oracle script through `compile` and `run` the way validators do, so a script
+//! crate can test its wasm build end to end:
+//!
+//! ```ignore
+//! let wasm = build_script(env!("CARGO_MANIFEST_DIR"), env!("CARGO_PKG_NAME")).unwrap();
+//! let mut harness = Harness::new(&wasm).unwrap();
+//! let scenario = Scenario::new().calldata(input.try_to_vec().unwrap()).report(1, 0, 0, "10");
+//! let result = harness.execute(ScriptQuerier::from(scenario)).unwrap();
+//! assert_eq!(result.output::<u64>(), Some(10));
+//! ```
+use crate::cache::{Cache, CacheOptions};
+use crate::compile::compile;
+use crate::error::Error;
+use crate::vm::Querier;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::rc::Rc;
+
+pub use owasm_scenario::Scenario;
+
+/// The external ID, data source ID and calldata of a raw request asked by a script.
+pub type RawRequest = (i64, i64, Vec<u8>);
+
+/// Builds the library of the given script crate for wasm32 in release mode and returns
+/// the wasm. The build uses its own target directory, so it can run from the crate's
+/// own tests.
+pub fn build_script(manifest_dir: impl AsRef<Path>, package: &str) -> io::Result<Vec<u8>> {
+    let manifest_dir = manifest_dir.as_ref();
+    let target_dir = std::env::var_os("CARGO_TARGET_DIR")
+        .map_or_else(|| manifest_dir.join("target"), PathBuf::from)
+        .join("owasm-harness");
+    let output = Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+        .args(["build", "--lib", "--release", "--target", "wasm32-unknown-unknown"])
+        .arg("--manifest-path")
+        .arg(manifest_dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(String::from_utf8_lossy(&output.stderr).into_owned()));
+    }
+    let wasm = format!("{}.wasm", package.replace('-', "_"));
+    std::fs::read(target_dir.join("wasm32-unknown-unknown").join("release").join(wasm))
+}
+
+/// A querier that serves the calldata and reports of a `Scenario`, and records the raw requests
+/// asked and the return data set by the script. Like the chain, it only allows asking
+/// for data during preparation and setting return data during execution.
+#[derive(Clone)]
+pub struct ScriptQuerier {
+    pub span_size: i64,
+    pub calldata: Vec<u8>,
+    pub ask_count: i64,
+    pub min_count: i64,
+    pub prepare_time: i64,
+    pub execute_time: i64,
+    pub ans_count: i64,
+    /// The status and data of each report, keyed by external ID and validator index.
+    /// Validators without an entry have not reported.
+    pub reports: HashMap<(i64, i64), (i64, Vec<u8>)>,
+    is_prepare: bool,
+    asked: Rc<RefCell<Vec<RawRequest>>>,
+    return_data: Rc<RefCell<Option<Vec<u8>>>>,
+}
+
+impl From<Scenario> for ScriptQuerier {
+    fn from(scenario: Scenario) -> Self {
+        ScriptQuerier {
+            span_size: 1 << 20,
+            ans_count: scenario.ans_count(),
+            calldata: scenario.calldata,
+            ask_count: scenario.ask_count,
+            min_count: scenario.min_count,
+            prepare_time: scenario.prepare_time,
+            execute_time: scenario.execute_time,
+            reports: scenario.reports.into_iter().collect(),
+            is_prepare: true,
+            asked: Rc::default(),
+            return_data: Rc::default(),
+        }
+    }
+}
+
+impl Querier for ScriptQuerier {
+    fn get_span_size(&self) -> i64 {
+        self.span_size
+    }
+
+    fn get_calldata(&self) -> Result<Vec<u8>, Error> {
+        Ok(self.calldata.clone())
+    }
+
+    fn set_return_data(&self, data: &[u8]) -> Result<(), Error> {
+        if self.is_prepare {
+            return Err(Error::WrongPeriodActionError);
+        }
+        let mut return_data = self.return_data.borrow_mut();
+        if return_data.is_some() {
+            return Err(Error::RepeatSetReturnDataError);
+        }
+        *return_data = Some(data.to_vec());
+        Ok(())
+    }
+
+    fn get_ask_count(&self) -> i64 {
+        self.ask_count
+    }
+
+    fn get_min_count(&self) -> i64 {
+        self.min_count
+    }
+
+    fn get_prepare_time(&self) -> i64 {
+        self.prepare_time
+    }
+
+    fn get_execute_time(&self) -> Result<i64, Error> {
+        if self.is_prepare {
+            return Err(Error::WrongPeriodActionError);
+        }
+        Ok(self.execute_time)
+    }
+
+    fn get_ans_count(&self) -> Result<i64, Error> {
+        if self.is_prepare {
+            return Err(Error::WrongPeriodActionError);
+        }
+        Ok(self.ans_count)
+    }
+
+    fn ask_external_data(&self, eid: i64, did: i64, data: &[u8]) -> Result<(), Error> {
+        if !self.is_prepare {
+            return Err(Error::WrongPeriodActionError);
+        }
+        let mut asked = self.asked.borrow_mut();
+        if asked.iter().any(|(asked_eid, _, _)| *asked_eid == eid) {
+            return Err(Error::DuplicateExternalIDError);
+        }
+        asked.push((eid, did, data.to_vec()));
+        Ok(())
+    }
+
+    fn get_external_data_status(&self, eid: i64, vid: i64) -> Result<i64, Error> {
+        if self.is_prepare {
+            return Err(Error::WrongPeriodActionError);
+        }
+        if vid < 0 || vid >= self.ask_count {
+            return Err(Error::BadValidatorIndexError);
+        }
+        Ok(self.reports.get(&(eid, vid)).map_or(-1, |(status, _)| *status))
+    }
+
+    fn get_external_data(&self, eid: i64, vid: i64) -> Result<Vec<u8>, Error> {
+        if self.is_prepare {
+            return Err(Error::WrongPeriodActionError);
+        }
+        if vid < 0 || vid >= self.ask_count {
+            return Err(Error::BadValidatorIndexError);
+        }
+        let (_, data) = self.reports.get(&(eid, vid)).ok_or(Error::UnavailableExternalDataError)?;
+        Ok(data.clone())
+    }
+}
+
+/// The outcome of running one phase of a script.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HarnessResult {
+    pub gas_used: u64,
+    pub asked: Vec<RawRequest>,
+    pub return_data: Option<Vec<u8>>,
+}
+
+impl HarnessResult {
+    /// Decodes the return data as OBI.
+    pub fn output<T: obi::OBIDecode>(&self) -> Option<T> {
+        T::try_from_slice(self.return_data.as_ref()?).ok()
+    }
+}
+
+/// The outcome of running both phases of a script for one request.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestResult {
+    pub prepare: HarnessResult,
+    pub execute: HarnessResult,
+}
+
+impl RequestResult {
+    /// Decodes the return data of execution as OBI.
+    pub fn output<T: obi::OBIDecode>(&self) -> Option<T> {
+        self.execute.output()
+    }
+}
+
+/// Gas used by a run, as checked by `assert_gas_under!`.
+pub trait GasReport {
+    fn gas_used(&self) -> u64;
+
+    /// Describes the gas used by each phase of the run.
+    fn gas_report(&self) -> String;
+}
+
+impl GasReport for HarnessResult {
+    fn gas_used(&self) -> u64 {
+        self.gas_used
+    }
+
+    fn gas_report(&self) -> String {
+        format!("{} gas", self.gas_used)
+    }
+}
+
+impl GasReport for RequestResult {
+    fn gas_used(&self) -> u64 {
+        self.prepare.gas_used.saturating_add(self.execute.gas_used)
+    }
+
+    fn gas_report(&self) -> String {
+        format!(
+            "prepare: {} gas, execute: {} gas, total: {} gas",
+            self.prepare.gas_used,
+            self.execute.gas_used,
+            self.gas_used()
+        )
+    }
+}
+
+/// Asserts that a harness result used at most the given gas, reporting the gas used by
+/// each phase otherwise.
+#[macro_export]
+macro_rules! assert_gas_under {
+    ($result:expr, $limit:expr $(,)?) => {{
+        let result = &$result;
+        let limit: u64 = $limit;
+        if $crate::harness::GasReport::gas_used(result) > limit {
+            panic!(
+                "gas limit of {} exceeded ({})",
+                limit,
+                $crate::harness::GasReport::gas_report(result)
+            );
+        }
+    }};
+}
+
+/// A compiled script with its own cache, ready to run either phase.
+pub struct Harness {
+    code: Vec<u8>,
+    cache: Cache,
+    gas_limit: u64,
+}
+
+impl Harness {
+    /// Compiles the given wasm, failing with the same error the chain would reject it with.
+    pub fn new(wasm: &[u8]) -> Result<Self, Error> {
+        let code = compile(wasm)?;
+        Ok(Harness { code, cache: Cache::new(CacheOptions { cache_size: 1 }), gas_limit: u64::MAX })
+    }
+
+    pub fn gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+
+    pub fn prepare(&mut self, querier: ScriptQuerier) -> Result<HarnessResult, Error> {
+        self.run(querier, true)
+    }
+
+    pub fn execute(&mut self, querier: ScriptQuerier) -> Result<HarnessResult, Error> {
+        self.run(querier, false)
+    }
+
+    /// Runs prepare and then execute with the same request and reports.
+    pub fn request(&mut self, querier: ScriptQuerier) -> Result<RequestResult, Error> {
+        let prepare = self.prepare(querier.clone())?;
+        let execute = self.execute(querier)?;
+        Ok(RequestResult { prepare, execute })
+    }
+
+    fn run(
+        &mut self,
+        mut querier: ScriptQuerier,
+        is_prepare: bool,
+    ) -> Result<HarnessResult, Error> {
+        querier.is_prepare = is_prepare;
+        querier.asked = Rc::default();
+        querier.return_data = Rc::default();
+        let (asked, return_data) = (querier.asked.clone(), querier.return_data.clone());
+        let gas_used =
+            crate::run(&mut self.cache, &self.code, self.gas_limit, is_prepare, querier)?;
+        let asked = asked.borrow().clone();
+        let return_data = return_data.borrow().clone();
+        Ok(HarnessResult { gas_used, asked, return_data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use tempfile::NamedTempFile;
+
+    fn wat2wasm(wat: impl AsRef<[u8]>) -> Vec<u8> {
+        let mut input_file = NamedTempFile::new().unwrap();
+        let mut output_file = NamedTempFile::new().unwrap();
+        input_file.write_all(wat.as_ref()).unwrap();
+        Command::new("wat2wasm")
+            .args([input_file.path().to_str().unwrap(), "-o", output_file.path().to_str().unwrap()])
+            .output()
+            .unwrap();
+        let mut wasm = Vec::new();
+        output_file.read_to_end(&mut wasm).unwrap();
+        wasm
+    }
+
+    #[test]
+    fn test_harness() {
+        let wasm = wat2wasm(
+            r#"(module
+                (type (func (param i64 i64 i64 i64) (result)))
+                (type (func (param i64 i64) (result)))
+                (type (func (param i64 i64 i64) (result i64)))
+                (import "env" "ask_external_data" (func (type 0)))
+                (import "env" "set_return_data" (func (type 1)))
+                (import "env" "read_external_data" (func (type 2)))
+                (func
+                    (i64.const 1)
+                    (i64.const 7)
+                    (i64.const 1048576)
+                    (i64.const 4)
+                    call 0)
+                (func (local $len i64)
+                    (local.set $len (call 2 (i64.const 1) (i64.const 1) (i64.const 1048576)))
+                    (call 1 (i64.const 1048576) (local.get $len)))
+                (memory (export "memory") 17)
+                (data (i32.const 1048576) "beeb")
+                (export "prepare" (func 3))
+                (export "execute" (func 4)))
+            "#,
+        );
+        let mut harness = Harness::new(&wasm).unwrap();
+        let querier = ScriptQuerier::from(Scenario::new().report(1, 1, 0, "band"));
+
+        let result = harness.prepare(querier.clone()).unwrap();
+        assert_eq!(result.asked, vec![(1, 7, b"beeb".to_vec())]);
+        assert_eq!(result.return_data, None);
+        assert!(result.gas_used > 0);
+
+        let result = harness.execute(querier.clone()).unwrap();
+        assert_eq!(result.return_data, Some(b"band".to_vec()));
+
+        let querier = ScriptQuerier::from(Scenario::new());
+        assert_eq!(harness.execute(querier).unwrap_err(), Error::BadValidatorIndexError);
+
+        let scenario = Scenario::new().ask_count(4).reports(1, vec!["a", "b", "c"]).missing(1, 1);
+        let result = harness.execute(ScriptQuerier::from(scenario.clone()));
+        assert_eq!(result.unwrap_err(), Error::UnavailableExternalDataError);
+        let result = harness.execute(ScriptQuerier::from(scenario.report(1, 1, 1, "fail")));
+        assert_eq!(result.unwrap().return_data, Some(b"fail".to_vec()));
+        assert_eq!(Harness::new(b"beeb").err(), Some(Error::ValidationError));
+
+        let querier = ScriptQuerier::from(Scenario::new().report(1, 1, 0, "band"));
+        let result = harness.request(querier).unwrap();
+        assert_eq!(result.prepare.asked, vec![(1, 7, b"beeb".to_vec())]);
+        assert_eq!(result.execute.return_data, Some(b"band".to_vec()));
+        assert_eq!(result.gas_used(), result.prepare.gas_used + result.execute.gas_used);
+        crate::assert_gas_under!(result, result.gas_used());
+        let report =
+            std::panic::catch_unwind(|| crate::assert_gas_under!(result, 100)).unwrap_err();
+        assert_eq!(
+            report.downcast_ref::<String>().unwrap(),
+            &format!("gas limit of 100 exceeded ({})", result.gas_report())
+        );
+        assert!(result
+            .gas_report()
+            .starts_with(&format!("prepare: {} gas", result.prepare.gas_used)));
+    }
+}
@@ -3,11 +3,62 @@ use crate::vm::{Environment, Querier};
 
 use wasmer::{imports, Function, ImportObject, Store};
 
-use owasm_crypto::ecvrf;
-use owasm_crypto::error::CryptoError;
+use owasm_crypto::error::{CryptoError, CryptoResult};
+use owasm_crypto::{ecvrf, groth16, poseidon};
 
 const IMPORTED_FUNCTION_GAS: u64 = 750_000_000;
 const ECVRF_VERIFY_GAS: u64 = 7_500_000_000_000;
+const GROTH16_VERIFY_GAS: u64 = 20_000_000_000_000;
+const GROTH16_INPUT_GAS: u64 = 250_000_000_000;
+const POSEIDON_INPUT_GAS: u64 = 25_000_000_000;
+
+/// Status codes returned to oracle scripts by `ecvrf_verify`. They are part of the script
+/// ABI, so existing values must never be renumbered. Failures keep the crypto error codes
+/// that scripts have always seen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+enum EcvrfStatus {
+    /// The proof is valid for the given public key and alpha.
+    Verified = 0,
+    /// The proof is well-formed but does not verify.
+    InvalidProof = 1,
+    /// The public key, a point in the proof, or the hash to the curve is not on the curve.
+    InvalidPointOnCurve = 2,
+    /// A point to decode is not 32 bytes.
+    InvalidHashFormat = 3,
+    /// The proof is not 80 bytes.
+    InvalidProofFormat = 4,
+    /// The public key is not 32 bytes.
+    InvalidPubkeyFormat = 5,
+    /// Any other failure.
+    Generic = 10,
+    /// A point in the proof is not canonically encoded.
+    NonCanonicalPoint = 11,
+    /// A scalar in the proof is not canonically encoded.
+    NonCanonicalScalar = 12,
+}
+
+impl From<CryptoResult<bool>> for EcvrfStatus {
+    fn from(result: CryptoResult<bool>) -> Self {
+        match result {
+            Ok(true) => EcvrfStatus::Verified,
+            Ok(false) => EcvrfStatus::InvalidProof,
+            Err(CryptoError::InvalidPointOnCurve { .. }) => EcvrfStatus::InvalidPointOnCurve,
+            Err(CryptoError::InvalidHashFormat { .. }) => EcvrfStatus::InvalidHashFormat,
+            Err(CryptoError::InvalidProofFormat { .. }) => EcvrfStatus::InvalidProofFormat,
+            Err(CryptoError::InvalidPubkeyFormat { .. }) => EcvrfStatus::InvalidPubkeyFormat,
+            Err(CryptoError::NonCanonicalPoint { .. }) => EcvrfStatus::NonCanonicalPoint,
+            Err(CryptoError::NonCanonicalScalar { .. }) => EcvrfStatus::NonCanonicalScalar,
+            // Secret key, signature and recovery errors are not produced by verification.
+            Err(
+                CryptoError::InvalidSecretKeyFormat { .. }
+                | CryptoError::InvalidSignatureFormat { .. }
+                | CryptoError::InvalidRecoveryParam { .. }
+                | CryptoError::GenericErr { .. },
+            ) => EcvrfStatus::Generic,
+        }
+    }
+}
 
 fn require_mem_range(max_range: usize, require_range: usize) -> Result<(), Error> {
     if max_range < require_range {
@@ -61,7 +112,7 @@ where
             .ok_or(Error::MemoryOutOfBoundError)?
             .set(*byte);
     }
-    Ok(safe_convert(data.len())?)
+    safe_convert(data.len())
 }
 
 fn calculate_read_memory_gas(len: i64) -> u64 {
@@ -256,17 +307,88 @@ where
         let pi: Vec<u8> = read_memory(env, pi_ptr, pi_len)?;
         let alpha: Vec<u8> = read_memory(env, alpha_ptr, alpha_len)?;
 
-        let result = ecvrf::ecvrf_verify(&y, &pi, &alpha);
-        Ok(result.map_or_else(
-            |err| match err {
-                CryptoError::InvalidPointOnCurve { .. }
-                | CryptoError::InvalidPubkeyFormat { .. }
-                | CryptoError::InvalidProofFormat { .. }
-                | CryptoError::InvalidHashFormat { .. }
-                | CryptoError::GenericErr { .. } => err.code(),
-            },
-            |valid| if valid { 0 } else { 1 },
-        ))
+        Ok(EcvrfStatus::from(ecvrf::ecvrf_verify(&y, &pi, &alpha)) as u32)
+    })
+}
+
+/// Verifies a BN254 Groth16 proof. Returns 0 if the proof is valid, 1 if it is well-formed
+/// but invalid, or the crypto error code if any of the inputs cannot be decoded.
+fn do_groth16_verify<Q>(
+    env: &Environment<Q>,
+    vk_ptr: i64,
+    vk_len: i64,
+    proof_ptr: i64,
+    proof_len: i64,
+    inputs_ptr: i64,
+    inputs_len: i64,
+) -> Result<u32, Error>
+where
+    Q: Querier + 'static,
+{
+    if vk_len < 0 || proof_len < 0 || inputs_len < 0 {
+        return Err(Error::DataLengthOutOfBound);
+    }
+    env.with_querier_from_context(|querier| {
+        let span_size = querier.get_span_size();
+
+        if vk_len > span_size || proof_len > span_size || inputs_len > span_size {
+            return Err(Error::SpanTooSmallError);
+        }
+        // consume gas relatively to the function running time (~20ms for the pairing check,
+        // plus a scalar multiplication per public input)
+        let input_count = (inputs_len as u64).div_ceil(groth16::INPUT_LEN as u64);
+        env.decrease_gas_left(
+            GROTH16_VERIFY_GAS.saturating_add(input_count.saturating_mul(GROTH16_INPUT_GAS)),
+        )?;
+        let vk: Vec<u8> = read_memory(env, vk_ptr, vk_len)?;
+        let proof: Vec<u8> = read_memory(env, proof_ptr, proof_len)?;
+        let inputs: Vec<u8> = read_memory(env, inputs_ptr, inputs_len)?;
+
+        Ok(match groth16::verify(&vk, &proof, &inputs) {
+            Ok(true) => 0,
+            Ok(false) => 1,
+            Err(err) => err.code(),
+        })
+    })
+}
+
+/// Hashes the concatenated 32-byte field elements at `inputs_ptr` and writes the 32-byte
+/// result to `output_ptr`. Returns 0 on success, or the crypto error code if the inputs are
+/// rejected, in which case nothing is written.
+fn do_poseidon_hash<Q>(
+    env: &Environment<Q>,
+    inputs_ptr: i64,
+    inputs_len: i64,
+    output_ptr: i64,
+) -> Result<u32, Error>
+where
+    Q: Querier + 'static,
+{
+    if inputs_len < 0 {
+        return Err(Error::DataLengthOutOfBound);
+    }
+    env.with_querier_from_context(|querier| {
+        let span_size = querier.get_span_size();
+
+        if inputs_len > span_size {
+            return Err(Error::SpanTooSmallError);
+        }
+        let input_count = (inputs_len as u64).div_ceil(poseidon::INPUT_LEN as u64);
+        env.decrease_gas_left(
+            IMPORTED_FUNCTION_GAS
+                .saturating_add(input_count.saturating_mul(POSEIDON_INPUT_GAS))
+                .saturating_add(calculate_write_memory_gas(poseidon::OUTPUT_LEN)),
+        )?;
+        let data: Vec<u8> = read_memory(env, inputs_ptr, inputs_len)?;
+        let inputs: Vec<&[u8]> = data.chunks(poseidon::INPUT_LEN).collect();
+
+        match poseidon::hash(&inputs) {
+            Ok(output) => {
+                write_memory(env, output_ptr, output.to_vec())?;
+                Ok(0)
+            }
+            Err(err) => Ok(err.code()),
+        }
     })
 }
 
@@ -289,11 +411,19 @@ where
             "get_external_data_status" => Function::new_native_with_env(store, owasm_env.clone(), do_get_external_data_status),
             "read_external_data" => Function::new_native_with_env(store, owasm_env.clone(), do_read_external_data),
             "ecvrf_verify" => Function::new_native_with_env(store, owasm_env.clone(), do_ecvrf_verify),
+            "groth16_verify" => Function::new_native_with_env(store, owasm_env.clone(), do_groth16_verify),
+            "poseidon_hash" => Function::new_native_with_env(store, owasm_env.clone(), do_poseidon_hash),
         },
     }
 }
 
 #[cfg(test)]
+#[allow(
+    clippy::assign_op_pattern,
+    clippy::needless_borrows_for_generic_args,
+    clippy::needless_return,
+    clippy::unnecessary_cast
+)]
 mod test {
     use super::*;
 
@@ -415,7 +545,7 @@ mod test {
         let querier = MockQuerier {};
         let owasm_env = Environment::new(querier);
         let store = make_store();
-        assert_eq!(create_import_object(&store, owasm_env.clone()).externs_vec().len(), 13);
+        assert_eq!(create_import_object(&store, owasm_env.clone()).externs_vec().len(), 15);
 
         assert_eq!(create_import_object(&store, owasm_env.clone()).externs_vec()[0].1, "gas");
         assert_eq!(
@@ -802,7 +932,10 @@ mod test {
         owasm_env.set_wasmer_instance(Some(instance_ptr));
         owasm_env.set_gas_left(gas_limit);
 
-        assert_eq!(Ok(5), do_ecvrf_verify(&owasm_env, 0, 0, 0, 0, 0, 0));
+        assert_eq!(
+            Ok(EcvrfStatus::InvalidPubkeyFormat as u32),
+            do_ecvrf_verify(&owasm_env, 0, 0, 0, 0, 0, 0)
+        );
         gas_limit = gas_limit - ECVRF_VERIFY_GAS;
         assert_eq!(gas_limit, owasm_env.get_gas_left());
 
@@ -887,4 +1020,113 @@ mod test {
         );
         assert_eq!(gas_limit, owasm_env.get_gas_left());
     }
+
+    #[test]
+    fn test_do_groth16_verify() {
+        let mut gas_limit = 1_000_000_000_000_000;
+        let (owasm_env, instance) = create_owasm_env();
+        let instance_ptr = NonNull::from(&instance);
+        owasm_env.set_wasmer_instance(Some(instance_ptr));
+        owasm_env.set_gas_left(gas_limit);
+
+        // An empty verifying key is rejected with the generic crypto error code.
+        assert_eq!(Ok(10), do_groth16_verify(&owasm_env, 0, 0, 0, 0, 0, 0));
+        gas_limit -= GROTH16_VERIFY_GAS;
+        assert_eq!(gas_limit, owasm_env.get_gas_left());
+
+        assert_eq!(Ok(10), do_groth16_verify(&owasm_env, 0, 0, 0, 0, 0, 64));
+        gas_limit -= GROTH16_VERIFY_GAS + 2 * GROTH16_INPUT_GAS;
+        assert_eq!(gas_limit, owasm_env.get_gas_left());
+
+        for ptr in [-1, i64::MAX, i64::MIN] {
+            assert_eq!(
+                Err(Error::MemoryOutOfBoundError),
+                do_groth16_verify(&owasm_env, 0, 0, ptr, 0, 0, 0),
+                "testing with ptr: {}",
+                ptr
+            );
+            gas_limit -= GROTH16_VERIFY_GAS;
+            assert_eq!(gas_limit, owasm_env.get_gas_left());
+        }
+
+        for len in [-1, i64::MIN] {
+            assert_eq!(
+                Err(Error::DataLengthOutOfBound),
+                do_groth16_verify(&owasm_env, 0, 0, 0, 0, 0, len),
+                "testing with len: {}",
+                len
+            );
+            assert_eq!(gas_limit, owasm_env.get_gas_left());
+        }
+
+        assert_eq!(
+            Err(Error::SpanTooSmallError),
+            do_groth16_verify(&owasm_env, 0, i64::MAX, 0, 0, 0, 0),
+        );
+        assert_eq!(gas_limit, owasm_env.get_gas_left());
+
+        owasm_env.set_gas_left(GROTH16_VERIFY_GAS - 1);
+        assert_eq!(Err(Error::OutOfGasError), do_groth16_verify(&owasm_env, 0, 0, 0, 0, 0, 0));
+    }
+
+    #[test]
+    fn test_do_poseidon_hash() {
+        let mut gas_limit = 100_000_000_000_000;
+        let (owasm_env, instance) = create_owasm_env();
+        let instance_ptr = NonNull::from(&instance);
+        owasm_env.set_wasmer_instance(Some(instance_ptr));
+        owasm_env.set_gas_left(gas_limit);
+        let gas = |inputs: u64| {
+            IMPORTED_FUNCTION_GAS
+                + inputs * POSEIDON_INPUT_GAS
+                + calculate_write_memory_gas(poseidon::OUTPUT_LEN)
+        };
+
+        let mut inputs = [0u8; 64];
+        inputs[31] = 1;
+        inputs[63] = 2;
+        write_memory(&owasm_env, 0, inputs.to_vec()).unwrap();
+        assert_eq!(Ok(0), do_poseidon_hash(&owasm_env, 0, 64, 100));
+        gas_limit -= gas(2);
+        assert_eq!(gas_limit, owasm_env.get_gas_left());
+        assert_eq!(
+            hex::encode(read_memory(&owasm_env, 100, 32).unwrap()),
+            "115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a"
+        );
+
+        // A partial element is rejected with the crypto error code and nothing is written.
+        assert_eq!(Ok(10), do_poseidon_hash(&owasm_env, 0, 33, 200));
+        gas_limit -= gas(2);
+        assert_eq!(gas_limit, owasm_env.get_gas_left());
+        assert_eq!(vec![0u8; 32], read_memory(&owasm_env, 200, 32).unwrap());
+
+        assert_eq!(Err(Error::MemoryOutOfBoundError), do_poseidon_hash(&owasm_env, -1, 32, 0));
+        gas_limit -= gas(1);
+        assert_eq!(gas_limit, owasm_env.get_gas_left());
+
+        assert_eq!(Err(Error::DataLengthOutOfBound), do_poseidon_hash(&owasm_env, 0, -1, 0));
+        assert_eq!(Err(Error::SpanTooSmallError), do_poseidon_hash(&owasm_env, 0, i64::MAX, 0));
+        assert_eq!(gas_limit, owasm_env.get_gas_left());
+    }
+
+    #[test]
+    fn test_ecvrf_status() {
+        let y = hex::decode("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a")
+            .unwrap();
+        let pi = hex::decode("7d9c633ffeee27349264cf5c667579fc583b4bda63ab71d001f89c10003ab46f25898f6bd7d4ed4c75f0282b0f7bb9d0e61b387b76db60b3cbf34bf09109ccb33fab742a8bddc0c8ba3caf5c0b75bb04").unwrap();
+        let status = |y: &[u8], pi: &[u8], alpha: &[u8]| {
+            EcvrfStatus::from(ecvrf::ecvrf_verify(y, pi, alpha)) as u32
+        };
+
+        assert_eq!(0, status(&y, &pi, &[]));
+        assert_eq!(1, status(&y, &pi, &[1]));
+        assert_eq!(5, status(&y[1..], &pi, &[]));
+        let mut off_curve = [0u8; 32];
+        off_curve[0] = 2;
+        assert_eq!(2, status(&off_curve, &pi, &[]));
+        // The proof is decoded before the public key.
+        assert_eq!(11, status(&off_curve, &[0xff; 80], &[]));
+        assert_eq!(4, status(&y, &pi[1..], &[]));
+        assert_eq!(11, status(&y, &[0xff; 80], &[]));
+    }
 }
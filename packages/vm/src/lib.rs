@@ -3,10 +3,12 @@ mod calls;
 mod checksum;
 mod compile;
 pub mod error;
+#[cfg(feature = "harness")]
+pub mod harness;
 mod imports;
 mod store;
 pub mod vm;
 
 pub use calls::run;
-pub use compile::compile;
+pub use compile::{compile, read_version_metadata, VersionMetadata};
 pub use error::Error;
@@ -0,0 +1,19 @@
+(module
+  (import "env" "ecvrf_verify"
+    (func $ecvrf_verify (param i64 i64 i64 i64 i64 i64) (result i32)))
+  (import "env" "set_return_data" (func $set_return_data (param i64 i64)))
+  (func $prepare)
+  (func $execute
+    (i32.store8 (i32.const 256)
+      (call $ecvrf_verify
+        (i64.const 0) (i64.const 32)
+        (i64.const 32) (i64.const 80)
+        (i64.const 112) (i64.const 3)))
+    (call $set_return_data (i64.const 256) (i64.const 1)))
+  (memory (export "memory") 1)
+  (data (i32.const 0)
+    "\d7\5a\98\01\82\b1\0a\b7\d5\4b\fe\d3\c9\64\07\3a"
+    "\0e\e1\72\f3\da\a6\23\25\af\02\1a\68\f7\07\51\1a")
+  (data (i32.const 112) "abc")
+  (export "prepare" (func $prepare))
+  (export "execute" (func $execute)))
@@ -0,0 +1,13 @@
+(module
+  (func $prepare
+    (local $idx i32)
+    (local.set $idx (i32.const 0))
+    (block
+      (loop
+        (local.set $idx (i32.add (local.get $idx) (i32.const 1)))
+        (br_if 0 (i32.lt_u (local.get $idx) (i32.const 100000))))))
+  (func $execute)
+  (memory 17)
+  (data (i32.const 1048576) "beeb")
+  (export "prepare" (func $prepare))
+  (export "execute" (func $execute)))
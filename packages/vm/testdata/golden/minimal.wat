@@ -0,0 +1,6 @@
+(module
+  (func $prepare)
+  (func $execute)
+  (memory (export "memory") 1)
+  (export "prepare" (func $prepare))
+  (export "execute" (func $execute)))
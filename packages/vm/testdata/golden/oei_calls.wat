@@ -0,0 +1,40 @@
+(module
+  (type $i64_ret (func (result i64)))
+  (import "env" "get_span_size" (func $get_span_size (type $i64_ret)))
+  (import "env" "read_calldata" (func $read_calldata (param i64) (result i64)))
+  (import "env" "set_return_data" (func $set_return_data (param i64 i64)))
+  (import "env" "get_ask_count" (func $get_ask_count (type $i64_ret)))
+  (import "env" "get_min_count" (func $get_min_count (type $i64_ret)))
+  (import "env" "get_prepare_time" (func $get_prepare_time (type $i64_ret)))
+  (import "env" "get_execute_time" (func $get_execute_time (type $i64_ret)))
+  (import "env" "get_ans_count" (func $get_ans_count (type $i64_ret)))
+  (import "env" "ask_external_data" (func $ask_external_data (param i64 i64 i64 i64)))
+  (import "env" "get_external_data_status" (func $get_external_data_status (param i64 i64) (result i64)))
+  (import "env" "read_external_data" (func $read_external_data (param i64 i64 i64) (result i64)))
+  (func $prepare
+    (local $len i64)
+    (local.set $len (call $read_calldata (i64.const 1024)))
+    (call $ask_external_data (i64.const 1) (i64.const 1) (i64.const 1024) (local.get $len))
+    (call $ask_external_data (i64.const 2) (i64.const 2) (i64.const 1024) (local.get $len))
+    (drop (call $get_span_size))
+    (drop (call $get_ask_count))
+    (drop (call $get_min_count))
+    (drop (call $get_prepare_time)))
+  (func $execute
+    (local $vid i64)
+    (local $len i64)
+    (drop (call $get_execute_time))
+    (drop (call $get_ans_count))
+    (block
+      (loop
+        (br_if 1 (i64.ge_s (local.get $vid) (call $get_ask_count)))
+        (if (i64.eqz (call $get_external_data_status (i64.const 1) (local.get $vid)))
+          (then
+            (local.set $len
+              (call $read_external_data (i64.const 1) (local.get $vid) (i64.const 2048)))))
+        (local.set $vid (i64.add (local.get $vid) (i64.const 1)))
+        (br 0)))
+    (call $set_return_data (i64.const 2048) (local.get $len)))
+  (memory (export "memory") 17)
+  (export "prepare" (func $prepare))
+  (export "execute" (func $execute)))
@@ -0,0 +1,25 @@
+(module
+  (type $unary (func (param i32) (result i32)))
+  (table 2 funcref)
+  (elem (i32.const 0) $fib $fact)
+  (func $fib (type $unary)
+    (if (result i32) (i32.lt_u (local.get 0) (i32.const 2))
+      (then (local.get 0))
+      (else
+        (i32.add
+          (call $fib (i32.sub (local.get 0) (i32.const 1)))
+          (call $fib (i32.sub (local.get 0) (i32.const 2)))))))
+  (func $fact (type $unary)
+    (if (result i32) (i32.eqz (local.get 0))
+      (then (i32.const 1))
+      (else
+        (i32.mul
+          (local.get 0)
+          (call_indirect (type $unary) (i32.sub (local.get 0) (i32.const 1)) (i32.const 1))))))
+  (func $prepare
+    (drop (call_indirect (type $unary) (i32.const 10) (i32.const 0))))
+  (func $execute
+    (drop (call_indirect (type $unary) (i32.const 10) (i32.const 1))))
+  (memory 1)
+  (export "prepare" (func $prepare))
+  (export "execute" (func $execute)))
@@ -0,0 +1,49 @@
+(module
+  (type $t0 (func))
+  (type $t1 (func (param i32 i32) (result i32)))
+  (type $t2 (func (param i64) (result i64)))
+  (import "env" "get_ask_count" (func $get_ask_count (result i64)))
+  (import "env" "read_calldata" (func $read_calldata (param i64) (result i64)))
+  (import "env" "set_return_data" (func $set_return_data (param i64 i64)))
+  (global $__stack_pointer (mut i32) (i32.const 1048576))
+  (global $__data_end i32 (i32.const 1048600))
+  (global $__heap_base i32 (i32.const 1048608))
+  (func $memcpy (type $t1) (param $dst i32) (param $src i32) (result i32)
+    (local $i i32)
+    (block
+      (loop
+        (br_if 1 (i32.ge_u (local.get $i) (i32.const 8)))
+        (i32.store8
+          (i32.add (local.get $dst) (local.get $i))
+          (i32.load8_u (i32.add (local.get $src) (local.get $i))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br 0)))
+    (local.get $dst))
+  (func $classify (type $t2) (param $x i64) (result i64)
+    (block $b2
+      (block $b1
+        (block $b0
+          (br_table $b0 $b1 $b2 (i32.wrap_i64 (local.get $x))))
+        (return (i64.const 10)))
+      (return (i64.const 20)))
+    (i64.const 30))
+  (func $prepare (type $t0)
+    (local $sp i32)
+    (local.set $sp (i32.sub (global.get $__stack_pointer) (i32.const 16)))
+    (global.set $__stack_pointer (local.get $sp))
+    (drop (call $read_calldata (i64.extend_i32_u (local.get $sp))))
+    (drop (call $classify (call $get_ask_count)))
+    (global.set $__stack_pointer (i32.add (local.get $sp) (i32.const 16))))
+  (func $execute (type $t0)
+    (local $sp i32)
+    (local.set $sp (i32.sub (global.get $__stack_pointer) (i32.const 16)))
+    (global.set $__stack_pointer (local.get $sp))
+    (drop (call $memcpy (local.get $sp) (i32.const 1048576)))
+    (call $set_return_data (i64.extend_i32_u (local.get $sp)) (i64.const 8))
+    (global.set $__stack_pointer (i32.add (local.get $sp) (i32.const 16))))
+  (memory (export "memory") 17)
+  (data (i32.const 1048576) "\00\00\00\00\00\00\00\2a")
+  (export "prepare" (func $prepare))
+  (export "execute" (func $execute))
+  (export "__data_end" (global $__data_end))
+  (export "__heap_base" (global $__heap_base)))